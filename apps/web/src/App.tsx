@@ -42,6 +42,7 @@ import { useBackendConnection } from '@/hooks/useBackendConnection';
 import { useDataSync } from '@/hooks/useDataSync';
 import { useObsEvents } from '@/hooks/useObsEvents';
 import { validateStreamConfig, displayValidationIssues } from '@/lib/streamValidation';
+import { buildIncomingUrl } from '@/types/profile';
 import { toast } from '@/hooks/useToast';
 import { useThemeStore } from '@/stores/themeStore';
 import { ChatOverlay } from '@/views/ChatOverlay';
@@ -432,7 +433,7 @@ function AppContent() {
 
       // Validation passed, start streaming
       // Build incoming URL from structured input
-      const incomingUrl = `rtmp://${current.input.bindAddress}:${current.input.port}/${current.input.application}`;
+      const incomingUrl = buildIncomingUrl(current.input);
       await startAllGroups(current.outputGroups, incomingUrl);
       toast.success(t('toast.streamStarted'));
     } catch (err) {
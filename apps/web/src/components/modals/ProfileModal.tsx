@@ -23,6 +23,9 @@ interface FormData {
   bindAddress: string;
   port: string;
   application: string;
+  // Ingest access control
+  ingestKey: string;
+  allowedNetworks: string; // comma-separated CIDR ranges
   // Password protection
   usePassword: boolean;
   password: string;
@@ -34,6 +37,8 @@ const defaultFormData: FormData = {
   bindAddress: '0.0.0.0',
   port: '1935',
   application: 'live',
+  ingestKey: '',
+  allowedNetworks: '',
   usePassword: false,
   password: '',
   confirmPassword: '',
@@ -59,6 +64,8 @@ export function ProfileModal({ open, onClose, mode, profile }: ProfileModalProps
           bindAddress: profile.input.bindAddress,
           port: String(profile.input.port),
           application: profile.input.application,
+          ingestKey: profile.input.ingestKey ?? '',
+          allowedNetworks: (profile.input.allowedNetworks ?? []).join(', '),
           usePassword: false,
           password: '',
           confirmPassword: '',
@@ -95,6 +102,13 @@ export function ProfileModal({ open, onClose, mode, profile }: ProfileModalProps
       newErrors.application = t('validation.applicationRequired');
     }
 
+    // Validate ingest key (it is sent as the RTMP stream name)
+    if (/[\s/?]/.test(formData.ingestKey.trim())) {
+      newErrors.ingestKey = tDynamic('validation.ingestKeyInvalid', {
+        defaultValue: 'Ingest key cannot contain spaces, "/" or "?"',
+      });
+    }
+
     // Validate password when protection is enabled
     if (formData.usePassword) {
       if (!formData.password) {
@@ -111,18 +125,30 @@ export function ProfileModal({ open, onClose, mode, profile }: ProfileModalProps
     return Object.keys(newErrors).length === 0;
   };
 
+  // Build the input from the form, keeping settings edited elsewhere (SRT options, slate)
+  const buildInput = (): RtmpInput => {
+    const existing = mode === 'edit' && profile ? profile.input : undefined;
+    return {
+      ...existing,
+      type: existing?.type ?? 'rtmp',
+      bindAddress: formData.bindAddress,
+      port: parseInt(formData.port),
+      application: formData.application,
+      ingestKey: formData.ingestKey.trim() || undefined,
+      allowedNetworks: formData.allowedNetworks
+        .split(',')
+        .map((network) => network.trim())
+        .filter(Boolean),
+    };
+  };
+
   // Validate port conflict with other profiles (Story 2.2)
   const validatePortConflict = async (): Promise<{
     conflictMessage?: string;
     errorMessage?: string;
   }> => {
     const profileId = mode === 'edit' && profile ? profile.id : '';
-    const input: RtmpInput = {
-      type: 'rtmp',
-      bindAddress: formData.bindAddress,
-      port: parseInt(formData.port),
-      application: formData.application,
-    };
+    const input = buildInput();
 
     try {
       await api.profile.validateInput(profileId, input);
@@ -138,12 +164,7 @@ export function ProfileModal({ open, onClose, mode, profile }: ProfileModalProps
 
   const persistProfile = async () => {
     // Build RTMP input object
-    const input: RtmpInput = {
-      type: 'rtmp',
-      bindAddress: formData.bindAddress,
-      port: parseInt(formData.port),
-      application: formData.application,
-    };
+    const input = buildInput();
 
     if (mode === 'create') {
       // Create new profile with default passthrough group
@@ -284,9 +305,40 @@ export function ProfileModal({ open, onClose, mode, profile }: ProfileModalProps
               helper={t('modals.applicationHelper')}
             />
           </div>
+          <div
+            style={{
+              display: 'grid',
+              gridTemplateColumns: '1fr 1fr',
+              gap: '12px',
+              marginTop: '12px',
+            }}
+          >
+            <Input
+              label={tDynamic('modals.ingestKey', { defaultValue: 'Ingest Key' })}
+              type="password"
+              placeholder={tDynamic('modals.ingestKeyPlaceholder', { defaultValue: 'Optional' })}
+              value={formData.ingestKey}
+              onChange={handleChange('ingestKey')}
+              error={errors.ingestKey}
+              helper={tDynamic('modals.ingestKeyHelper', {
+                defaultValue: 'Publishers must use this as their stream key',
+              })}
+            />
+            <Input
+              label={tDynamic('modals.allowedNetworks', { defaultValue: 'Allowed Networks' })}
+              placeholder="192.168.1.0/24, 10.0.0.5"
+              value={formData.allowedNetworks}
+              onChange={handleChange('allowedNetworks')}
+              error={errors.allowedNetworks}
+              helper={tDynamic('modals.allowedNetworksHelper', {
+                defaultValue: 'Leave empty to accept publishers from any address',
+              })}
+            />
+          </div>
           <div style={{ marginTop: '8px', fontSize: '0.75rem', color: 'var(--text-tertiary)' }}>
             {t('modals.rtmpUrlPreview')}: rtmp://{formData.bindAddress}:{formData.port}/
             {formData.application}
+            {formData.ingestKey ? '/••••••' : ''}
           </div>
           <div style={{
             marginTop: '8px',
@@ -6,7 +6,8 @@ import { Select, SelectOption } from '@/components/ui/Select';
 import { Button } from '@/components/ui/Button';
 import { useProfileStore } from '@/stores/profileStore';
 import type { StreamTarget, Platform, OutputGroup } from '@/types/profile';
-import { PLATFORMS as platformConfig } from '@/types/profile';
+import { PLATFORMS } from '@/types/profile';
+import { getPlatformConfig, usePlatformStore } from '@/stores/platformStore';
 
 export interface TargetModalProps {
   open: boolean;
@@ -16,8 +17,6 @@ export interface TargetModalProps {
   target?: StreamTarget;
 }
 
-// Platform values - dynamically loaded from JSON
-const PLATFORM_VALUES: Platform[] = Object.keys(platformConfig) as Platform[];
 const DEFAULT_PLATFORM: Platform = 'YouTube - RTMPS';
 
 interface FormData {
@@ -30,12 +29,14 @@ interface FormData {
 const defaultFormData: FormData = {
   service: DEFAULT_PLATFORM,
   name: '',
-  url: platformConfig[DEFAULT_PLATFORM].defaultServer,
+  url: PLATFORMS[DEFAULT_PLATFORM].defaultServer,
   streamKey: '',
 };
 
 export function TargetModal({ open, onClose, mode, groupId, target }: TargetModalProps) {
   const { t } = useTranslation();
+  // Platform values - loaded from the backend registry at runtime
+  const platforms = usePlatformStore((state) => state.platforms);
   const { current, addStreamTarget, updateStreamTarget, moveStreamTarget } = useProfileStore();
   const [formData, setFormData] = useState<FormData>(defaultFormData);
   const [errors, setErrors] = useState<Partial<FormData>>({});
@@ -74,9 +75,9 @@ export function TargetModal({ open, onClose, mode, groupId, target }: TargetModa
       ...prev,
       service: newService,
       // Only update URL if in create mode or URL hasn't been modified
-      url: mode === 'create' ? platformConfig[newService].defaultServer : prev.url,
+      url: mode === 'create' ? getPlatformConfig(platforms, newService).defaultServer : prev.url,
       // Update name suggestion if empty
-      name: prev.name || platformConfig[newService].displayName,
+      name: prev.name || getPlatformConfig(platforms, newService).displayName,
     }));
   };
 
@@ -153,10 +154,10 @@ export function TargetModal({ open, onClose, mode, groupId, target }: TargetModa
 
   const title = mode === 'create' ? t('modals.addStreamTarget') : t('modals.editStreamTarget');
 
-  // Create platform options using displayName from PLATFORMS
-  const platformOptions: SelectOption[] = PLATFORM_VALUES.map((value) => ({
+  // Create platform options using displayName from the registry
+  const platformOptions: SelectOption[] = Object.keys(platforms).map((value) => ({
     value,
-    label: platformConfig[value].displayName,
+    label: platforms[value].displayName,
   }));
 
   // Create output group options
@@ -221,7 +222,7 @@ export function TargetModal({ open, onClose, mode, groupId, target }: TargetModa
           value={formData.url}
           onChange={handleChange('url')}
           error={errors.url}
-          helper={`${t('modals.default')}: ${platformConfig[formData.service].defaultServer}`}
+          helper={`${t('modals.default')}: ${getPlatformConfig(platforms, formData.service).defaultServer}`}
         />
 
         <div style={{ position: 'relative' }}>
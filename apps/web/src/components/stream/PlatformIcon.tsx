@@ -1,5 +1,6 @@
 import { cn } from '@/lib/cn';
-import { type Platform, PLATFORMS } from '@/types/profile';
+import type { Platform } from '@/types/profile';
+import { getPlatformConfig, usePlatformStore } from '@/stores/platformStore';
 
 export interface PlatformIconProps {
   platform: Platform;
@@ -14,9 +15,10 @@ const sizeStyles = {
 };
 
 export function PlatformIcon({ platform, size = 'md', className }: PlatformIconProps) {
-  const platformConfig = PLATFORMS[platform];
+  const platforms = usePlatformStore((state) => state.platforms);
+  const platformConfig = getPlatformConfig(platforms, platform);
 
-  // Use the color from PLATFORMS constant
+  // Use the color from the platform registry
   const bgStyle = { backgroundColor: platformConfig.color };
 
   return (
@@ -1,6 +1,7 @@
 import { useEffect, useRef } from 'react';
 import { useProfileStore } from '@/stores/profileStore';
 import { useStreamStore } from '@/stores/streamStore';
+import { usePlatformStore } from '@/stores/platformStore';
 import { api } from '@/lib/backend';
 
 /**
@@ -18,6 +19,9 @@ export function useInitialize() {
     if (!initialized.current) {
       initialized.current = true;
 
+      // Platforms can change at runtime, so take them from the backend registry
+      usePlatformStore.getState().loadPlatforms();
+
       // Load profiles and sync stream state in parallel
       Promise.all([loadProfiles(), syncWithBackend()])
         .then(async () => {
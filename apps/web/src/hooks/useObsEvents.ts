@@ -4,6 +4,7 @@ import { useObsStore } from '@/stores/obsStore';
 import { useStreamStore } from '@/stores/streamStore';
 import { useProfileStore } from '@/stores/profileStore';
 import type { ObsConnectionStatus, ObsStreamStatus, ObsIntegrationDirection } from '@/types/api';
+import { buildIncomingUrl } from '@/types/profile';
 
 interface ObsStatusEvent {
   status: 'connecting' | 'connected' | 'disconnected' | 'error';
@@ -274,8 +275,7 @@ export function useObsEvents() {
           }
 
           // Build incoming URL from structured input
-          const { bindAddress, port, application } = currentProfile.input;
-          const incomingUrl = `rtmp://${bindAddress}:${port}/${application}`;
+          const incomingUrl = buildIncomingUrl(currentProfile.input);
 
           // Add delay before starting SpiritStream to let OBS stabilize
           setTimeout(() => {
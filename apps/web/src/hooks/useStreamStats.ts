@@ -2,9 +2,12 @@ import { useEffect, useRef, useCallback } from 'react';
 import { events, api } from '@/lib/backend';
 import { useStreamStore } from '@/stores/streamStore';
 import { useSettingsStore } from '@/stores/settingsStore';
+import { useProfileStore } from '@/stores/profileStore';
 import { toast } from '@/hooks/useToast';
 import { showSystemNotification } from '@/lib/notification';
 import { useTranslation } from 'react-i18next';
+import type { ScheduleEvent } from '@/types/schedule';
+import type { FFmpegErrorCode, IngestStats, PlatformWarning } from '@/types/stream';
 
 /**
  * Stream statistics from FFmpeg
@@ -19,6 +22,22 @@ export interface StreamStats {
   time: number;
   droppedFrames: number;
   dupFrames: number;
+  /** Per-rendition figures, present when the group encodes renditions */
+  renditions?: RenditionStats[];
+}
+
+/**
+ * Stats for one video rendition of an output group
+ */
+export interface RenditionStats {
+  /** Null for the group's main video settings */
+  renditionId: string | null;
+  name: string;
+  width: number;
+  height: number;
+  fps: number;
+  /** Measured video bitrate in kbps */
+  bitrate: number;
 }
 
 /**
@@ -27,7 +46,13 @@ export interface StreamStats {
 export interface StreamError {
   groupId: string;
   error: string;
+  code: FFmpegErrorCode;
+  /** FFmpeg log line the code was recognized from */
+  detail?: string | null;
+  /** False when retrying unchanged would fail the same way (e.g. a rejected stream key) */
   canRetry: boolean;
+  /** Backend supervisor will reconnect on its own */
+  autoReconnect?: boolean;
   suggestion?: string;
 }
 
@@ -41,16 +66,52 @@ export interface StreamReconnecting {
   delaySecs: number;
 }
 
+/**
+ * Stream reconnected event from backend
+ */
+export interface StreamReconnected {
+  groupId: string;
+  pid: number;
+  attempt: number;
+}
+
+/**
+ * Backend stopped reconnecting a group after exhausting its retries
+ */
+export interface StreamGaveUp {
+  groupId: string;
+  attempts: number;
+  error: string;
+  code: FFmpegErrorCode;
+}
+
+/**
+ * Backup slate went on or off air while the ingest was disconnected
+ */
+export interface BackupSlate {
+  active: boolean;
+  error?: string | null;
+}
+
+/**
+ * Publisher refused by the ingest key or network allowlist
+ */
+export interface IngestRejected {
+  clientAddr: string;
+  reason: string;
+}
+
 /**
  * Hook to listen to real-time stream statistics from the Rust backend
  * Also handles automatic retry on stream errors
  */
 export function useStreamStats() {
   const { t } = useTranslation();
-  const { updateStats, setStreamEnded, setStreamError } = useStreamStore();
+  const { updateStats, setStreamEnded, setStreamError, setActiveGroup } = useStreamStore();
 
   // Track groups currently being retried to prevent duplicate retry attempts
   const retryingGroups = useRef<Set<string>>(new Set());
+  const reportedIngestWarnings = useRef<Set<string>>(new Set());
 
   // Handle auto-retry for a failed stream
   const handleAutoRetry = useCallback(async (groupId: string) => {
@@ -113,6 +174,15 @@ export function useStreamStats() {
     let unlistenEnded: (() => void) | null = null;
     let unlistenError: (() => void) | null = null;
     let unlistenReconnecting: (() => void) | null = null;
+    let unlistenReconnected: (() => void) | null = null;
+    let unlistenGaveUp: (() => void) | null = null;
+    let unlistenSlate: (() => void) | null = null;
+    let unlistenIngestRejected: (() => void) | null = null;
+    let unlistenIngestStats: (() => void) | null = null;
+    let unlistenPlatformWarnings: (() => void) | null = null;
+    let unlistenScheduleUpcoming: (() => void) | null = null;
+    let unlistenScheduleStarted: (() => void) | null = null;
+    let unlistenScheduleMissed: (() => void) | null = null;
 
     const setupListeners = async () => {
       // Listen for stream stats updates
@@ -129,9 +199,16 @@ export function useStreamStats() {
       unlistenError = await events.on<StreamError>('stream_error', (payload) => {
         setStreamError(payload.groupId, payload.error);
 
-        // Auto-retry if the backend says we can
-        if (payload.canRetry) {
+        // Auto-retry if the backend says we can and is not reconnecting itself
+        if (payload.canRetry && !payload.autoReconnect) {
           handleAutoRetry(payload.groupId);
+        } else if (!payload.canRetry) {
+          toast.error(
+            t('streams.errorNeedsFix', '{{error}}. {{suggestion}}', {
+              error: payload.error,
+              suggestion: payload.suggestion ?? '',
+            })
+          );
         }
       });
 
@@ -144,6 +221,108 @@ export function useStreamStats() {
           })
         );
       });
+
+      // Listen for successful backend reconnects
+      unlistenReconnected = await events.on<StreamReconnected>('stream_reconnected', (payload) => {
+        setActiveGroup(payload.groupId, true);
+        toast.success(t('streams.retrySuccess', 'Stream reconnected successfully'));
+      });
+
+      // Listen for the backend giving up after its last attempt
+      unlistenGaveUp = await events.on<StreamGaveUp>('stream_gave_up', (payload) => {
+        toast.error(
+          t('streams.reconnectGaveUp', 'Reconnection failed after {{attempts}} attempts: {{error}}', {
+            attempts: payload.attempts,
+            error: payload.error,
+          })
+        );
+      });
+
+      // Listen for the backup slate covering an ingest drop
+      unlistenSlate = await events.on<BackupSlate>('backup_slate', (payload) => {
+        if (payload.error) {
+          toast.error(t('streams.slateFailed', 'Backup slate failed: {{error}}', { error: payload.error }));
+        } else if (payload.active) {
+          toast.info(t('streams.slateActive', 'Ingest lost, playing the backup slate'));
+        } else {
+          toast.success(t('streams.slateEnded', 'Ingest is back'));
+        }
+      });
+
+      // Listen for publishers refused by the ingest access settings
+      unlistenIngestRejected = await events.on<IngestRejected>('ingest_rejected', (payload) => {
+        toast.error(
+          t('streams.ingestRejected', 'Rejected ingest from {{client}}: {{reason}}', {
+            client: payload.clientAddr,
+            reason: payload.reason,
+          })
+        );
+      });
+
+      // Warn once per passthrough problem reported with the ingest measurements
+      unlistenIngestStats = await events.on<IngestStats>('ingest_stats', (payload) => {
+        const current = new Set<string>();
+        for (const warning of payload.warnings) {
+          const key = `${warning.groupId}:${warning.targetId}:${warning.message}`;
+          current.add(key);
+          if (!reportedIngestWarnings.current.has(key)) {
+            toast.error(
+              t('streams.ingestWarning', 'Passthrough target may reject the ingest: {{message}}', {
+                message: warning.message,
+              })
+            );
+          }
+        }
+        reportedIngestWarnings.current = current;
+      });
+
+      // Warn about targets set up beyond their platform's limits as groups start
+      unlistenPlatformWarnings = await events.on<PlatformWarning[]>('platform_warnings', (payload) => {
+        for (const warning of payload) {
+          toast.error(
+            t('streams.platformWarning', 'Target may be rejected by its platform: {{message}}', {
+              message: warning.message,
+            })
+          );
+        }
+      });
+
+      // Listen for scheduled streams started or missed by the backend
+      unlistenScheduleUpcoming = await events.on<ScheduleEvent>('schedule_upcoming', (payload) => {
+        toast.info(
+          t('streams.scheduleUpcoming', '{{profile}} goes live at {{time}}', {
+            profile: payload.profileName,
+            time: new Date(payload.occurrence).toLocaleTimeString(),
+          })
+        );
+      });
+
+      unlistenScheduleStarted = await events.on<ScheduleEvent>('schedule_started', async (payload) => {
+        const profileStore = useProfileStore.getState();
+        if (profileStore.current?.name !== payload.profileName) {
+          await profileStore.loadProfile(payload.profileName);
+        }
+        const groups = useProfileStore.getState().current?.outputGroups ?? [];
+        for (const group of groups) {
+          if (payload.groupIds.length === 0 || payload.groupIds.includes(group.id)) {
+            setActiveGroup(group.id, true);
+          }
+        }
+        toast.success(
+          t('streams.scheduleStarted', 'Scheduled stream started for {{profile}}', {
+            profile: payload.profileName,
+          })
+        );
+      });
+
+      unlistenScheduleMissed = await events.on<ScheduleEvent>('schedule_missed', (payload) => {
+        toast.error(
+          t('streams.scheduleMissed', 'Scheduled stream for {{profile}} did not start: {{reason}}', {
+            profile: payload.profileName,
+            reason: payload.reason ?? '',
+          })
+        );
+      });
     };
 
     setupListeners();
@@ -154,8 +333,17 @@ export function useStreamStats() {
       if (unlistenEnded) unlistenEnded();
       if (unlistenError) unlistenError();
       if (unlistenReconnecting) unlistenReconnecting();
+      if (unlistenReconnected) unlistenReconnected();
+      if (unlistenGaveUp) unlistenGaveUp();
+      if (unlistenSlate) unlistenSlate();
+      if (unlistenIngestRejected) unlistenIngestRejected();
+      if (unlistenIngestStats) unlistenIngestStats();
+      if (unlistenPlatformWarnings) unlistenPlatformWarnings();
+      if (unlistenScheduleUpcoming) unlistenScheduleUpcoming();
+      if (unlistenScheduleStarted) unlistenScheduleStarted();
+      if (unlistenScheduleMissed) unlistenScheduleMissed();
     };
-  }, [updateStats, setStreamEnded, setStreamError, handleAutoRetry, t]);
+  }, [updateStats, setStreamEnded, setStreamError, setActiveGroup, handleAutoRetry, t]);
 
   return null;
 }
@@ -1,8 +1,13 @@
-import type { Profile, ProfileSummary, OutputGroup, RtmpInput } from '@/types/profile';
-import type { Encoders } from '@/types/stream';
+import type { Profile, ProfileSummary, OutputGroup, PlatformService, IngestProbeResult, RtmpInput } from '@/types/profile';
+import type { Encoders, IngestStats, IngestStatus, FFmpegProcessStats, PlatformWarning, StreamHealth, TargetStatus } from '@/types/stream';
+import type { ScheduleEntry } from '@/types/schedule';
+import type { StreamSession, StreamSessionSummary } from '@/types/session';
+import type { PreflightReport } from '@/types/preflight';
 import type {
   AppSettings,
+  BandwidthTestResult,
   FFmpegVersionInfo,
+  HlsStreamInfo,
   RotationReport,
   RtmpTestResult,
   ObsConfig,
@@ -88,7 +93,7 @@ export const api = {
     load: (name: string, password?: string, setActive: boolean = true) =>
       invokeHttp<Profile>('load_profile', { name, password, setActive }),
     save: (profile: Profile, password?: string) =>
-      invokeHttp<void>('save_profile', { profile, password }),
+      invokeHttp<PlatformWarning[]>('save_profile', { profile, password }),
     delete: (name: string) => invokeHttp<void>('delete_profile', { name }),
     isEncrypted: (name: string) => invokeHttp<boolean>('is_profile_encrypted', { name }),
     validateInput: (profileId: string, input: RtmpInput) =>
@@ -113,14 +118,44 @@ export const api = {
     isGroupStreaming: (groupId: string) =>
       invokeHttp<boolean>('is_group_streaming', { groupId }),
     getActiveGroupIds: () => invokeHttp<string[]>('get_active_group_ids'),
+    /** Local HLS outputs of running groups with their playlist URLs */
+    getHlsStreams: () => invokeHttp<HlsStreamInfo[]>('get_hls_streams'),
+    /** Publisher live on the built-in RTMP ingest, if any */
+    getIngestStatus: () => invokeHttp<IngestStatus | null>('get_ingest_status'),
+    /** Codec, resolution, GOP and bitrate of the incoming stream */
+    getIngestStats: () => invokeHttp<IngestStats | null>('get_ingest_stats'),
+    /** Current health rating of running groups */
+    getHealth: () => invokeHttp<StreamHealth[]>('get_stream_health'),
+    /** CPU, memory and thread usage of running FFmpeg processes */
+    getProcessStats: () => invokeHttp<FFmpegProcessStats[]>('get_ffmpeg_process_stats'),
+    /** Per-target connection state of running groups */
+    getTargetStatuses: (groupId?: string) =>
+      invokeHttp<TargetStatus[]>('get_target_statuses', { groupId }),
     toggleTarget: (targetId: string, enabled: boolean, group: OutputGroup, incomingUrl: string) =>
       invokeHttp<number>('toggle_stream_target', { targetId, enabled, group, incomingUrl }),
+    /** Apply target changes to a live group without restarting its other outputs */
+    updateLiveTargets: (group: OutputGroup) =>
+      invokeHttp<number | null>('update_live_targets', { group }),
     isTargetDisabled: (targetId: string) =>
       invokeHttp<boolean>('is_target_disabled', { targetId }),
     /** Retry a failed stream. Returns PID and next delay if another retry is needed */
     retry: (groupId: string) =>
       invokeHttp<{ pid: number; nextDelaySecs: number | null }>('retry_stream', { groupId }),
   },
+  schedule: {
+    /** Schedule entries, optionally limited to one profile */
+    getAll: (profileName?: string) => invokeHttp<ScheduleEntry[]>('get_schedules', { profileName }),
+    /** Create or update an entry; returns it with its ID and run state */
+    save: (schedule: ScheduleEntry) => invokeHttp<ScheduleEntry>('save_schedule', { schedule }),
+    delete: (id: string) => invokeHttp<void>('delete_schedule', { id }),
+  },
+  sessions: {
+    /** Recorded stream sessions, newest first (including the live one) */
+    list: () => invokeHttp<StreamSessionSummary[]>('list_stream_sessions'),
+    get: (id: string) => invokeHttp<StreamSession>('get_stream_session', { id }),
+    /** Write a session to a .json file, or its samples to a .csv file */
+    export: (id: string, path: string) => invokeHttp<void>('export_stream_session', { id, path }),
+  },
   system: {
     /** Get available video and audio encoders detected on the system */
     getEncoders: () => invokeHttp<Encoders>('get_encoders'),
@@ -131,8 +166,41 @@ export const api = {
     checkFfmpegUpdate: (installedVersion?: string) =>
       invokeHttp<FFmpegVersionInfo>('check_ffmpeg_update', { installedVersion }),
     validateFfmpegPath: (path: string) => invokeHttp<string>('validate_ffmpeg_path', { path }),
-    testRtmpTarget: (url: string, streamKey: string) =>
-      invokeHttp<RtmpTestResult>('test_rtmp_target', { url, streamKey }),
+    testRtmpTarget: (url: string, streamKey: string, publish?: boolean) =>
+      invokeHttp<RtmpTestResult>('test_rtmp_target', { url, streamKey, publish }),
+    testWhipTarget: (url: string, token: string) =>
+      invokeHttp<RtmpTestResult>('test_whip_target', { url, token }),
+    /** Publish a test pattern at rising bitrates and compare the uplink with the profile's needs */
+    testUploadBandwidth: (
+      profileName: string,
+      groupId: string,
+      targetId: string,
+      password?: string,
+      maxKbps?: number,
+      /** Required for targets other than Twitch, which receive the test pattern live */
+      confirmLive?: boolean
+    ) =>
+      invokeHttp<BandwidthTestResult>('test_upload_bandwidth', {
+        profileName,
+        groupId,
+        targetId,
+        password,
+        maxKbps,
+        confirmLive,
+      }),
+    /** Run the go-live checklist for a profile */
+    runPreflight: (profileName: string, password?: string) =>
+      invokeHttp<PreflightReport>('run_preflight', { profileName, password }),
+    /** Measure ingest server latency per RTMP target; apply switches targets to the fastest */
+    probeIngestServers: (profileName: string, password?: string, apply?: boolean) =>
+      invokeHttp<IngestProbeResult[]>('probe_ingest_servers', { profileName, password, apply }),
+    /** Platform services from the runtime registry */
+    getPlatforms: () => invokeHttp<PlatformService[]>('get_platforms'),
+    /** Re-read the services and platform-overrides.json files; returns the platform count */
+    reloadPlatforms: () => invokeHttp<number>('reload_platforms'),
+    /** Import an OBS rtmp-services services.json; returns the platform count */
+    importPlatformServices: (path: string) =>
+      invokeHttp<number>('import_platform_services', { path }),
     getRecentLogs: (maxLines?: number) =>
       invokeHttp<string[]>('get_recent_logs', { maxLines }),
     exportLogs: (path: string, content: string) =>
@@ -117,6 +117,10 @@ export async function validateStreamConfig(
   // 6. Validate each target
   for (const target of targetsToValidate) {
     const targetName = target.name || i18n.t('common.unnamed', 'Unnamed');
+    const protocol = target.protocol ?? 'rtmp';
+
+    // Recordings and HLS write to the app data dir and need neither URL nor key
+    if (protocol === 'record' || protocol === 'hls') continue;
 
     if (!target.url || target.url.trim() === '') {
       issues.push({
@@ -127,7 +131,8 @@ export async function validateStreamConfig(
       });
     }
 
-    if (!target.streamKey || target.streamKey.trim() === '') {
+    // SRT passphrases, RIST secrets and WHIP tokens are optional
+    if (protocol === 'rtmp' && (!target.streamKey || target.streamKey.trim() === '')) {
       issues.push({
         code: 'TARGET_MISSING_STREAM_KEY',
         message: i18n.t('errors.targetMissingKey', { name: targetName }),
@@ -235,6 +240,10 @@ export async function testStreamConnectivity(
       if (enabledTargetsOnly && !enabledTargetIds.has(target.id)) {
         continue;
       }
+      // Local recordings and HLS output have no endpoint to test
+      if (target.protocol === 'record' || target.protocol === 'hls') {
+        continue;
+      }
       allTargets.push({ target, groupName: group.name });
     }
   }
@@ -261,7 +270,10 @@ export async function testStreamConnectivity(
     onProgress?.(i + 1, allTargets.length, targetName);
 
     try {
-      const result: RtmpTestResult = await api.system.testRtmpTarget(target.url, target.streamKey);
+      const result: RtmpTestResult =
+        target.protocol === 'whip'
+          ? await api.system.testWhipTarget(target.url, target.streamKey)
+          : await api.system.testRtmpTarget(target.url, target.streamKey);
 
       const testResult: TargetTestResult = {
         targetId: target.id,
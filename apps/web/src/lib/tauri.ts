@@ -1,9 +1,14 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Profile, ProfileSummary, OutputGroup, RtmpInput } from '@/types/profile';
-import type { Encoders } from '@/types/stream';
+import type { Profile, ProfileSummary, OutputGroup, PlatformService, IngestProbeResult, RtmpInput } from '@/types/profile';
+import type { Encoders, IngestStats, IngestStatus, FFmpegProcessStats, PlatformWarning, StreamHealth, TargetStatus } from '@/types/stream';
+import type { ScheduleEntry } from '@/types/schedule';
+import type { StreamSession, StreamSessionSummary } from '@/types/session';
+import type { PreflightReport } from '@/types/preflight';
 import type {
   AppSettings,
+  BandwidthTestResult,
   FFmpegVersionInfo,
+  HlsStreamInfo,
   ObsConfig,
   ObsIntegrationDirection,
   ObsState,
@@ -31,7 +36,7 @@ export const api = {
     load: (name: string, password?: string, setActive: boolean = true) =>
       invoke<Profile>('load_profile', { name, password, setActive }),
     save: (profile: Profile, password?: string) =>
-      invoke<void>('save_profile', { profile, password }),
+      invoke<PlatformWarning[]>('save_profile', { profile, password }),
     delete: (name: string) => invoke<void>('delete_profile', { name }),
     isEncrypted: (name: string) => invoke<boolean>('is_profile_encrypted', { name }),
     /** Validate RTMP input doesn't conflict with existing profiles (Story 2.2) */
@@ -52,13 +57,43 @@ export const api = {
     getActiveCount: () => invoke<number>('get_active_stream_count'),
     isGroupStreaming: (groupId: string) => invoke<boolean>('is_group_streaming', { groupId }),
     getActiveGroupIds: () => invoke<string[]>('get_active_group_ids'),
+    /** Local HLS outputs of running groups with their playlist URLs */
+    getHlsStreams: () => invoke<HlsStreamInfo[]>('get_hls_streams'),
+    /** Publisher live on the built-in RTMP ingest, if any */
+    getIngestStatus: () => invoke<IngestStatus | null>('get_ingest_status'),
+    /** Codec, resolution, GOP and bitrate of the incoming stream */
+    getIngestStats: () => invoke<IngestStats | null>('get_ingest_stats'),
+    /** Current health rating of running groups */
+    getHealth: () => invoke<StreamHealth[]>('get_stream_health'),
+    /** CPU, memory and thread usage of running FFmpeg processes */
+    getProcessStats: () => invoke<FFmpegProcessStats[]>('get_ffmpeg_process_stats'),
+    /** Per-target connection state of running groups */
+    getTargetStatuses: (groupId?: string) =>
+      invoke<TargetStatus[]>('get_target_statuses', { groupId }),
     toggleTarget: (targetId: string, enabled: boolean, group: OutputGroup, incomingUrl: string) =>
       invoke<number>('toggle_stream_target', { targetId, enabled, group, incomingUrl }),
+    /** Apply target changes to a live group without restarting its other outputs */
+    updateLiveTargets: (group: OutputGroup) =>
+      invoke<number | null>('update_live_targets', { group }),
     isTargetDisabled: (targetId: string) => invoke<boolean>('is_target_disabled', { targetId }),
     /** Retry a failed stream. Returns PID and next delay if another retry is needed */
     retry: (groupId: string) =>
       invoke<{ pid: number; nextDelaySecs: number | null }>('retry_stream', { groupId }),
   },
+  schedule: {
+    /** Schedule entries, optionally limited to one profile */
+    getAll: (profileName?: string) => invoke<ScheduleEntry[]>('get_schedules', { profileName }),
+    /** Create or update an entry; returns it with its ID and run state */
+    save: (schedule: ScheduleEntry) => invoke<ScheduleEntry>('save_schedule', { schedule }),
+    delete: (id: string) => invoke<void>('delete_schedule', { id }),
+  },
+  sessions: {
+    /** Recorded stream sessions, newest first (including the live one) */
+    list: () => invoke<StreamSessionSummary[]>('list_stream_sessions'),
+    get: (id: string) => invoke<StreamSession>('get_stream_session', { id }),
+    /** Write a session to a .json file, or its samples to a .csv file */
+    export: (id: string, path: string) => invoke<void>('export_stream_session', { id, path }),
+  },
   system: {
     getEncoders: () => invoke<Encoders>('get_encoders'),
     testFfmpeg: () => invoke<string>('test_ffmpeg'),
@@ -67,9 +102,43 @@ export const api = {
       invoke<FFmpegVersionInfo>('check_ffmpeg_update', { installedVersion }),
     /** Validate a custom FFmpeg path before saving */
     validateFfmpegPath: (path: string) => invoke<string>('validate_ffmpeg_path', { path }),
-    /** Test RTMP target connectivity with actual connection attempt */
-    testRtmpTarget: (url: string, streamKey: string) =>
-      invoke<RtmpTestResult>('test_rtmp_target', { url, streamKey }),
+    /** Check an RTMP target and its key without going live; publish also sends publish (may go live) */
+    testRtmpTarget: (url: string, streamKey: string, publish?: boolean) =>
+      invoke<RtmpTestResult>('test_rtmp_target', { url, streamKey, publish }),
+    /** Test WHIP target by performing the SDP offer/answer exchange */
+    testWhipTarget: (url: string, token: string) =>
+      invoke<RtmpTestResult>('test_whip_target', { url, token }),
+    /** Publish a test pattern at rising bitrates and compare the uplink with the profile's needs */
+    testUploadBandwidth: (
+      profileName: string,
+      groupId: string,
+      targetId: string,
+      password?: string,
+      maxKbps?: number,
+      /** Required for targets other than Twitch, which receive the test pattern live */
+      confirmLive?: boolean
+    ) =>
+      invoke<BandwidthTestResult>('test_upload_bandwidth', {
+        profileName,
+        groupId,
+        targetId,
+        password,
+        maxKbps,
+        confirmLive,
+      }),
+    /** Run the go-live checklist for a profile */
+    runPreflight: (profileName: string, password?: string) =>
+      invoke<PreflightReport>('run_preflight', { profileName, password }),
+    /** Measure ingest server latency per RTMP target; apply switches targets to the fastest */
+    probeIngestServers: (profileName: string, password?: string, apply?: boolean) =>
+      invoke<IngestProbeResult[]>('probe_ingest_servers', { profileName, password, apply }),
+    /** Platform services from the runtime registry */
+    getPlatforms: () => invoke<PlatformService[]>('get_platforms'),
+    /** Re-read the services and platform-overrides.json files; returns the platform count */
+    reloadPlatforms: () => invoke<number>('reload_platforms'),
+    /** Import an OBS rtmp-services services.json; returns the platform count */
+    importPlatformServices: (path: string) =>
+      invoke<number>('import_platform_services', { path }),
     downloadFfmpeg: () => invoke<string>('download_ffmpeg'),
     cancelFfmpegDownload: () => invoke<void>('cancel_ffmpeg_download'),
     deleteFfmpeg: () => invoke<void>('delete_ffmpeg'),
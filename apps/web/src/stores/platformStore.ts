@@ -0,0 +1,72 @@
+import { create } from 'zustand';
+import { api } from '@/lib/backend';
+import { PLATFORMS, type PlatformConfig, type PlatformService } from '@/types/profile';
+
+interface PlatformState {
+  // Display config per platform name; starts with the bundled list until the backend answers
+  platforms: Record<string, PlatformConfig>;
+
+  loadPlatforms: () => Promise<void>;
+  importServices: (path: string) => Promise<number>;
+}
+
+// Black text on bright backgrounds, white on dark (same rule as generate-platforms)
+function textColorFor(hexColor: string): string {
+  const hex = hexColor.replace('#', '');
+  const r = parseInt(hex.substring(0, 2), 16);
+  const g = parseInt(hex.substring(2, 4), 16);
+  const b = parseInt(hex.substring(4, 6), 16);
+  const luminance = (0.299 * r + 0.587 * g + 0.114 * b) / 255;
+  return luminance > 0.5 ? '#000000' : '#FFFFFF';
+}
+
+function toPlatformConfig(service: PlatformService): PlatformConfig {
+  const color = service.color ?? PLATFORMS.Custom.color;
+  return {
+    displayName: service.displayName ?? service.name,
+    abbreviation: service.abbreviation ?? service.name.slice(0, 2).toUpperCase(),
+    color,
+    textColor: textColorFor(color),
+    defaultServer: service.defaultUrl.replace(/\/+$/, ''),
+    streamKeyPlacement: service.streamKeyPlacement,
+  };
+}
+
+/**
+ * Display config for a target's service; names the registry no longer lists show as Custom
+ */
+export function getPlatformConfig(
+  platforms: Record<string, PlatformConfig>,
+  service: string
+): PlatformConfig {
+  return platforms[service] ?? platforms.Custom ?? PLATFORMS.Custom;
+}
+
+export const usePlatformStore = create<PlatformState>((set, get) => ({
+  platforms: PLATFORMS,
+
+  // Load the services the backend registry uses (bundled, imported and user overrides)
+  loadPlatforms: async () => {
+    try {
+      const services = await api.system.getPlatforms();
+      const platforms: Record<string, PlatformConfig> = {};
+      for (const service of services) {
+        const isRtmp = /^rtmps?:\/\//.test(service.defaultUrl);
+        const placement = service.streamKeyPlacement;
+        if (isRtmp && (placement === 'append' || placement === 'in_url_template')) {
+          platforms[service.name] = toPlatformConfig(service);
+        }
+      }
+      set({ platforms });
+    } catch (error) {
+      console.error('[PlatformStore] Failed to load platforms:', error);
+    }
+  },
+
+  // Import an OBS rtmp-services services.json, returning the number of platforms
+  importServices: async (path) => {
+    const count = await api.system.importPlatformServices(path);
+    await get().loadPlatforms();
+    return count;
+  },
+}));
@@ -10,10 +10,31 @@ import {
   type Platform,
   createDefaultProfile,
 } from '@/types/profile';
+import type { PlatformWarning } from '@/types/stream';
 import { useThemeStore } from '@/stores/themeStore';
 import { useLanguageStore, type Language } from '@/stores/languageStore';
 import { useSettingsStore } from '@/stores/settingsStore';
 import { useObsStore } from '@/stores/obsStore';
+import { useStreamStore } from '@/stores/streamStore';
+
+/**
+ * Push target changes of live output groups to the backend.
+ * Only the changed targets reconnect; the group's other outputs keep streaming.
+ */
+async function syncLiveTargets(profile: Profile | null, groupIds: string[]): Promise<void> {
+  if (!profile) return;
+  const { activeGroups } = useStreamStore.getState();
+  for (const groupId of groupIds) {
+    const group = profile.outputGroups.find((g) => g.id === groupId);
+    if (!group || !activeGroups.has(groupId)) continue;
+    try {
+      await api.stream.updateLiveTargets(group);
+    } catch (error) {
+      console.error('[ProfileStore] Failed to update live targets:', error);
+      useStreamStore.getState().setError(String(error));
+    }
+  }
+}
 
 interface ProfileState {
   // State
@@ -21,6 +42,7 @@ interface ProfileState {
   current: Profile | null;
   loading: boolean;
   error: string | null;
+  platformWarnings: PlatformWarning[]; // Targets beyond their platform's limits, from the last save
 
   // Encryption state
   pendingPasswordProfile: string | null; // Profile name awaiting password
@@ -173,6 +195,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
   current: null,
   loading: false,
   error: null,
+  platformWarnings: [],
   pendingPasswordProfile: null,
   passwordError: null,
   pendingUnlock: false,
@@ -328,7 +351,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
     // The caller should have already updated the state optimistically
     set({ error: null });
     try {
-      await api.profile.save(current, password);
+      const platformWarnings = await api.profile.save(current, password);
       console.log('[ProfileStore] saveProfile completed (backend save successful)');
       // Update the summary in the list
       const summaries = get().profiles.map((s) =>
@@ -338,7 +361,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
       if (!summaries.find((s) => s.name === current.name)) {
         summaries.push(createSummary(current));
       }
-      set({ profiles: summaries });
+      set({ profiles: summaries, platformWarnings });
     } catch (error) {
       console.error('[ProfileStore] saveProfile failed:', error);
       set({ error: String(error) });
@@ -515,6 +538,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
         },
       });
       await get().saveProfile();
+      await syncLiveTargets(get().current, [groupId]);
     }
   },
 
@@ -537,6 +561,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
         },
       });
       await get().saveProfile();
+      await syncLiveTargets(get().current, [groupId]);
     }
   },
 
@@ -554,6 +579,7 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
         },
       });
       await get().saveProfile();
+      await syncLiveTargets(get().current, [groupId]);
     }
   },
 
@@ -585,5 +611,6 @@ export const useProfileStore = create<ProfileState>((set, get) => ({
       },
     });
     await get().saveProfile();
+    await syncLiveTargets(get().current, [fromGroupId, toGroupId]);
   },
 }));
@@ -303,10 +303,10 @@ export const useStreamStore = create<StreamState>((set, get) => ({
   },
 
   // Toggle a target on/off during live streaming
-  // This will restart the parent output group with the updated target list
+  // Only that target's output starts or stops; the group's other targets stay connected
   toggleTargetLive: async (targetId, enabled, group, incomingUrl) => {
     try {
-      // Call backend to toggle target and restart group
+      // Call backend to toggle the target's output
       await api.stream.toggleTarget(targetId, enabled, group, incomingUrl);
 
       // Update frontend state
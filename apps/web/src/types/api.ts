@@ -70,6 +70,57 @@ export interface RtmpTestResult {
   message: string;
   /** Time taken for the test in milliseconds */
   latency_ms: number | null;
+  /** RTMP probe stages up to the first failure (absent for WHIP tests) */
+  stages?: RtmpStageResult[];
+}
+
+/**
+ * One stage of the RTMP target probe
+ */
+export interface RtmpStageResult {
+  stage: 'dns' | 'tcp' | 'tls' | 'handshake' | 'connect' | 'key' | 'publish';
+  success: boolean;
+  message: string;
+  latency_ms: number;
+}
+
+/**
+ * One bitrate step of an upload bandwidth test
+ * (also emitted as 'bandwidth_test_progress' while the test runs)
+ */
+export interface BandwidthStep {
+  bitrateKbps: number; // video + audio published in this step
+  measuredKbps: number; // throughput that got out in real time
+  sustained: boolean;
+  error?: string;
+}
+
+/**
+ * Result of test_upload_bandwidth
+ */
+export interface BandwidthTestResult {
+  groupId: string;
+  targetId: string;
+  targetName: string;
+  bandwidthTestMode: boolean; // Twitch ?bandwidthtest=true, nothing went live
+  steps: BandwidthStep[];
+  sustainableKbps: number;
+  atLeast: boolean; // every step was sustained; capacity may be higher
+  requiredKbps: number; // summed bitrate of the profile's network targets
+  warning?: string; // set when requiredKbps exceeds the measured capacity
+}
+
+/**
+ * Local HLS output served by the backend
+ */
+export interface HlsStreamInfo {
+  groupId: string;
+  targetId: string;
+  name: string;
+  /** Authenticated playlist URL (relative to the backend) */
+  playlistUrl: string;
+  /** Tokenized URL for sharing, or null when no share token is set */
+  shareUrl: string | null;
 }
 
 /**
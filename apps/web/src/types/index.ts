@@ -3,3 +3,6 @@ export * from './profile';
 export * from './stream';
 export * from './api';
 export * from './chat';
+export * from './schedule';
+export * from './preflight';
+export * from './session';
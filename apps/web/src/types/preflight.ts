@@ -0,0 +1,26 @@
+/**
+ * Outcome of a preflight check, from best to worst
+ */
+export type PreflightStatus = 'pass' | 'warn' | 'fail';
+
+/**
+ * One line of the go-live checklist
+ */
+export interface PreflightCheck {
+  id: string; // stable identifier, e.g. "ffmpeg", "encoders", "target", "oauth"
+  label: string;
+  status: PreflightStatus;
+  message: string;
+  groupId?: string; // set for per-target checks
+  targetId?: string;
+}
+
+/**
+ * Result of run_preflight
+ */
+export interface PreflightReport {
+  profileName: string;
+  status: PreflightStatus; // worst status of all checks
+  checks: PreflightCheck[];
+  checkedAt: number; // Unix ms
+}
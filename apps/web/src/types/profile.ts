@@ -86,6 +86,34 @@ export interface OAuthSettings {
   youtube: OAuthProfileAccount;
 }
 
+/**
+ * Automatic reconnection after an output group's FFmpeg process exits
+ */
+export interface ReconnectSettings {
+  enabled: boolean;
+  maxRetries: number;
+  initialDelaySecs: number;
+  maxDelaySecs: number;
+  jitterPercent: number; // random spread applied to each backoff delay, 0-100
+}
+
+/**
+ * Stream health thresholds and what to do when a group's rating changes
+ */
+export interface HealthSettings {
+  enabled: boolean;
+  windowSecs: number; // stats are averaged over this window; recovery must hold this long
+  speedDegraded: number; // encoding speed, 1.0 = real-time
+  speedCritical: number;
+  droppedDegradedPercent: number; // share of frames dropped within the window
+  droppedCriticalPercent: number;
+  bitrateDegradedPercent: number; // output bitrate as % of the configured bitrate
+  bitrateCriticalPercent: number;
+  discordAlerts: boolean; // post changes to the profile's Discord webhook
+  obsCriticalScene: string; // scene to switch to when a group turns critical (empty = none)
+  obsRecoveredScene: string; // scene to switch back to once every group is good (empty = none)
+}
+
 /**
  * Per-profile settings (theme, language, integrations, security)
  */
@@ -104,16 +132,47 @@ export interface ProfileSettings {
   discord: DiscordSettings;
   chat: ChatSettings;
   oauth: OAuthSettings;
+
+  // Streaming Settings
+  reconnect?: ReconnectSettings;
+  health?: HealthSettings;
 }
 
 /**
- * RTMP Input configuration - where the stream enters the system
+ * SRT listener options (only used when input type is "srt")
+ */
+export interface SrtInputOptions {
+  latencyMs?: number; // receiver latency, e.g., 200
+  passphrase?: string; // 10-79 characters, empty = unencrypted
+  streamId?: string; // expected stream ID, empty = accept any
+}
+
+/**
+ * Backup slate played to every target while the ingest is disconnected
+ */
+export interface SlateSettings {
+  enabled: boolean;
+  source: 'card' | 'image' | 'video';
+  path?: string; // image or video file (unused for generated cards)
+  message: string; // text shown on generated cards
+  width: number; // e.g., 1280
+  height: number; // e.g., 720
+  fps: number; // e.g., 30
+  delayMs: number; // ingest silence before the slate takes over, e.g., 1500
+}
+
+/**
+ * Stream input configuration - where the stream enters the system
  */
 export interface RtmpInput {
-  type: 'rtmp';
+  type: 'rtmp' | 'srt';
   bindAddress: string; // e.g., "0.0.0.0"
   port: number; // e.g., 1935
-  application: string; // e.g., "live"
+  application: string; // e.g., "live" (RTMP only)
+  ingestKey?: string; // Stream key publishers must use (RTMP only)
+  allowedNetworks?: string[]; // e.g., ["192.168.1.0/24"] (RTMP only, empty = any)
+  srt?: SrtInputOptions;
+  slate?: SlateSettings;
 }
 
 /**
@@ -148,14 +207,81 @@ export interface ContainerSettings {
 }
 
 /**
- * Stream target - RTMP destination
+ * Transport protocol for a stream target
+ */
+export type TargetProtocol = 'rtmp' | 'srt' | 'rist' | 'whip' | 'record' | 'hls';
+
+/**
+ * SRT caller options (streamKey is used as the passphrase)
+ */
+export interface SrtOutputOptions {
+  latencyMs?: number; // e.g., 200
+  streamId?: string;
+}
+
+/**
+ * RIST sender options (streamKey is used as the shared secret)
+ */
+export interface RistOutputOptions {
+  bufferMs?: number; // e.g., 1000
+  encryption?: 128 | 256;
+  profile?: 'simple' | 'main';
+}
+
+/**
+ * Local recording options (protocol "record"; url and streamKey are unused)
+ */
+export interface RecordOptions {
+  format?: 'mp4' | 'mkv' | 'ts'; // default "mkv"
+  segmentSeconds?: number; // start a new file every N seconds
+  segmentSizeMb?: number; // approximate, estimated from the group bitrate
+  filenameTemplate?: string; // file name only: {profile}, {group}, {target}, {timestamp}
+  minFreeSpaceMb?: number; // default 1024
+}
+
+/**
+ * Local HLS options (protocol "hls"; streamKey is an optional share token)
+ */
+export interface HlsOptions {
+  lowLatency?: boolean;
+  segmentSeconds?: number; // default 2
+  playlistSize?: number; // default 6
+}
+
+/**
+ * Stream target - network destination or local recording/HLS output
  */
 export interface StreamTarget {
   id: string;
   service: Platform; // renamed from 'platform'
   name: string;
+  protocol?: TargetProtocol; // defaults to 'rtmp'
   url: string;
-  streamKey: string; // supports ${ENV_VAR} syntax
+  streamKey: string; // supports ${ENV_VAR} syntax (passphrase/secret/bearer token for SRT/RIST/WHIP)
+  srt?: SrtOutputOptions;
+  rist?: RistOutputOptions;
+  record?: RecordOptions;
+  hls?: HlsOptions;
+  renditionId?: string; // output group rendition to send (defaults to the group's video settings)
+  ingestServer?: string; // platform ingest server picked by probe_ingest_servers
+}
+
+/**
+ * Extra video rendition encoded from the output group's single decode (ABR ladder)
+ */
+export interface Rendition {
+  id: string;
+  name: string;
+  video: VideoSettings; // must re-encode ("copy" is rejected)
+}
+
+/**
+ * CPU and I/O priority of a group's FFmpeg processes (Linux; niceness on any Unix; ignored on Windows)
+ */
+export interface ProcessPriority {
+  nice?: number; // -20 (highest) to 19 (lowest); below 0 needs privileges
+  ioClass?: 'realtime' | 'best-effort' | 'idle';
+  ioLevel?: number; // 0 (highest) to 7, within the realtime or best-effort class
 }
 
 /**
@@ -169,7 +295,9 @@ export interface OutputGroup {
   video: VideoSettings;
   audio: AudioSettings;
   container: ContainerSettings;
+  renditions?: Rendition[];
   streamTargets: StreamTarget[];
+  priority?: ProcessPriority; // unset = inherit the server's priority
 }
 
 /**
@@ -205,6 +333,57 @@ export interface ProfileSummary {
 import { PLATFORMS } from './generated-platforms';
 export { PLATFORMS };
 
+/**
+ * Display config of one platform (see usePlatformStore for the list loaded at runtime)
+ */
+export type PlatformConfig = (typeof PLATFORMS)[Platform];
+
+/**
+ * Service entry as served by get_platforms (streaming-platforms.json format)
+ */
+export interface PlatformService {
+  name: string;
+  displayName?: string;
+  defaultUrl: string;
+  streamKeyPlacement: 'append' | 'in_url_template';
+  abbreviation?: string;
+  color?: string;
+  faviconPath?: string;
+  recommended?: Record<string, unknown>; // OBS keys, e.g. "max video bitrate"
+  'supported video codecs'?: string[];
+  'supported audio codecs'?: string[];
+  servers?: IngestServer[]; // ingest servers to choose from by latency
+}
+
+/**
+ * Named platform ingest server
+ */
+export interface IngestServer {
+  name: string;
+  url: string;
+}
+
+/**
+ * Latency measured to one ingest server
+ */
+export interface IngestServerProbe extends IngestServer {
+  latencyMs?: number; // best TCP connect time
+  handshakeMs?: number; // RTMP handshake reply time (plain RTMP only)
+  error?: string;
+}
+
+/**
+ * Probe results for one target, servers ordered fastest first
+ */
+export interface IngestProbeResult {
+  groupId: string;
+  targetId: string;
+  targetName: string;
+  servers: IngestServerProbe[];
+  recommended?: IngestServer;
+  applied: boolean; // target URL was switched to the recommended server
+}
+
 /**
  * Factory functions for creating default objects
  */
@@ -215,6 +394,24 @@ export const createDefaultRtmpInput = (): RtmpInput => ({
   application: 'live',
 });
 
+/**
+ * Build the URL the backend relay listens on for an input
+ */
+export const buildIncomingUrl = (input: RtmpInput): string => {
+  if (input.type !== 'srt') {
+    return `rtmp://${input.bindAddress}:${input.port}/${input.application}`;
+  }
+
+  const params = new URLSearchParams({ mode: 'listener' });
+  if (input.srt?.latencyMs !== undefined) {
+    // libsrt's URL option is expressed in microseconds
+    params.set('latency', String(input.srt.latencyMs * 1000));
+  }
+  if (input.srt?.passphrase) params.set('passphrase', input.srt.passphrase);
+  if (input.srt?.streamId) params.set('streamid', input.srt.streamId);
+  return `srt://${input.bindAddress}:${input.port}?${params.toString()}`;
+};
+
 export const createDefaultVideoSettings = (): VideoSettings => ({
   codec: 'copy',
   width: 0,
@@ -262,8 +459,8 @@ export const createPassthroughOutputGroup = (): OutputGroup => ({
 export const createDefaultStreamTarget = (service: Platform): StreamTarget => ({
   id: crypto.randomUUID(),
   service,
-  name: PLATFORMS[service].displayName,
-  url: PLATFORMS[service].defaultServer,
+  name: (PLATFORMS[service] ?? PLATFORMS.Custom).displayName,
+  url: (PLATFORMS[service] ?? PLATFORMS.Custom).defaultServer,
   streamKey: '',
 });
 
@@ -0,0 +1,29 @@
+/**
+ * Scheduled stream start/stop for a profile
+ * One-off entries set `startAt`; recurring entries set `cron` instead
+ */
+export interface ScheduleEntry {
+  id: string; // empty when creating
+  profileName: string;
+  enabled: boolean;
+  startAt?: number; // one-off start, Unix ms
+  cron?: string; // five-field cron expression in server local time, e.g. "0 19 * * 1-5"
+  durationMinutes?: number; // stop after this long; unset = stream until stopped
+  groupIds: string[]; // empty = every group with targets
+  createdAt: number;
+  lastOccurrence?: number; // maintained by the backend
+  runningSince?: number; // maintained by the backend
+  startedGroupIds?: string[]; // groups the live occurrence started; maintained by the backend
+}
+
+/**
+ * Payload of the schedule_upcoming, schedule_started and schedule_missed events
+ */
+export interface ScheduleEvent {
+  scheduleId: string;
+  profileName: string;
+  occurrence: number; // Unix ms
+  stopAt?: number; // Unix ms
+  groupIds: string[];
+  reason?: string; // why a start was missed
+}
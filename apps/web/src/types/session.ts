@@ -0,0 +1,64 @@
+import type { Platform, TargetProtocol } from './profile';
+
+/**
+ * Stream target as recorded in a session (no URL or stream key is kept)
+ */
+export interface SessionTarget {
+  id: string;
+  name: string;
+  service: Platform;
+  protocol: TargetProtocol;
+}
+
+export interface SessionGroup {
+  id: string;
+  name: string;
+  targets: SessionTarget[];
+}
+
+export type SessionEventKind = 'error' | 'reconnecting' | 'reconnected' | 'gave_up' | 'ended';
+
+export interface SessionEvent {
+  at: number; // Unix ms
+  groupId: string;
+  kind: SessionEventKind;
+  message: string;
+}
+
+/**
+ * One group's stream stats averaged over a 5-second interval
+ */
+export interface SessionSample {
+  at: number; // start of the interval, Unix ms
+  groupId: string;
+  fps: number;
+  bitrate: number; // kbps
+  speed: number;
+  droppedFrames: number; // FFmpeg's count at the end of the interval; restarts after a reconnect
+}
+
+/**
+ * One streaming run, from the first group starting until the last one stopped
+ */
+export interface StreamSession {
+  id: string;
+  profileName: string;
+  startedAt: number; // Unix ms
+  endedAt?: number; // unset while live
+  groups: SessionGroup[];
+  events: SessionEvent[];
+  samples: SessionSample[];
+}
+
+export interface StreamSessionSummary {
+  id: string;
+  profileName: string;
+  startedAt: number;
+  endedAt?: number;
+  groupCount: number;
+  targetCount: number;
+  errors: number;
+  reconnects: number;
+  droppedFrames: number; // summed across reconnects
+  averageBitrate: number; // kbps
+}
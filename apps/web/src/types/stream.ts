@@ -13,6 +13,111 @@ export interface TargetStats {
   status: StreamStatusType;
 }
 
+/**
+ * Connection state of a single stream target, tracked by the backend
+ */
+export type TargetConnectionState = 'connecting' | 'live' | 'failed' | 'retrying';
+
+/**
+ * Per-target status from the 'target_status' event / get_target_statuses
+ */
+export interface TargetStatus {
+  groupId: string;
+  targetId: string;
+  name: string;
+  service: string;
+  protocol: string;
+  state: TargetConnectionState;
+  /** Failure reason parsed from FFmpeg output */
+  error?: string;
+  /** Unix milliseconds of the last state change */
+  updatedAt: number;
+}
+
+/**
+ * Stable category of an FFmpeg failure (code of the stream_error event)
+ */
+export type FFmpegErrorCode =
+  | 'auth_rejected'
+  | 'dns_failure'
+  | 'connection_refused'
+  | 'connection_timeout'
+  | 'connection_lost'
+  | 'tls_error'
+  | 'encoder_unavailable'
+  | 'invalid_argument'
+  | 'input_timeout'
+  | 'disk_full'
+  | 'unknown';
+
+/**
+ * Stream properties the publisher announced in onMetaData
+ */
+export interface IngestMetadata {
+  width?: number;
+  height?: number;
+  frameRate?: number;
+  videoCodec?: string;
+  videoBitrateKbps?: number;
+  audioCodec?: string;
+  audioBitrateKbps?: number;
+  audioSampleRate?: number;
+  audioChannels?: number;
+  encoder?: string;
+}
+
+/**
+ * Publisher live on the built-in RTMP ingest ('ingest_connected' event / get_ingest_status)
+ */
+export interface IngestStatus {
+  clientAddr: string;
+  app: string;
+  /** Unix milliseconds when publishing started */
+  connectedAt: number;
+  metadata: IngestMetadata;
+}
+
+/**
+ * Incoming stream as measured by the backend ('ingest_stats' event / get_ingest_stats)
+ */
+export interface IngestStats {
+  videoCodec?: string;
+  videoProfile?: string;
+  videoLevel?: string;
+  width?: number;
+  height?: number;
+  fps?: number;
+  /** Seconds between the last two key frames */
+  keyframeIntervalSecs?: number;
+  videoBitrateKbps?: number;
+  audioCodec?: string;
+  audioProfile?: string;
+  audioSampleRate?: number;
+  audioChannels?: number;
+  audioBitrateKbps?: number;
+  /** Passthrough targets that cannot take what is arriving */
+  warnings: IngestWarning[];
+}
+
+/**
+ * A passthrough target that will likely reject the ingest as is
+ */
+export interface IngestWarning {
+  groupId: string;
+  targetId: string;
+  message: string;
+}
+
+/**
+ * A target whose encode exceeds its platform's limits
+ * (returned by save_profile, emitted as 'platform_warnings' when groups start)
+ */
+export interface PlatformWarning {
+  groupId: string;
+  targetId: string;
+  message: string;
+}
+
 /**
  * Overall stream statistics
  */
@@ -56,3 +161,38 @@ export interface Encoders {
   video: string[];
   audio: string[];
 }
+
+export type HealthLevel = 'good' | 'degraded' | 'critical';
+
+export interface HealthReason {
+  metric: 'speed' | 'droppedFrames' | 'bitrate';
+  level: HealthLevel;
+  value: number; // speed factor, % of frames dropped or % of configured bitrate
+  threshold: number;
+  message: string;
+}
+
+/**
+ * Health rating of an output group (payload of the stream_health event)
+ */
+export interface StreamHealth {
+  groupId: string;
+  level: HealthLevel;
+  previous: HealthLevel;
+  reasons: HealthReason[]; // empty when good
+  at: number; // Unix ms
+}
+
+/**
+ * Resource usage of an FFmpeg child process (Linux only)
+ */
+export interface FFmpegProcessStats {
+  role: 'relay' | 'group' | 'target' | 'slate';
+  id: string; // group or target ID; empty for the relay and slate
+  pid: number;
+  cpuPercent: number; // since the previous reading; 100 = one core
+  cpuSeconds: number;
+  rssBytes: number;
+  threads: number;
+  nice: number;
+}
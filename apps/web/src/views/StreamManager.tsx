@@ -15,6 +15,7 @@ import { formatUptime, formatBitrate } from '@/hooks/useStreamStats';
 import { toast } from '@/hooks/useToast';
 import type { View } from '@/App';
 import type { OutputGroup as OutputGroupType, StreamTarget } from '@/types/profile';
+import { buildIncomingUrl } from '@/types/profile';
 import { validateStreamConfig, displayValidationIssues } from '@/lib/streamValidation';
 
 interface StreamManagerProps {
@@ -84,7 +85,7 @@ export function StreamManager({ onNavigate }: StreamManagerProps) {
 
       // Validation passed, start streaming
       // Build incoming URL from structured input
-      const incomingUrl = `rtmp://${current.input.bindAddress}:${current.input.port}/${current.input.application}`;
+      const incomingUrl = buildIncomingUrl(current.input);
       await startAllGroups(current.outputGroups, incomingUrl);
       toast.success(t('toast.streamStarted'));
     } catch (err) {
@@ -127,7 +128,7 @@ export function StreamManager({ onNavigate }: StreamManagerProps) {
       const group = current?.outputGroups.find((g) => g.id === groupId);
       if (group && current) {
         try {
-          const incomingUrl = `rtmp://${current.input.bindAddress}:${current.input.port}/${current.input.application}`;
+          const incomingUrl = buildIncomingUrl(current.input);
           await startGroup(group, incomingUrl);
           toast.success(t('toast.groupStarted'));
         } catch (err) {
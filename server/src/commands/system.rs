@@ -2,7 +2,9 @@
 // Handles system-level operations like encoder detection
 
 use std::process::Command;
-use crate::models::Encoders;
+use crate::models::{BandwidthStep, Encoders, IngestStats, OutputGroup, TargetProtocol};
+use crate::services::parse_bitrate_kbps;
+use crate::services::rtmp::{probe_rtmp_publish, RtmpStageResult};
 
 // Windows: Hide console windows for spawned processes
 #[cfg(windows)]
@@ -294,156 +296,393 @@ pub struct RtmpTestResult {
     pub message: String,
     /// Time taken in milliseconds
     pub latency_ms: Option<u64>,
+    /// DNS, TCP, TLS, handshake, connect, key and publish results, up to the first failure
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<RtmpStageResult>,
 }
 
-/// Test RTMP target connectivity by attempting a brief connection
+/// Test whether an RTMP(S) target is reachable and accepts the stream key
 ///
-/// This performs:
-/// 1. TCP connectivity test to the RTMP host:port
-/// 2. Brief FFmpeg publish attempt to verify the endpoint accepts streams
-pub fn test_rtmp_target(url: String, stream_key: String) -> Result<RtmpTestResult, String> {
-    use std::net::TcpStream;
-    use std::time::{Duration, Instant};
+/// Runs the native probe: DNS, TCP, TLS for `rtmps://`, the RTMP handshake,
+/// `connect` and the `releaseStream`/`FCPublish` key check, each with a short
+/// timeout. This never goes live. With `publish` the probe also sends `publish`,
+/// which starts a broadcast on platforms that go live on publish (YouTube,
+/// Facebook) and can disrupt a session already live on the key.
+pub async fn test_rtmp_target(url: String, stream_key: String, publish: bool) -> Result<RtmpTestResult, String> {
+    use std::time::Instant;
 
     let start = Instant::now();
+    let stages = probe_rtmp_publish(&url, &stream_key, publish).await?;
+    let latency_ms = Some(start.elapsed().as_millis() as u64);
+
+    let failed = stages.iter().find(|stage| !stage.success);
+    let message = match (failed, stages.last()) {
+        (Some(stage), _) => stage.message.clone(),
+        (None, Some(stage)) => stage.message.clone(),
+        (None, None) => "Connection failed - unknown error".to_string(),
+    };
+    if let Some(stage) = failed {
+        log::info!("RTMP target test failed at {:?}: {}", stage.stage, stage.message);
+    }
+
+    Ok(RtmpTestResult {
+        success: failed.is_none() && !stages.is_empty(),
+        message,
+        latency_ms,
+        stages,
+    })
+}
+
+/// Lavfi test pattern and silent audio inputs for test publishes
+///
+/// Inputs are read at their native rate (`-re`), so output is paced like a live
+/// source instead of being pushed as fast as the encoder allows.
+fn add_test_pattern_inputs(cmd: &mut Command, size: &str, duration_secs: u32) {
+    let video = format!("testsrc=duration={duration_secs}:size={size}:rate=30");
+    for input in [video.as_str(), "anullsrc=r=44100:cl=stereo"] {
+        cmd.args(["-re", "-f", "lavfi", "-i", input]);
+    }
+}
+
+/// Turn FFmpeg's stderr from a failed RTMP publish into a user-friendly message
+fn describe_publish_error(stderr: &str) -> String {
+    if stderr.contains("Connection refused") {
+        "Connection refused - server not accepting connections".to_string()
+    } else if stderr.contains("Connection timed out") {
+        "Connection timed out - server not responding".to_string()
+    } else if stderr.contains("Server returned 404") || stderr.contains("NetStream.Publish.BadName") {
+        "Stream key rejected - check your stream key".to_string()
+    } else if stderr.contains("Authorization") || stderr.contains("auth") || stderr.contains("401") {
+        "Authentication failed - invalid stream key".to_string()
+    } else if stderr.contains("NetConnection.Connect.Rejected") {
+        "Connection rejected by server - may need authentication".to_string()
+    } else if stderr.contains("Already publishing") {
+        // This actually means the key is valid, someone is already using it
+        "Stream key is valid but already in use".to_string()
+    } else if stderr.is_empty() {
+        "Connection failed - unknown error".to_string()
+    } else {
+        // Return a truncated error for other cases
+        let truncated: String = stderr.chars().take(200).collect();
+        format!("Connection failed: {}", truncated.trim())
+    }
+}
+
+/// Bitrates the upload bandwidth test steps through, in kbps (video + audio)
+const BANDWIDTH_STEPS_KBPS: [u64; 8] = [1000, 2500, 4000, 6000, 8000, 12000, 20000, 30000];
+
+/// How long each bandwidth step publishes
+const BANDWIDTH_STEP_SECS: u32 = 8;
 
-    // Parse the RTMP URL to extract host and port
-    let (host, port) = parse_rtmp_url(&url)?;
+/// Audio share of each bandwidth step
+const BANDWIDTH_AUDIO_KBPS: u64 = 128;
 
-    // Step 1: TCP connectivity test (fast check)
-    let tcp_timeout = Duration::from_secs(5);
-    let addr = format!("{host}:{port}");
+/// Share of a step's bitrate that must get out in real time for it to count as sustained
+const BANDWIDTH_SUSTAINED_RATIO: f64 = 0.9;
 
-    match TcpStream::connect_timeout(
-        &addr.parse().map_err(|e| format!("Invalid address {addr}: {e}"))?,
-        tcp_timeout,
-    ) {
-        Ok(_) => {
-            log::info!("TCP connection to {addr} successful");
+/// Extra time a step may take beyond its publish length before FFmpeg is killed
+const BANDWIDTH_STEP_GRACE: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Socket read/write timeout for bandwidth steps, in microseconds
+const BANDWIDTH_RW_TIMEOUT_US: &str = "5000000";
+
+/// Measure sustainable upload by publishing a constant-bitrate test pattern at rising bitrates
+///
+/// `full_url` must already contain the stream key. Steps above `max_kbps` are skipped
+/// and the test stops at the first step the destination cannot take in real time.
+/// `on_step` is called as each step finishes. Fails only if the first step cannot publish.
+pub fn test_upload_bandwidth<F>(full_url: &str, max_kbps: u64, mut on_step: F) -> Result<Vec<BandwidthStep>, String>
+where
+    F: FnMut(&BandwidthStep),
+{
+    let ffmpeg_path = find_ffmpeg();
+    let max_kbps = max_kbps.max(BANDWIDTH_STEPS_KBPS[0]);
+    let mut steps = Vec::new();
+
+    for bitrate_kbps in BANDWIDTH_STEPS_KBPS.into_iter().filter(|kbps| *kbps <= max_kbps) {
+        let step = run_bandwidth_step(&ffmpeg_path, full_url, bitrate_kbps)?;
+        if steps.is_empty() && step.measured_kbps == 0 {
+            if let Some(error) = step.error {
+                return Err(error);
+            }
         }
-        Err(e) => {
-            return Ok(RtmpTestResult {
-                success: false,
-                message: format!("Cannot reach {addr} - {e}"),
-                latency_ms: Some(start.elapsed().as_millis() as u64),
-            });
+        on_step(&step);
+        let sustained = step.sustained;
+        steps.push(step);
+        if !sustained {
+            break;
         }
     }
+    Ok(steps)
+}
 
-    // Step 2: FFmpeg publish test
-    // Generate a 2-second test pattern and attempt to publish
-    let ffmpeg_path = find_ffmpeg();
-    let full_url = if url.ends_with('/') {
-        format!("{url}{stream_key}")
-    } else {
-        format!("{url}/{stream_key}")
-    };
+/// Publish one bandwidth step and measure the bytes FFmpeg got out per wall-clock second
+fn run_bandwidth_step(ffmpeg_path: &str, full_url: &str, bitrate_kbps: u64) -> Result<BandwidthStep, String> {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
 
-    // Build FFmpeg command for a brief test publish
-    // Using testsrc for video and anullsrc for audio, 2 seconds duration
-    let mut cmd = Command::new(&ffmpeg_path);
+    // Constant bitrate with filler data, since a test pattern would otherwise compress to almost nothing
+    let video_rate = format!("{}k", bitrate_kbps - BANDWIDTH_AUDIO_KBPS);
+    let audio_rate = format!("{BANDWIDTH_AUDIO_KBPS}k");
+    let duration = BANDWIDTH_STEP_SECS.to_string();
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(["-hide_banner", "-loglevel", "error", "-nostats", "-progress", "pipe:1"]);
+    add_test_pattern_inputs(&mut cmd, "1280x720", BANDWIDTH_STEP_SECS);
     cmd.args([
-        "-hide_banner",
-        "-loglevel", "error",
-        // Test video source
-        "-f", "lavfi",
-        "-i", "testsrc=duration=2:size=320x240:rate=30",
-        // Test audio source
-        "-f", "lavfi",
-        "-i", "anullsrc=r=44100:cl=stereo",
-        "-t", "2",
-        // Fast encoding settings
+        "-t", &duration,
         "-c:v", "libx264",
         "-preset", "ultrafast",
         "-tune", "zerolatency",
-        "-b:v", "500k",
+        "-g", "60",
+        "-b:v", &video_rate,
+        "-minrate", &video_rate,
+        "-maxrate", &video_rate,
+        "-bufsize", &video_rate,
+        "-x264-params", "nal-hrd=cbr",
         "-c:a", "aac",
-        "-b:a", "64k",
-        // Output format
+        "-b:a", &audio_rate,
+        "-rw_timeout", BANDWIDTH_RW_TIMEOUT_US,
         "-f", "flv",
-        &full_url,
+        full_url,
     ]);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
     #[cfg(windows)]
     cmd.creation_flags(CREATE_NO_WINDOW);
 
-    // Set a timeout for the FFmpeg process
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to run FFmpeg: {e}"))?;
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to run FFmpeg: {e}"))?;
+
+    // `-progress` reports the bytes written so far about twice a second
+    let stdout = child.stdout.take();
+    let reader = std::thread::spawn(move || {
+        let mut first: Option<(Instant, u64)> = None;
+        let mut last: Option<(Instant, u64)> = None;
+        if let Some(stdout) = stdout {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(size) = parse_progress_total_size(&line) {
+                    let sample = (Instant::now(), size);
+                    first.get_or_insert(sample);
+                    last = Some(sample);
+                }
+            }
+        }
+        (first, last)
+    });
+
+    // A destination that accepts the connection and then stalls must not hang the test
+    let deadline = Instant::now() + Duration::from_secs(u64::from(BANDWIDTH_STEP_SECS)) + BANDWIDTH_STEP_GRACE;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(100)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+        }
+    };
+    let (first, last) = reader.join().unwrap_or((None, None));
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
 
-    let elapsed = start.elapsed().as_millis() as u64;
+    let measured_kbps = match (first, last) {
+        (Some((start, start_bytes)), Some((end, end_bytes))) if end > start => {
+            let seconds = end.duration_since(start).as_secs_f64();
+            (end_bytes.saturating_sub(start_bytes) as f64 * 8.0 / 1000.0 / seconds) as u64
+        }
+        _ => 0,
+    };
+    let error = match status {
+        Some(status) if status.success() => None,
+        Some(_) => Some(describe_publish_error(&stderr)),
+        None => Some("Destination stopped accepting data - step timed out".to_string()),
+    };
+    let sustained = error.is_none()
+        && measured_kbps as f64 >= bitrate_kbps as f64 * BANDWIDTH_SUSTAINED_RATIO;
+
+    Ok(BandwidthStep {
+        bitrate_kbps,
+        measured_kbps,
+        sustained,
+        error,
+    })
+}
 
-    if output.status.success() {
-        Ok(RtmpTestResult {
-            success: true,
-            message: "Connection successful - stream accepted".to_string(),
-            latency_ms: Some(elapsed),
-        })
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Parse common RTMP errors for user-friendly messages
-        let message = if stderr.contains("Connection refused") {
-            "Connection refused - server not accepting connections".to_string()
-        } else if stderr.contains("Connection timed out") {
-            "Connection timed out - server not responding".to_string()
-        } else if stderr.contains("Server returned 404") || stderr.contains("NetStream.Publish.BadName") {
-            "Stream key rejected - check your stream key".to_string()
-        } else if stderr.contains("Authorization") || stderr.contains("auth") || stderr.contains("401") {
-            "Authentication failed - invalid stream key".to_string()
-        } else if stderr.contains("NetConnection.Connect.Rejected") {
-            "Connection rejected by server - may need authentication".to_string()
-        } else if stderr.contains("Already publishing") {
-            // This actually means the key is valid, someone is already using it
-            "Stream key is valid but already in use".to_string()
-        } else if stderr.is_empty() {
-            "Connection failed - unknown error".to_string()
-        } else {
-            // Return a truncated error for other cases
-            let truncated: String = stderr.chars().take(200).collect();
-            format!("Connection failed: {}", truncated.trim())
-        };
+/// Bytes written so far, from a `total_size=` line of FFmpeg's `-progress` output
+fn parse_progress_total_size(line: &str) -> Option<u64> {
+    line.strip_prefix("total_size=")?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|size| *size > 0)
+}
 
-        Ok(RtmpTestResult {
-            success: false,
-            message,
-            latency_ms: Some(elapsed),
+/// Upload the profile needs: each group's bitrate once per network target
+///
+/// Recording and HLS targets are local and not counted. Passthrough video and
+/// audio use the measured ingest bitrate when the ingest is live; otherwise they
+/// count as zero, since their bitrate is not known until the stream arrives.
+pub fn required_upload_kbps(groups: &[OutputGroup], ingest: Option<&IngestStats>) -> u64 {
+    let passthrough_kbps = |copied: Option<f64>| copied.map_or(0, |kbps| kbps.round() as u64);
+    groups
+        .iter()
+        .flat_map(|group| group.stream_targets.iter().map(move |target| (group, target)))
+        .filter(|(_, target)| !matches!(target.protocol, TargetProtocol::Record | TargetProtocol::Hls))
+        .map(|(group, target)| {
+            let video = group.video_for_target(target);
+            let video_kbps = if video.codec.eq_ignore_ascii_case("copy") {
+                passthrough_kbps(ingest.and_then(|stats| stats.video_bitrate_kbps))
+            } else {
+                parse_bitrate_kbps(&video.bitrate)
+            };
+            let audio_kbps = if group.audio.codec.eq_ignore_ascii_case("copy") {
+                passthrough_kbps(ingest.and_then(|stats| stats.audio_bitrate_kbps))
+            } else {
+                parse_bitrate_kbps(&group.audio.bitrate)
+            };
+            video_kbps + audio_kbps
         })
-    }
+        .sum()
 }
 
-/// Parse an RTMP URL to extract host and port
-fn parse_rtmp_url(url: &str) -> Result<(String, u16), String> {
-    let url = url.trim();
+/// Test a WHIP endpoint by performing the SDP offer/answer exchange
+///
+/// Posts a synthetic sendonly offer (Opus + H.264) with the bearer token, expects
+/// `201 Created` with an SDP answer, then deletes the session resource again.
+/// No media is sent; this only verifies the endpoint and token.
+pub async fn test_whip_target(url: String, token: String) -> Result<RtmpTestResult, String> {
+    use std::time::{Duration, Instant};
 
-    // Handle rtmp:// and rtmps:// protocols
-    let (is_secure, rest) = if let Some(rest) = url.strip_prefix("rtmps://") {
-        (true, rest)
-    } else if let Some(rest) = url.strip_prefix("rtmp://") {
-        (false, rest)
-    } else {
-        return Err("Invalid RTMP URL: must start with rtmp:// or rtmps://".to_string());
-    };
+    let endpoint = url::Url::parse(url.trim())
+        .map_err(|e| format!("Invalid WHIP URL: {e}"))?;
+    if endpoint.scheme() != "https" && endpoint.scheme() != "http" {
+        return Err("Invalid WHIP URL: must start with https:// or http://".to_string());
+    }
 
-    // Extract host:port from the path
-    // URL format: rtmp://host:port/app/stream or rtmp://host/app/stream
-    let host_port = rest.split('/').next().unwrap_or(rest);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
 
-    let (host, port) = if host_port.contains(':') {
-        let parts: Vec<&str> = host_port.splitn(2, ':').collect();
-        let port: u16 = parts[1].parse()
-            .map_err(|_| format!("Invalid port in URL: {}", parts[1]))?;
-        (parts[0].to_string(), port)
-    } else {
-        // Default ports
-        let default_port = if is_secure { 443 } else { 1935 };
-        (host_port.to_string(), default_port)
+    let start = Instant::now();
+    let mut request = client
+        .post(endpoint.clone())
+        .header(reqwest::header::CONTENT_TYPE, "application/sdp")
+        .body(build_whip_probe_offer());
+    if !token.is_empty() {
+        request = request.bearer_auth(&token);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let message = if e.is_timeout() {
+                "Connection timed out - server not responding".to_string()
+            } else if e.is_connect() {
+                format!("Cannot reach {} - connection failed", endpoint.host_str().unwrap_or_default())
+            } else {
+                "Request failed - check the WHIP URL".to_string()
+            };
+            return Ok(RtmpTestResult {
+                success: false,
+                message,
+                latency_ms: Some(start.elapsed().as_millis() as u64),
+                stages: Vec::new(),
+            });
+        }
     };
+    let elapsed = start.elapsed().as_millis() as u64;
 
-    if host.is_empty() {
-        return Err("Empty host in RTMP URL".to_string());
-    }
+    let status = response.status();
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| endpoint.join(value).ok());
+    let answer = response.text().await.unwrap_or_default();
+
+    let message = match status.as_u16() {
+        201 if answer.trim_start().starts_with("v=0") => {
+            // Tear the session down so the platform doesn't wait for media
+            if let Some(resource) = location {
+                let mut delete = client.delete(resource);
+                if !token.is_empty() {
+                    delete = delete.bearer_auth(&token);
+                }
+                if let Err(e) = delete.send().await {
+                    log::warn!("Failed to delete WHIP test session: {e}");
+                }
+            }
+            return Ok(RtmpTestResult {
+                success: true,
+                message: "Connection successful - offer accepted".to_string(),
+                latency_ms: Some(elapsed),
+                stages: Vec::new(),
+            });
+        }
+        201 => "Server accepted the offer but returned no SDP answer".to_string(),
+        401 | 403 => "Authentication failed - invalid bearer token".to_string(),
+        404 => "WHIP endpoint not found - check the URL".to_string(),
+        415 => "Server rejected the SDP content type".to_string(),
+        code => format!("Server returned HTTP {code}"),
+    };
+
+    Ok(RtmpTestResult {
+        success: false,
+        message,
+        latency_ms: Some(elapsed),
+        stages: Vec::new(),
+    })
+}
 
-    Ok((host, port))
+/// Build a minimal sendonly SDP offer with one Opus and one H.264 section
+fn build_whip_probe_offer() -> String {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    let session_id: u64 = rng.gen_range(1..u64::MAX / 2);
+    let ufrag: String = (0..8).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+    let pwd: String = (0..24).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+    let fingerprint = (0..32)
+        .map(|_| format!("{:02X}", rng.gen::<u8>()))
+        .collect::<Vec<_>>()
+        .join(":");
+
+    let media_attrs = |mid: u8| {
+        format!(
+            "c=IN IP4 0.0.0.0\r\n\
+             a=ice-ufrag:{ufrag}\r\n\
+             a=ice-pwd:{pwd}\r\n\
+             a=fingerprint:sha-256 {fingerprint}\r\n\
+             a=setup:actpass\r\n\
+             a=mid:{mid}\r\n\
+             a=sendonly\r\n\
+             a=rtcp-mux\r\n"
+        )
+    };
+
+    format!(
+        "v=0\r\n\
+         o=- {session_id} 2 IN IP4 127.0.0.1\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         a=group:BUNDLE 0 1\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         {audio}\
+         a=rtpmap:111 opus/48000/2\r\n\
+         a=fmtp:111 minptime=10;useinbandfec=1\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+         {video}\
+         a=rtpmap:96 H264/90000\r\n\
+         a=fmtp:96 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\n",
+        audio = media_attrs(0),
+        video = media_attrs(1),
+    )
 }
 
 /// Validate a specific FFmpeg path and return version if valid
@@ -490,3 +729,70 @@ pub fn validate_ffmpeg_path(path: String) -> Result<String, String> {
 
     Ok(version_line)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BandwidthTestResult, StreamTarget};
+
+    fn target(id: &str, protocol: TargetProtocol) -> StreamTarget {
+        let mut target: StreamTarget = serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "url": "", "streamKey": ""
+        }))
+        .unwrap();
+        target.protocol = protocol;
+        target
+    }
+
+    #[test]
+    fn test_required_upload_counts_network_targets() {
+        let mut encoded = OutputGroup::new();
+        encoded.video.codec = "libx264".to_string();
+        encoded.video.bitrate = "6000k".to_string();
+        encoded.audio.codec = "aac".to_string();
+        encoded.audio.bitrate = "160k".to_string();
+        encoded.stream_targets = vec![
+            target("twitch", TargetProtocol::Rtmp),
+            target("youtube", TargetProtocol::Rtmp),
+            target("disk", TargetProtocol::Record),
+        ];
+
+        let mut passthrough = OutputGroup::new();
+        passthrough.stream_targets = vec![target("kick", TargetProtocol::Rtmp)];
+        let groups = vec![encoded, passthrough];
+
+        assert_eq!(required_upload_kbps(&groups, None), 2 * 6160);
+
+        let stats = IngestStats {
+            video_bitrate_kbps: Some(4500.4),
+            audio_bitrate_kbps: Some(128.0),
+            ..Default::default()
+        };
+        assert_eq!(required_upload_kbps(&groups, Some(&stats)), 2 * 6160 + 4628);
+    }
+
+    #[test]
+    fn test_bandwidth_result_verdict() {
+        let step = |bitrate_kbps, measured_kbps, sustained| BandwidthStep {
+            bitrate_kbps,
+            measured_kbps,
+            sustained,
+            error: None,
+        };
+
+        let steps = vec![step(1000, 1010, true), step(2500, 2490, true), step(4000, 2900, false)];
+        let result = BandwidthTestResult::new("g", "t", "Twitch", true, steps, 6160);
+        assert_eq!(result.sustainable_kbps, 2490);
+        assert!(!result.at_least);
+        assert!(result.warning.unwrap().contains("sustained only 2490 kbps"));
+
+        let steps = vec![step(1000, 1005, true), step(2500, 2510, true)];
+        let result = BandwidthTestResult::new("g", "t", "Twitch", true, steps, 2000);
+        assert!(result.at_least);
+        assert!(result.warning.is_none());
+
+        assert_eq!(parse_progress_total_size("total_size=52428"), Some(52428));
+        assert_eq!(parse_progress_total_size("total_size=N/A"), None);
+        assert_eq!(parse_progress_total_size("out_time_us=1000"), None);
+    }
+}
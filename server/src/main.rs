@@ -38,13 +38,21 @@ use tower_http::{
     set_header::SetResponseHeaderLayer,
 };
 
-use spiritstream_server::commands::{get_encoders, test_ffmpeg, test_rtmp_target, validate_ffmpeg_path};
-use spiritstream_server::models::{ChatConfig, ChatCredentials, ChatMessage, ChatPlatform, ChatSendResult, ChatSettings, ObsIntegrationDirection, OutputGroup, Profile, ProfileSettings, RtmpInput, Settings, TwitchAuth, YouTubeAuth};
+use spiritstream_server::commands::{
+    get_encoders, required_upload_kbps, test_ffmpeg, test_rtmp_target, test_upload_bandwidth,
+    test_whip_target, validate_ffmpeg_path,
+};
+use spiritstream_server::models::{BandwidthTestResult, HealthLevel, StreamHealth, StreamStats, ChatConfig, ChatCredentials, ChatMessage, ChatPlatform, ChatSendResult, ChatSettings, IngestProbeResult, IngestServerProbe, ObsIntegrationDirection, OutputGroup, PreflightCheck, PreflightReport, Profile, ProfileSettings, RtmpInput, ScheduleEntry, Settings, TargetProtocol, TwitchAuth, YouTubeAuth};
 use spiritstream_server::services::{
-    prune_logs, read_recent_logs, validate_extension, validate_path_within_any,
+    prune_logs, read_recent_logs, validate_extension, validate_path_within_any, SessionHistory,
+    MetricsCollector, MetricsSnapshot, METRICS_CONTENT_TYPE,
+    HealthMonitor, expected_bitrate_kbps,
     ChatManager, DiscordWebhookService, Encryption, EventSink, FFmpegDownloader, FFmpegHandler,
     OAuthCallback, OAuthCallbackServer, OAuthConfig, OAuthService, ObsConfig, ObsWebSocketHandler,
-    ProfileManager, SettingsManager, ThemeManager,
+    PlatformRegistry, ProfileManager, ScheduleAction, Scheduler, SettingsManager, ThemeManager,
+    whip_standin_router, hls_content_type, hls_playlist_name, prune_hls_dir, resolve_hls_file,
+    run_scheduler, check_encoders, check_listen_port, decrypt_target_keys, resolve_env_key,
+    fastest_server, probe_ingest_servers,
 };
 
 // ============================================================================
@@ -54,6 +62,8 @@ use spiritstream_server::services::{
 const AUTH_COOKIE_NAME: &str = "spiritstream_session";
 const COOKIE_MAX_AGE_SECS: i64 = 7 * 24 * 60 * 60; // 7 days
 const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 300;
+const HLS_PRUNE_INTERVAL_SECS: u64 = 30;
+const HLS_PRUNE_MAX_AGE_SECS: u64 = 600;
 
 // ============================================================================
 // Event System
@@ -105,6 +115,10 @@ struct AppState {
     discord_service: Arc<DiscordWebhookService>,
     chat_manager: Arc<ChatManager>,
     oauth_service: Arc<OAuthService>,
+    scheduler: Arc<Scheduler>,
+    session_history: Arc<SessionHistory>,
+    metrics: Arc<MetricsCollector>,
+    health_monitor: Arc<HealthMonitor>,
     event_bus: EventBus,
     log_dir: PathBuf,
     app_data_dir: PathBuf,
@@ -242,7 +256,7 @@ fn redact_payload(value: &Value) -> Value {
     const REDACT_KEYS: &[&str] = &[
         "token", "key", "password", "secret", "oauth", "accessToken",
         "refreshToken", "oauthToken", "apiKey", "access_token", "refresh_token",
-        "session_token", "webhookUrl",
+        "session_token", "webhookUrl", "passphrase",
     ];
 
     match value {
@@ -267,6 +281,10 @@ fn redact_payload(value: &Value) -> Value {
             Value::Object(redacted)
         }
         Value::Array(arr) => Value::Array(arr.iter().map(redact_payload).collect()),
+        // SRT URLs (e.g. incomingUrl) carry the passphrase in the query string
+        Value::String(s) if s.starts_with("srt://") => {
+            Value::String(PlatformRegistry::generic_redact(s))
+        }
         other => other.clone(),
     }
 }
@@ -420,6 +438,13 @@ async fn set_active_profile(state: &AppState, profile: &Profile) {
         let mut guard = state.active_profile_name.lock().await;
         *guard = Some(profile.name.clone());
     }
+    state.ffmpeg_handler.set_profile_name(Some(profile.name.clone()));
+    state.ffmpeg_handler.set_reconnect_settings(&profile.settings.reconnect);
+    state.health_monitor.set_settings(&profile.settings.health);
+    state.ffmpeg_handler.set_slate_settings(&profile.input.slate);
+    if let Err(e) = state.ffmpeg_handler.set_ingest_access(&profile.input) {
+        log::warn!("Ignoring invalid ingest access settings: {e}");
+    }
     {
         let mut guard = state.active_profile_settings.lock().await;
         *guard = Some(profile.settings.clone());
@@ -468,6 +493,8 @@ async fn get_active_profile_settings(state: &AppState) -> Option<ProfileSettings
 }
 
 async fn set_active_profile_settings_only(state: &AppState, settings: ProfileSettings) {
+    state.ffmpeg_handler.set_reconnect_settings(&settings.reconnect);
+    state.health_monitor.set_settings(&settings.health);
     let mut guard = state.active_profile_settings.lock().await;
     *guard = Some(settings);
 }
@@ -1259,6 +1286,25 @@ async fn health() -> impl IntoResponse {
     Json(json!({ "ok": true }))
 }
 
+/// Prometheus scrape endpoint for streaming, chat, OBS and FFmpeg process metrics
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let obs = state.obs_handler.get_state().await;
+    let snapshot = MetricsSnapshot {
+        active_group_ids: state.ffmpeg_handler.get_active_group_ids(),
+        chat: state.chat_manager.get_status().await,
+        obs_connection: obs.connection_status,
+        obs_stream: obs.stream_status,
+        relay_running: state.ffmpeg_handler.is_relay_running(),
+        publisher_connected: state.ffmpeg_handler.ingest_status().is_some(),
+        slate_on_air: state.ffmpeg_handler.is_slate_on_air(),
+        processes: state.ffmpeg_handler.process_stats(),
+    };
+    (
+        [(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+        state.metrics.render(&snapshot),
+    )
+}
+
 /// Readiness check - verifies critical services are functional
     async fn ready(State(state): State<AppState>) -> impl IntoResponse {
         #[derive(Debug, Serialize)]
@@ -1383,6 +1429,632 @@ fn system_bin_paths() -> Vec<PathBuf> {
     ]
 }
 
+/// GET /hls/:target_id/:file - Serve local HLS output (authenticated)
+async fn hls_file(
+    State(state): State<AppState>,
+    Path((target_id, file)): Path<(String, String)>,
+) -> Response {
+    serve_hls_file(&state, &target_id, &file, false).await
+}
+
+/// GET /hls-share/:token/:target_id/:file - Serve local HLS output with a share token
+/// The token is part of the path so relative segment URLs in the playlist keep it
+async fn hls_share_file(
+    State(state): State<AppState>,
+    Path((token, target_id, file)): Path<(String, String, String)>,
+) -> Response {
+    let authorized = state
+        .ffmpeg_handler
+        .hls_share_token(&target_id)
+        .is_some_and(|expected| verify_token(&expected, &token));
+    if !authorized {
+        // Same response as a missing file so valid target IDs aren't revealed
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    serve_hls_file(&state, &target_id, &file, true).await
+}
+
+async fn serve_hls_file(state: &AppState, target_id: &str, file: &str, shared: bool) -> Response {
+    let Some(path) = resolve_hls_file(state.ffmpeg_handler.hls_dir(), target_id, file) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let content_type = hls_content_type(file).unwrap_or("application/octet-stream");
+
+    // Playlists change every segment; segments are immutable once listed
+    let cache_control = if file.ends_with(".m3u8") || file.ends_with(".mpd") {
+        "no-cache"
+    } else {
+        "max-age=60"
+    };
+
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => {
+            let mut response = (
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::CACHE_CONTROL, cache_control),
+                ],
+                bytes,
+            )
+                .into_response();
+            // Shared streams are meant to be embedded in players on other origins
+            if shared {
+                response.headers_mut().insert(
+                    header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                    HeaderValue::from_static("*"),
+                );
+            }
+            response
+        }
+        // Segments can be deleted between listing and fetching
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Background task to prune HLS output left behind by stopped or crashed groups
+fn start_hls_prune_task(state: &AppState) {
+    let hls_dir = state.ffmpeg_handler.hls_dir().to_path_buf();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(HLS_PRUNE_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let dir = hls_dir.clone();
+            let max_age = std::time::Duration::from_secs(HLS_PRUNE_MAX_AGE_SECS);
+            let _ = tokio::task::spawn_blocking(move || prune_hls_dir(&dir, max_age)).await;
+        }
+    });
+}
+
+/// Run due schedule entries against the stream and chat services
+fn start_scheduler_task(state: &AppState) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(run_scheduler(state.scheduler.clone(), tx));
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        while let Some(action) = rx.recv().await {
+            match action {
+                ScheduleAction::Start { entry, occurrence, resumed } => {
+                    if resumed {
+                        log::info!("Resuming scheduled stream for profile '{}' after restart", entry.profile_name);
+                    }
+                    match start_scheduled_stream(&state, &entry).await {
+                        Ok(group_ids) => state.scheduler.report_started(&entry.id, occurrence, group_ids),
+                        Err(e) => state.scheduler.report_failed(&entry.id, occurrence, e),
+                    }
+                }
+                ScheduleAction::Stop { entry } => stop_scheduled_stream(&state, &entry),
+            }
+        }
+    });
+}
+
+/// Feed emitted stream stats and events into the session history, metrics and health monitor
+fn start_event_recorder_task(state: &AppState) {
+    let state = state.clone();
+    let mut rx = state.event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(message) => {
+                    state.session_history.observe(&message.event, &message.payload);
+                    state.session_history.record_group_end(&message.event, &message.payload, || {
+                        groups_left(&state, &message.payload)
+                    });
+                    state.metrics.observe(&message.event, &message.payload);
+                    observe_health(&state, &message.event, &message.payload);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Whether a session should stay open after the group in `payload` stopped by itself
+///
+/// A group that is no longer active was stopped (or is being restarted) by a
+/// command, which takes care of the session itself.
+fn groups_left(state: &AppState, payload: &Value) -> bool {
+    let group_id = payload["groupId"].as_str().or_else(|| payload.as_str()).unwrap_or_default();
+    !state.ffmpeg_handler.is_group_active(group_id)
+        || state.ffmpeg_handler.active_count() > 0
+        || state.ffmpeg_handler.has_pending_reconnects()
+}
+
+/// Rate a group's stats and announce level changes as `stream_health` events
+fn observe_health(state: &AppState, event: &str, payload: &Value) {
+    match event {
+        "stream_stats" => {
+            let Ok(stats) = serde_json::from_value::<StreamStats>(payload.clone()) else {
+                return;
+            };
+            let group = state.ffmpeg_handler.active_group(&stats.group_id);
+            let expected_kbps = group.as_ref().and_then(expected_bitrate_kbps);
+            if let Some(health) = state.health_monitor.observe(&stats, expected_kbps) {
+                log::info!("Output group {} health: {:?} -> {:?}", health.group_id, health.previous, health.level);
+                state.event_bus.emit("stream_health", json!(health));
+                let group_name = group.map(|group| group.name).unwrap_or_else(|| health.group_id.clone());
+                tokio::spawn(run_health_alerts(state.clone(), health, group_name));
+            }
+        }
+        "stream_ended" => {
+            if let Some(group_id) = payload.as_str() {
+                state.health_monitor.forget(group_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Post a health change to Discord and switch OBS scenes, as the profile's health settings ask
+async fn run_health_alerts(state: AppState, health: StreamHealth, group_name: String) {
+    let Some(settings) = get_active_profile_settings(&state).await else {
+        return;
+    };
+
+    let discord = &settings.discord;
+    if settings.health.discord_alerts
+        && discord.webhook_enabled
+        && !discord.webhook_url.is_empty()
+        && state.health_monitor.take_alert(&health.group_id)
+    {
+        let message = match health.level {
+            HealthLevel::Good => format!("✅ **{group_name}** is healthy again"),
+            level => {
+                let reasons: Vec<&str> = health.reasons.iter().map(|reason| reason.message.as_str()).collect();
+                let label = if level == HealthLevel::Critical { "🔴 critical" } else { "🟠 degraded" };
+                format!("**{group_name}** is {label}: {}", reasons.join("; "))
+            }
+        };
+        if let Err(e) = state.discord_service.send_message(&discord.webhook_url, &message).await {
+            log::warn!("Failed to post health alert to Discord: {e}");
+        }
+    }
+
+    let critical_scene = settings.health.obs_critical_scene.trim();
+    let recovered_scene = settings.health.obs_recovered_scene.trim();
+    if health.level == HealthLevel::Critical && !critical_scene.is_empty() {
+        match state.obs_handler.set_current_scene(critical_scene).await {
+            Ok(()) => state.health_monitor.set_critical_scene_shown(true),
+            Err(e) => log::warn!("Health alert could not switch OBS scene: {e}"),
+        }
+    } else if health.level == HealthLevel::Good
+        && state.health_monitor.all_good()
+        && state.health_monitor.critical_scene_shown()
+        && !recovered_scene.is_empty()
+    {
+        match state.obs_handler.set_current_scene(recovered_scene).await {
+            Ok(()) => state.health_monitor.set_critical_scene_shown(false),
+            Err(e) => log::warn!("Health alert could not switch OBS scene back: {e}"),
+        }
+    }
+}
+
+/// Start a schedule entry's output groups, making its profile active
+///
+/// Groups that are already running are left alone. Returns the groups started.
+/// Encrypted profiles cannot be started unattended (their password is not stored).
+async fn start_scheduled_stream(state: &AppState, entry: &ScheduleEntry) -> Result<Vec<String>, String> {
+    let was_streaming = state.ffmpeg_handler.active_count() > 0;
+    if was_streaming {
+        let active = get_active_profile_name(state).await.unwrap_or_default();
+        if active != entry.profile_name {
+            return Err(format!("Another profile ('{active}') is already live"));
+        }
+    }
+
+    let profile = state
+        .profile_manager
+        .load_with_key_decryption(&entry.profile_name, None)
+        .await?;
+    let groups: Vec<OutputGroup> = profile
+        .output_groups
+        .iter()
+        .filter(|group| entry.group_ids.is_empty() || entry.group_ids.contains(&group.id))
+        .filter(|group| !group.stream_targets.is_empty())
+        .cloned()
+        .collect();
+    if groups.is_empty() {
+        return Err("No output groups with stream targets to start".to_string());
+    }
+    let groups: Vec<OutputGroup> = groups
+        .into_iter()
+        .filter(|group| !state.ffmpeg_handler.is_streaming(&group.id))
+        .collect();
+    if groups.is_empty() {
+        log::info!("Scheduled groups for profile '{}' are already live", entry.profile_name);
+        return Ok(Vec::new());
+    }
+
+    if !was_streaming {
+        set_active_profile(state, &profile).await;
+    }
+    let incoming_url = profile.input.listen_url();
+    let event_sink: Arc<dyn EventSink> = Arc::new(state.event_bus.clone());
+    if was_streaming {
+        for group in &groups {
+            state.ffmpeg_handler.start(group, &incoming_url, Arc::clone(&event_sink))?;
+        }
+    } else {
+        state.ffmpeg_handler.start_all(&groups, &incoming_url, event_sink)?;
+        state.chat_manager.start_log_session();
+    }
+    state.session_history.record_start(&entry.profile_name, &groups);
+    tokio::spawn(auto_connect_chat_platforms(state.clone()));
+    Ok(groups.into_iter().map(|group| group.id).collect())
+}
+
+/// Stop the output groups a schedule entry started once its duration has elapsed
+///
+/// Groups started by hand, including ones the entry found already running, keep streaming.
+fn stop_scheduled_stream(state: &AppState, entry: &ScheduleEntry) {
+    log::info!("Scheduled stream for profile '{}' is over, stopping", entry.profile_name);
+    let result = entry
+        .started_group_ids
+        .iter()
+        .filter(|group_id| state.ffmpeg_handler.is_streaming(group_id))
+        .try_for_each(|group_id| state.ffmpeg_handler.stop(group_id));
+    if let Err(e) = result {
+        log::warn!("Failed to stop scheduled stream: {e}");
+    }
+
+    if state.ffmpeg_handler.active_count() == 0 {
+        state.chat_manager.end_log_session();
+        state.session_history.record_stop();
+        tokio::spawn(auto_disconnect_chat_platforms(state.chat_manager.clone(), state.event_bus.clone()));
+    }
+}
+
+/// Run the go-live checklist for a profile
+///
+/// Checks that fail do not stop the others; only a profile that cannot be loaded is an error.
+async fn run_preflight(
+    state: &AppState,
+    profile_name: &str,
+    password: Option<&str>,
+) -> Result<PreflightReport, String> {
+    let mut checks = Vec::new();
+
+    // Stream keys and other secrets
+    let (mut profile, settings_decrypted) = match state
+        .profile_manager
+        .load_with_key_decryption(profile_name, password)
+        .await
+    {
+        Ok(profile) => {
+            checks.push(PreflightCheck::pass("secrets", "Stream keys", "All stream keys and secrets decrypt"));
+            (profile, true)
+        }
+        Err(e) => {
+            let mut profile = state.profile_manager.load(profile_name, password).await?;
+            let key_checks = decrypt_target_keys(&mut profile, &state.app_data_dir);
+            if key_checks.is_empty() {
+                checks.push(PreflightCheck::fail("secrets", "Profile secrets", e));
+            }
+            checks.extend(key_checks);
+            (profile, false)
+        }
+    };
+
+    // FFmpeg and encoders
+    let ffmpeg = tokio::task::spawn_blocking(test_ffmpeg)
+        .await
+        .map_err(|e| format!("FFmpeg check failed: {e}"))?;
+    let ffmpeg_ok = ffmpeg.is_ok();
+    checks.push(match ffmpeg {
+        Ok(version) => PreflightCheck::pass("ffmpeg", "FFmpeg", version),
+        Err(e) => PreflightCheck::fail("ffmpeg", "FFmpeg", e),
+    });
+    if ffmpeg_ok {
+        match tokio::task::spawn_blocking(get_encoders).await {
+            Ok(Ok(encoders)) => checks.extend(check_encoders(&profile.output_groups, &encoders)),
+            Ok(Err(e)) => checks.push(PreflightCheck::fail("encoders", "Encoders", e)),
+            Err(e) => checks.push(PreflightCheck::fail("encoders", "Encoders", e.to_string())),
+        }
+    }
+
+    // Ingest port
+    let held_by_ingest = state.ffmpeg_handler.ingest_listen_port() == Some(profile.input.port);
+    checks.push(check_listen_port(&profile.input, held_by_ingest));
+
+    // Targets
+    let registry = state.ffmpeg_handler.platform_registry().clone();
+    checks.extend(preflight_targets(&profile.output_groups, &registry).await);
+    checks.extend(
+        state
+            .ffmpeg_handler
+            .platform_warnings(&profile.output_groups)
+            .into_iter()
+            .map(|warning| {
+                PreflightCheck::warn("platform_limits", "Platform limits", warning.message)
+                    .for_target(&warning.group_id, &warning.target_id)
+            }),
+    );
+
+    if settings_decrypted {
+        checks.extend(preflight_oauth(state, &mut profile, password).await);
+
+        if profile.settings.obs.direction != ObsIntegrationDirection::Disabled {
+            checks.push(if state.obs_handler.is_connected().await {
+                PreflightCheck::pass("obs", "OBS", "Connected")
+            } else {
+                PreflightCheck::fail("obs", "OBS", "OBS integration is enabled but OBS is not connected")
+            });
+        }
+
+        let discord = &profile.settings.discord;
+        if discord.webhook_enabled {
+            let result = state.discord_service.validate_webhook(&discord.webhook_url).await;
+            checks.push(if result.success {
+                PreflightCheck::pass("discord", "Discord webhook", result.message)
+            } else {
+                PreflightCheck::fail("discord", "Discord webhook", result.message)
+            });
+        }
+    }
+
+    Ok(PreflightReport::new(profile_name, checks))
+}
+
+/// Test each remote target's endpoint, all at once
+async fn preflight_targets(
+    groups: &[OutputGroup],
+    registry: &PlatformRegistry,
+) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    let mut tests = tokio::task::JoinSet::new();
+
+    for (group, target) in groups
+        .iter()
+        .flat_map(|group| group.stream_targets.iter().map(move |target| (group, target)))
+    {
+        let label = format!("Target: {}", target.name);
+        let with_target = |check: PreflightCheck| check.for_target(&group.id, &target.id);
+        match target.protocol {
+            TargetProtocol::Record | TargetProtocol::Hls => continue,
+            TargetProtocol::Srt | TargetProtocol::Rist => {
+                checks.push(with_target(PreflightCheck::warn(
+                    "target",
+                    label,
+                    "Not tested; SRT and RIST targets are only known to work once connected",
+                )));
+                continue;
+            }
+            TargetProtocol::Rtmp | TargetProtocol::Whip => {}
+        }
+
+        let key = match resolve_env_key(&target.stream_key) {
+            Ok(key) if !key.is_empty() => key,
+            Ok(_) => {
+                checks.push(with_target(PreflightCheck::fail("target", label, "No stream key")));
+                continue;
+            }
+            Err(e) => {
+                checks.push(with_target(PreflightCheck::fail("target", label, e)));
+                continue;
+            }
+        };
+
+        let url = registry.normalize_url(&target.service, &target.url);
+        let protocol = target.protocol;
+        let ids = (group.id.clone(), target.id.clone());
+        tests.spawn(async move {
+            let result = match protocol {
+                TargetProtocol::Whip => test_whip_target(url, key).await,
+                _ => test_rtmp_target(url, key, false).await,
+            };
+            let check = match result {
+                Ok(result) if result.success => PreflightCheck::pass("target", label, result.message),
+                Ok(result) => PreflightCheck::fail("target", label, result.message),
+                Err(e) => PreflightCheck::fail("target", label, e),
+            };
+            check.for_target(&ids.0, &ids.1)
+        });
+    }
+
+    while let Some(result) = tests.join_next().await {
+        if let Ok(check) = result {
+            checks.push(check);
+        }
+    }
+    checks
+}
+
+/// Measure upload capacity against one RTMP target and compare it with the profile's output groups
+///
+/// Twitch targets use `?bandwidthtest=true`, so the test never goes live there;
+/// other targets receive the test pattern like a normal publish and are only
+/// tested when the caller passes `confirm_live`.
+async fn run_bandwidth_test(
+    state: &AppState,
+    profile_name: &str,
+    password: Option<&str>,
+    group_id: &str,
+    target_id: &str,
+    max_kbps: Option<u64>,
+    confirm_live: bool,
+) -> Result<BandwidthTestResult, String> {
+    let profile = state
+        .profile_manager
+        .load_with_key_decryption(profile_name, password)
+        .await?;
+    let target = profile
+        .output_groups
+        .iter()
+        .find(|group| group.id == group_id)
+        .and_then(|group| group.stream_targets.iter().find(|target| target.id == target_id))
+        .ok_or_else(|| format!("Stream target not found: {target_id}"))?;
+    if target.protocol != TargetProtocol::Rtmp {
+        return Err("Bandwidth tests need an RTMP target".to_string());
+    }
+
+    let stream_key = resolve_env_key(&target.stream_key)?;
+    let mut url = state
+        .ffmpeg_handler
+        .platform_registry()
+        .build_url_with_key(&target.service, &target.url, &stream_key);
+    let bandwidth_test_mode = target.service.as_str() == "Twitch";
+    if !bandwidth_test_mode && !confirm_live {
+        return Err(format!(
+            "The bandwidth test publishes a test pattern live on '{}'; confirm to run it on this target",
+            target.name
+        ));
+    }
+    if bandwidth_test_mode {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str("bandwidthtest=true");
+    }
+
+    let event_bus = state.event_bus.clone();
+    let progress_target = target.id.clone();
+    let steps = tokio::task::spawn_blocking(move || {
+        test_upload_bandwidth(&url, max_kbps.unwrap_or(u64::MAX), |step| {
+            event_bus.emit("bandwidth_test_progress", json!({ "targetId": progress_target, "step": step }));
+        })
+    })
+    .await
+    .map_err(|e| format!("Bandwidth test failed: {e}"))??;
+
+    let required_kbps = required_upload_kbps(&profile.output_groups, state.ffmpeg_handler.ingest_stats().as_ref());
+    let result = BandwidthTestResult::new(group_id, target_id, &target.name, bandwidth_test_mode, steps, required_kbps);
+    if let Some(ref warning) = result.warning {
+        log::warn!("Bandwidth test for {}: {warning}", target.name);
+    }
+    Ok(result)
+}
+
+/// Probe each RTMP target's platform ingest servers, optionally switching targets to the fastest
+async fn probe_ingest_targets(
+    state: &AppState,
+    profile_name: &str,
+    password: Option<&str>,
+    apply: bool,
+) -> Result<Vec<IngestProbeResult>, String> {
+    let mut profile = state
+        .profile_manager
+        .load_with_key_decryption(profile_name, password)
+        .await?;
+    let registry = state.ffmpeg_handler.platform_registry();
+
+    // Targets on the same platform share one probe run
+    let mut probed: HashMap<String, Vec<IngestServerProbe>> = HashMap::new();
+    let mut results = Vec::new();
+    for group in profile.output_groups.iter_mut() {
+        for target in group.stream_targets.iter_mut() {
+            if target.protocol != TargetProtocol::Rtmp {
+                continue;
+            }
+            let Some(config) = registry.get(&target.service) else { continue };
+            if config.servers.len() < 2 {
+                continue;
+            }
+            let servers = match probed.get(target.service.as_str()) {
+                Some(servers) => servers.clone(),
+                None => {
+                    let servers = probe_ingest_servers(&config.servers).await;
+                    probed.insert(target.service.as_str().to_string(), servers.clone());
+                    servers
+                }
+            };
+            let recommended = fastest_server(&servers);
+            let applied = match recommended {
+                Some(ref server) if apply && target.url != server.url => {
+                    target.url = server.url.clone();
+                    target.ingest_server = Some(server.name.clone());
+                    true
+                }
+                _ => false,
+            };
+            results.push(IngestProbeResult {
+                group_id: group.id.clone(),
+                target_id: target.id.clone(),
+                target_name: target.name.clone(),
+                servers,
+                recommended,
+                applied,
+            });
+        }
+    }
+
+    if results.iter().any(|result| result.applied) {
+        state
+            .profile_manager
+            .save_with_key_encryption(&profile, password)
+            .await?;
+        let is_active = {
+            let guard = state.active_profile_name.lock().await;
+            guard.as_deref() == Some(profile.name.as_str())
+        };
+        if is_active {
+            set_active_profile(state, &profile).await;
+        }
+        state.event_bus.emit("profile_changed", json!({ "action": "saved", "name": profile.name }));
+    }
+    Ok(results)
+}
+
+/// Make sure linked OAuth accounts have usable tokens, refreshing and saving expired ones
+async fn preflight_oauth(
+    state: &AppState,
+    profile: &mut Profile,
+    password: Option<&str>,
+) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    let mut refreshed = false;
+
+    for (provider, label) in [("twitch", "Twitch account"), ("youtube", "YouTube account")] {
+        let account = match provider {
+            "twitch" => &mut profile.settings.oauth.twitch,
+            _ => &mut profile.settings.oauth.youtube,
+        };
+        if account.access_token.is_empty() {
+            continue;
+        }
+        match ensure_fresh_oauth_token(
+            provider,
+            &account.access_token,
+            &account.refresh_token,
+            account.expires_at,
+            &state.oauth_service,
+        )
+        .await
+        {
+            Ok(fresh) => {
+                if fresh.refreshed {
+                    account.access_token = fresh.access_token;
+                    if let Some(refresh_token) = fresh.refresh_token {
+                        account.refresh_token = refresh_token;
+                    }
+                    account.expires_at = fresh.expires_at;
+                    refreshed = true;
+                }
+                let message = if fresh.refreshed { "Token refreshed" } else { "Token valid" };
+                checks.push(PreflightCheck::pass("oauth", label, message));
+            }
+            Err(e) => checks.push(PreflightCheck::fail("oauth", label, e)),
+        }
+    }
+
+    // Refresh tokens may be single-use, so a refresh has to be saved
+    if refreshed {
+        let active = get_active_profile_name(state).await;
+        let saved = if active.as_deref() == Some(profile.name.as_str()) {
+            persist_active_profile_settings(state, profile.settings.clone()).await
+        } else {
+            state.profile_manager.save_with_key_encryption(profile, password).await
+        };
+        if let Err(e) = saved {
+            log::warn!("Failed to save refreshed OAuth tokens: {e}");
+        }
+    }
+    checks
+}
+
 /// GET /api/files/browse - List directory contents
 /// Query params: path (optional, defaults to home directory)
 async fn files_browse(
@@ -1677,6 +2349,7 @@ const QUIET_COMMANDS: &[&str] = &[
     "obs_is_connected",
     "get_active_stream_count",
     "get_active_group_ids",
+    "get_ffmpeg_process_stats",
 ];
 
 async fn invoke_command(
@@ -1743,7 +2416,7 @@ async fn invoke_command(
                 set_active_profile(state, &profile).await;
             }
             state.event_bus.emit("profile_changed", json!({ "action": "saved", "name": profile.name }));
-            Ok(Value::Null)
+            Ok(json!(state.ffmpeg_handler.platform_warnings(&profile.output_groups)))
         }
         "delete_profile" => {
             let name: String = get_arg(&payload, "name")?;
@@ -1804,6 +2477,20 @@ async fn invoke_command(
             let map = state.profile_manager.ensure_order_indexes().await?;
             Ok(json!(map))
         }
+        "get_schedules" => {
+            let profile_name: Option<String> = get_opt_arg(&payload, "profileName")?;
+            Ok(json!(state.scheduler.list(profile_name.as_deref())))
+        }
+        "save_schedule" => {
+            let schedule: ScheduleEntry = get_arg(&payload, "schedule")?;
+            let saved = state.scheduler.save(schedule)?;
+            Ok(json!(saved))
+        }
+        "delete_schedule" => {
+            let id: String = get_arg(&payload, "id")?;
+            state.scheduler.delete(&id)?;
+            Ok(Value::Null)
+        }
         "start_stream" => {
             let group: OutputGroup = get_arg(&payload, "group")?;
             let incoming_url: String = get_arg(&payload, "incomingUrl")?;
@@ -1812,6 +2499,8 @@ async fn invoke_command(
             let pid = state.ffmpeg_handler.start(&group, &incoming_url, event_sink)?;
             // Reset reconnection state on successful manual start
             state.ffmpeg_handler.reset_reconnection_state(&group.id);
+            let profile_name = get_active_profile_name(state).await.unwrap_or_default();
+            state.session_history.record_start(&profile_name, std::slice::from_ref(&group));
             // Auto-connect chat platforms on first stream start
             if !was_streaming {
                 state.chat_manager.start_log_session();
@@ -1825,6 +2514,8 @@ async fn invoke_command(
             let was_streaming = state.ffmpeg_handler.active_count() > 0;
             let event_sink: Arc<dyn EventSink> = Arc::new(state.event_bus.clone());
             let pids = state.ffmpeg_handler.start_all(&groups, &incoming_url, event_sink)?;
+            let profile_name = get_active_profile_name(state).await.unwrap_or_default();
+            state.session_history.record_start(&profile_name, &groups);
             // Auto-connect chat platforms when streams start
             if !was_streaming {
                 state.chat_manager.start_log_session();
@@ -1838,6 +2529,7 @@ async fn invoke_command(
             // Auto-disconnect chat when no more streams are running
             if state.ffmpeg_handler.active_count() == 0 {
                 state.chat_manager.end_log_session();
+                state.session_history.record_stop();
                 let chat_mgr = state.chat_manager.clone();
                 let bus = state.event_bus.clone();
                 tokio::spawn(auto_disconnect_chat_platforms(chat_mgr, bus));
@@ -1846,7 +2538,10 @@ async fn invoke_command(
         }
         "stop_all_streams" => {
             state.ffmpeg_handler.stop_all()?;
+            // A manual stop ends any scheduled occurrence, so it is not resumed after a restart
+            state.scheduler.clear_running();
             state.chat_manager.end_log_session();
+            state.session_history.record_stop();
             // Auto-disconnect all chat platforms
             let chat_mgr = state.chat_manager.clone();
             let bus = state.event_bus.clone();
@@ -1869,11 +2564,42 @@ async fn invoke_command(
             }))
         }
         "get_active_stream_count" => Ok(json!(state.ffmpeg_handler.active_count())),
+        "get_recordings_dir" => Ok(json!(state.ffmpeg_handler.recordings_dir().to_string_lossy())),
+        "get_hls_streams" => {
+            let streams: Vec<Value> = state
+                .ffmpeg_handler
+                .active_hls_targets()
+                .into_iter()
+                .map(|(group_id, target)| {
+                    let playlist = hls_playlist_name(&target.hls.clone().unwrap_or_default());
+                    let share_url = state
+                        .ffmpeg_handler
+                        .hls_share_token(&target.id)
+                        .map(|token| format!("/hls-share/{token}/{}/{playlist}", target.id));
+                    json!({
+                        "groupId": group_id,
+                        "targetId": target.id,
+                        "name": target.name,
+                        "playlistUrl": format!("/hls/{}/{playlist}", target.id),
+                        "shareUrl": share_url,
+                    })
+                })
+                .collect();
+            Ok(json!(streams))
+        }
         "is_group_streaming" => {
             let group_id: String = get_arg(&payload, "groupId")?;
             Ok(json!(state.ffmpeg_handler.is_streaming(&group_id)))
         }
         "get_active_group_ids" => Ok(json!(state.ffmpeg_handler.get_active_group_ids())),
+        "get_ingest_status" => Ok(json!(state.ffmpeg_handler.ingest_status())),
+        "get_stream_health" => Ok(json!(state.health_monitor.current())),
+        "get_ffmpeg_process_stats" => Ok(json!(state.ffmpeg_handler.process_stats())),
+        "get_ingest_stats" => Ok(json!(state.ffmpeg_handler.ingest_stats())),
+        "get_target_statuses" => {
+            let group_id: Option<String> = get_opt_arg(&payload, "groupId")?;
+            Ok(json!(state.ffmpeg_handler.target_statuses(group_id.as_deref())))
+        }
         "toggle_stream_target" => {
             let target_id: String = get_arg(&payload, "targetId")?;
             let enabled: bool = get_arg(&payload, "enabled")?;
@@ -1885,11 +2611,20 @@ async fn invoke_command(
                 state.ffmpeg_handler.disable_target(&target_id);
             }
             let event_sink: Arc<dyn EventSink> = Arc::new(state.event_bus.clone());
-            let pid = state
-                .ffmpeg_handler
-                .restart_group(&group.id, &group, &incoming_url, event_sink)?;
+            // A live group only starts or stops this target's output
+            let pid = match state.ffmpeg_handler.update_live_targets(&group, Arc::clone(&event_sink))? {
+                Some(pid) => pid,
+                None => state
+                    .ffmpeg_handler
+                    .restart_group(&group.id, &group, &incoming_url, event_sink)?,
+            };
             Ok(json!(pid))
         }
+        "update_live_targets" => {
+            let group: OutputGroup = get_arg(&payload, "group")?;
+            let event_sink: Arc<dyn EventSink> = Arc::new(state.event_bus.clone());
+            Ok(json!(state.ffmpeg_handler.update_live_targets(&group, event_sink)?))
+        }
         "is_target_disabled" => {
             let target_id: String = get_arg(&payload, "targetId")?;
             Ok(json!(state.ffmpeg_handler.is_target_disabled(&target_id)))
@@ -1903,7 +2638,68 @@ async fn invoke_command(
         "test_rtmp_target" => {
             let url: String = get_arg(&payload, "url")?;
             let stream_key: String = get_arg(&payload, "streamKey")?;
-            Ok(json!(test_rtmp_target(url, stream_key)?))
+            let publish: bool = get_opt_arg(&payload, "publish")?.unwrap_or(false);
+            Ok(json!(test_rtmp_target(url, stream_key, publish).await?))
+        }
+        "test_upload_bandwidth" => {
+            let profile_name: String = get_arg(&payload, "profileName")?;
+            let password: Option<String> = get_opt_arg(&payload, "password")?;
+            let group_id: String = get_arg(&payload, "groupId")?;
+            let target_id: String = get_arg(&payload, "targetId")?;
+            let max_kbps: Option<u64> = get_opt_arg(&payload, "maxKbps")?;
+            let confirm_live: bool = get_opt_arg(&payload, "confirmLive")?.unwrap_or(false);
+            Ok(json!(
+                run_bandwidth_test(
+                    state,
+                    &profile_name,
+                    password.as_deref(),
+                    &group_id,
+                    &target_id,
+                    max_kbps,
+                    confirm_live,
+                )
+                .await?
+            ))
+        }
+        "test_whip_target" => {
+            let url: String = get_arg(&payload, "url")?;
+            let token: String = get_arg(&payload, "token")?;
+            Ok(json!(test_whip_target(url, token).await?))
+        }
+        "run_preflight" => {
+            let profile_name: String = get_arg(&payload, "profileName")?;
+            let password: Option<String> = get_opt_arg(&payload, "password")?;
+            Ok(json!(run_preflight(state, &profile_name, password.as_deref()).await?))
+        }
+        "probe_ingest_servers" => {
+            let profile_name: String = get_arg(&payload, "profileName")?;
+            let password: Option<String> = get_opt_arg(&payload, "password")?;
+            let apply: bool = get_opt_arg(&payload, "apply")?.unwrap_or(false);
+            Ok(json!(probe_ingest_targets(state, &profile_name, password.as_deref(), apply).await?))
+        }
+        "get_platforms" => Ok(json!(state.ffmpeg_handler.platform_registry().services())),
+        "reload_platforms" => {
+            let count = state.ffmpeg_handler.platform_registry().reload()?;
+            state.event_bus.emit("platforms_changed", json!({ "count": count }));
+            Ok(json!(count))
+        }
+        "import_platform_services" => {
+            let path: String = get_arg(&payload, "path")?;
+            let path = PathBuf::from(&path);
+
+            // Security: Only read JSON files from allowed directories
+            validate_extension(&path, &["json"])?;
+            let mut allowed_dirs: Vec<&std::path::Path> = vec![state.app_data_dir.as_path()];
+            if let Some(ref home) = state.home_dir {
+                allowed_dirs.push(home.as_path());
+            }
+            validate_path_within_any(&path, &allowed_dirs)?;
+
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read services file: {e}"))?;
+            let count = state.ffmpeg_handler.platform_registry().import_obs_services(&content)?;
+            state.event_bus.emit("platforms_changed", json!({ "count": count }));
+            Ok(json!(count))
         }
         "get_recent_logs" => {
             let max_lines: Option<usize> = get_opt_arg(&payload, "maxLines")?;
@@ -1928,6 +2724,27 @@ async fn invoke_command(
             std::fs::write(&path, content).map_err(|e| format!("Failed to write log file: {e}"))?;
             Ok(Value::Null)
         }
+        "list_stream_sessions" => Ok(json!(state.session_history.list())),
+        "get_stream_session" => {
+            let id: String = get_arg(&payload, "id")?;
+            Ok(json!(state.session_history.get(&id)?))
+        }
+        "export_stream_session" => {
+            let id: String = get_arg(&payload, "id")?;
+            let path: String = get_arg(&payload, "path")?;
+
+            // Security: Only CSV or JSON inside the allowed export directories
+            let export_path = PathBuf::from(&path);
+            validate_extension(&export_path, &["csv", "json"])?;
+            let mut allowed_dirs: Vec<&std::path::Path> = vec![state.app_data_dir.as_path()];
+            if let Some(ref home) = state.home_dir {
+                allowed_dirs.push(home.as_path());
+            }
+            validate_path_within_any(&export_path, &allowed_dirs)?;
+
+            state.session_history.export(&id, &export_path)?;
+            Ok(Value::Null)
+        }
         "get_settings" => Ok(json!(state.settings_manager.load()?)),
         "save_settings" => {
             let new_settings: Settings = get_arg(&payload, "settings")?;
@@ -3153,6 +3970,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize OAuth service
     let oauth_service = Arc::new(OAuthService::new(OAuthConfig::default()));
 
+    // Load stream schedules
+    let scheduler = Arc::new(Scheduler::new(app_data_dir.clone(), Arc::new(event_bus.clone())));
+
+    // Open stream session history
+    let session_history = Arc::new(SessionHistory::new(&app_data_dir));
+
     let state = AppState {
         profile_manager,
         settings_manager,
@@ -3163,6 +3986,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         discord_service,
         chat_manager,
         oauth_service,
+        scheduler,
+        session_history,
+        metrics: Arc::new(MetricsCollector::new()),
+        health_monitor: Arc::new(HealthMonitor::new()),
         event_bus,
         log_dir: log_dir_path,
         app_data_dir,
@@ -3179,6 +4006,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Start chat reconnect task (stream-tied)
     start_chat_reconnect_task(state.clone()).await;
 
+    // Start HLS output pruning task
+    start_hls_prune_task(&state);
+
+    // Reconnect output groups whose FFmpeg process exits unexpectedly
+    state
+        .ffmpeg_handler
+        .start_reconnect_supervisor(Arc::new(state.event_bus.clone()));
+
+    // Start and stop scheduled streams
+    start_scheduler_task(&state);
+
+    // Record stream stats and events into session history, metrics and health ratings
+    start_event_recorder_task(&state);
+
     // Build CORS layer
     let cors = build_cors_layer();
 
@@ -3186,7 +4027,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let csp_value = HeaderValue::from_static(
         "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
          connect-src 'self' ws://localhost:* wss://localhost:* http://localhost:* http://127.0.0.1:*; \
-         img-src 'self' data:; font-src 'self'"
+         img-src 'self' data:; media-src 'self' blob:; font-src 'self'"
     );
 
     // Build router with security layers
@@ -3194,6 +4035,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let protected_routes = Router::new()
         .route("/api/invoke/:command", post(invoke))
         .route("/ws", get(ws_handler))
+        .route("/metrics", get(metrics))
         // File browser endpoints for HTTP mode dialogs
         .route("/api/files/browse", get(files_browse))
         .route("/api/files/home", get(files_home))
@@ -3221,6 +4063,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             csp_value,
         ));
 
+    // HLS output is polled by players every segment, so it bypasses the API rate limiter.
+    // /hls requires the normal session; /hls-share accepts the target's share token instead.
+    let hls_routes = Router::new()
+        .route("/hls/:target_id/:file", get(hls_file))
+        .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
+        .route("/hls-share/:token/:target_id/:file", get(hls_share_file))
+        .with_state(state.clone())
+        .layer(CookieManagerLayer::new());
+    app = app.merge(hls_routes);
+
+    // Optional local WHIP endpoint for testing WHIP targets offline
+    // (uses its own bearer token; an empty value accepts any offer)
+    if let Ok(token) = env::var("SPIRITSTREAM_WHIP_STANDIN_TOKEN") {
+        log::info!("WHIP stand-in endpoint enabled at /whip-standin");
+        app = app.nest("/whip-standin", whip_standin_router(Some(token)));
+    }
+
     // Optionally serve static UI files
     let ui_path = PathBuf::from(ui_dir);
     if ui_enabled && ui_path.exists() {
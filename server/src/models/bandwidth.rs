@@ -0,0 +1,92 @@
+// Bandwidth Test Model
+// Upload capacity measured against a stream target
+
+use serde::{Deserialize, Serialize};
+
+/// One bitrate step of an upload bandwidth test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthStep {
+    /// Bitrate published in this step (video + audio)
+    pub bitrate_kbps: u64,
+
+    /// Throughput that actually got out, over wall-clock time
+    pub measured_kbps: u64,
+
+    /// Whether the destination took the step's bitrate in real time
+    pub sustained: bool,
+
+    /// Why FFmpeg stopped, when it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of an upload bandwidth test, compared with what the profile needs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthTestResult {
+    pub group_id: String,
+    pub target_id: String,
+    pub target_name: String,
+
+    /// Twitch's `bandwidthtest` mode was used, so nothing went live
+    pub bandwidth_test_mode: bool,
+
+    pub steps: Vec<BandwidthStep>,
+
+    /// Highest throughput measured in a sustained step
+    pub sustainable_kbps: u64,
+
+    /// Every step was sustained, so the uplink may take more than measured
+    pub at_least: bool,
+
+    /// Summed bitrate of the profile's network targets
+    pub required_kbps: u64,
+
+    /// Set when the profile needs more than the test showed the uplink can take
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl BandwidthTestResult {
+    /// Summarize the steps and compare them with the profile's required bitrate
+    pub fn new(
+        group_id: &str,
+        target_id: &str,
+        target_name: &str,
+        bandwidth_test_mode: bool,
+        steps: Vec<BandwidthStep>,
+        required_kbps: u64,
+    ) -> Self {
+        let sustainable_kbps = steps
+            .iter()
+            .filter(|step| step.sustained)
+            .map(|step| step.measured_kbps)
+            .max()
+            .unwrap_or(0);
+        let at_least = !steps.is_empty() && steps.iter().all(|step| step.sustained);
+        let warning = if required_kbps <= sustainable_kbps {
+            None
+        } else if at_least {
+            Some(format!(
+                "Output groups need {required_kbps} kbps but the test stopped at {sustainable_kbps} kbps"
+            ))
+        } else {
+            Some(format!(
+                "Output groups need {required_kbps} kbps but the uplink sustained only {sustainable_kbps} kbps"
+            ))
+        };
+
+        Self {
+            group_id: group_id.to_string(),
+            target_id: target_id.to_string(),
+            target_name: target_name.to_string(),
+            bandwidth_test_mode,
+            steps,
+            sustainable_kbps,
+            at_least,
+            required_kbps,
+            warning,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// FFmpeg Error Model
+// Typed classification of why an FFmpeg process failed
+
+use serde::{Deserialize, Serialize};
+
+/// Category of an FFmpeg failure
+///
+/// The serialized name is the stable code sent to clients in `stream_error`
+/// and `stream_gave_up` events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FFmpegErrorKind {
+    /// The server rejected the stream key or credentials
+    AuthRejected,
+    /// The server's hostname could not be resolved
+    DnsFailure,
+    /// Nothing accepted the connection at the server's address
+    ConnectionRefused,
+    /// The server did not answer in time
+    ConnectionTimeout,
+    /// An established connection was reset or dropped
+    ConnectionLost,
+    /// The TLS handshake or certificate check failed
+    TlsError,
+    /// The configured encoder is not in this FFmpeg build or its hardware is missing
+    EncoderUnavailable,
+    /// FFmpeg could not parse an option it was given
+    InvalidArgument,
+    /// No data arrived from the ingest
+    InputTimeout,
+    /// A file could not be written because the disk is full
+    DiskFull,
+    /// Nothing recognizable in FFmpeg's output
+    #[default]
+    Unknown,
+}
+
+impl FFmpegErrorKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            FFmpegErrorKind::AuthRejected => "auth_rejected",
+            FFmpegErrorKind::DnsFailure => "dns_failure",
+            FFmpegErrorKind::ConnectionRefused => "connection_refused",
+            FFmpegErrorKind::ConnectionTimeout => "connection_timeout",
+            FFmpegErrorKind::ConnectionLost => "connection_lost",
+            FFmpegErrorKind::TlsError => "tls_error",
+            FFmpegErrorKind::EncoderUnavailable => "encoder_unavailable",
+            FFmpegErrorKind::InvalidArgument => "invalid_argument",
+            FFmpegErrorKind::InputTimeout => "input_timeout",
+            FFmpegErrorKind::DiskFull => "disk_full",
+            FFmpegErrorKind::Unknown => "unknown",
+        }
+    }
+
+    /// What the user can do about it
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            FFmpegErrorKind::AuthRejected => "Check the stream key and credentials for this target.",
+            FFmpegErrorKind::DnsFailure => "Check the server URL for typos and that this machine can resolve hostnames.",
+            FFmpegErrorKind::ConnectionRefused => "Check the server URL and port, and that the server is accepting streams.",
+            FFmpegErrorKind::ConnectionTimeout => "Check your internet connection and that a firewall is not blocking the server.",
+            FFmpegErrorKind::ConnectionLost => "Stream connection lost; check your internet connection.",
+            FFmpegErrorKind::TlsError => "Check the URL uses the right scheme (rtmps/https) and that the system clock is correct.",
+            FFmpegErrorKind::EncoderUnavailable => "Pick another encoder or update your GPU drivers and FFmpeg build.",
+            FFmpegErrorKind::InvalidArgument => "Check the output group's encoding settings and custom FFmpeg arguments.",
+            FFmpegErrorKind::InputTimeout => "Check that OBS (or your encoder) is still streaming to SpiritStream.",
+            FFmpegErrorKind::DiskFull => "Free up disk space or change the recording folder.",
+            FFmpegErrorKind::Unknown => "Check the FFmpeg log for details.",
+        }
+    }
+
+    /// Whether restarting the process unchanged could succeed
+    ///
+    /// Rejected credentials, bad settings and a full disk fail the same way
+    /// on every attempt, so they are not reconnected automatically.
+    pub fn retryable(&self) -> bool {
+        !matches!(
+            self,
+            FFmpegErrorKind::AuthRejected
+                | FFmpegErrorKind::EncoderUnavailable
+                | FFmpegErrorKind::InvalidArgument
+                | FFmpegErrorKind::DiskFull
+        )
+    }
+}
+
+/// A classified FFmpeg failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FFmpegError {
+    pub kind: FFmpegErrorKind,
+    /// Short description of what went wrong
+    pub message: String,
+    /// The stderr line it was recognized from
+    pub line: String,
+}
@@ -0,0 +1,50 @@
+// Stream Health Model
+// Rating of an output group derived from its recent stats
+
+use serde::{Deserialize, Serialize};
+
+/// Overall rating, ordered from best to worst
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    #[default]
+    Good,
+    Degraded,
+    Critical,
+}
+
+/// Stat a health reason refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthMetric {
+    Speed,
+    DroppedFrames,
+    Bitrate,
+}
+
+/// One threshold a group crossed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReason {
+    pub metric: HealthMetric,
+    pub level: HealthLevel,
+    /// Measured value (speed factor, percent of frames dropped or percent of target bitrate)
+    pub value: f64,
+    /// Threshold it crossed, in the same unit
+    pub threshold: f64,
+    pub message: String,
+}
+
+/// Payload of the `stream_health` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamHealth {
+    pub group_id: String,
+    pub level: HealthLevel,
+    /// Rating before this change
+    pub previous: HealthLevel,
+    /// Empty when the group is good
+    pub reasons: Vec<HealthReason>,
+    /// Unix ms
+    pub at: i64,
+}
@@ -0,0 +1,151 @@
+// Ingest Model
+// The publisher connected to the built-in RTMP ingest
+
+use std::net::IpAddr;
+use serde::{Deserialize, Serialize};
+
+/// Stream properties announced by the publisher in `onMetaData`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_rate: Option<f64>,
+    /// Video codec name (e.g., "h264", "hevc")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_codec: Option<String>,
+    /// Configured video bitrate in kbps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_bitrate_kbps: Option<f64>,
+    /// Audio codec name (e.g., "aac")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_codec: Option<String>,
+    /// Configured audio bitrate in kbps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_bitrate_kbps: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_sample_rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_channels: Option<u32>,
+    /// Publishing software (e.g., "obs-output module (libobs version 30.1.2)")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoder: Option<String>,
+}
+
+/// Publisher currently live on the ingest, emitted as `ingest_connected`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestStatus {
+    /// Publisher address (ip:port)
+    pub client_addr: String,
+
+    /// RTMP application published to
+    pub app: String,
+
+    /// When publishing started (Unix milliseconds)
+    pub connected_at: i64,
+
+    /// Filled in once the publisher sends `onMetaData`
+    #[serde(default)]
+    pub metadata: IngestMetadata,
+}
+
+/// Network in CIDR notation (`192.168.1.0/24`, `fd00::/8`; a bare address is one host)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    pub fn parse(cidr: &str) -> Result<Self, String> {
+        let cidr = cidr.trim();
+        let (addr, prefix) = match cidr.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (cidr, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid network '{cidr}': expected an IP address or CIDR range"))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(|| format!("Invalid network '{cidr}': prefix must be 0-{max}"))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    /// Whether `ip` is inside the network (IPv4-mapped IPv6 addresses match IPv4 ranges)
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// What the ingest is actually carrying, measured from its FLV tags
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestStats {
+    /// Video codec name (e.g., "h264", "hevc")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_codec: Option<String>,
+    /// Codec profile (e.g., "High", "Main 10")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_profile: Option<String>,
+    /// Codec level (e.g., "4.2")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+    /// Seconds between the last two key frames (GOP length)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyframe_interval_secs: Option<f64>,
+    /// Measured over the last few seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_bitrate_kbps: Option<f64>,
+    /// Audio codec name (e.g., "aac")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_codec: Option<String>,
+    /// Codec profile (e.g., "LC", "HE-AAC")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_sample_rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_channels: Option<u32>,
+    /// Measured over the last few seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_bitrate_kbps: Option<f64>,
+    /// Passthrough targets that cannot take what is arriving
+    #[serde(default)]
+    pub warnings: Vec<IngestWarning>,
+}
+
+/// A passthrough target that will likely reject the ingest as is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestWarning {
+    pub group_id: String,
+    pub target_id: String,
+    pub message: String,
+}
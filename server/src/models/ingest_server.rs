@@ -0,0 +1,54 @@
+// Ingest Server Model
+// Platform ingest servers and the latency measured to each
+
+use serde::{Deserialize, Serialize};
+
+/// One ingest server listed for a platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestServer {
+    /// Server name as listed by the platform (e.g., "Europe: Frankfurt, Germany")
+    pub name: String,
+
+    /// RTMP(S) URL without the stream key
+    pub url: String,
+}
+
+/// Latency measured to one ingest server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestServerProbe {
+    pub name: String,
+    pub url: String,
+
+    /// Best TCP connect time of a few attempts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+
+    /// Time for the server's RTMP handshake reply (plain RTMP only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handshake_ms: Option<u32>,
+
+    /// Why the server could not be measured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Probe results for one stream target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestProbeResult {
+    pub group_id: String,
+    pub target_id: String,
+    pub target_name: String,
+
+    /// Servers of the target's platform, fastest first (unreachable ones last)
+    pub servers: Vec<IngestServerProbe>,
+
+    /// Fastest server that answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended: Option<IngestServer>,
+
+    /// Whether the recommended server was stored on the target
+    pub applied: bool,
+}
@@ -8,8 +8,18 @@ mod output_group;
 mod stream_target;
 mod encoders;
 mod stream_stats;
+mod target_status;
 mod theme;
 mod chat;
+mod schedule;
+mod ingest;
+mod preflight;
+mod ingest_server;
+mod bandwidth;
+mod session;
+mod process;
+mod health;
+mod ffmpeg_error;
 
 pub use settings::*;
 pub use profile_settings::*;
@@ -18,5 +28,15 @@ pub use output_group::*;
 pub use stream_target::*;
 pub use encoders::*;
 pub use stream_stats::*;
+pub use target_status::*;
 pub use theme::*;
 pub use chat::*;
+pub use schedule::*;
+pub use ingest::*;
+pub use preflight::*;
+pub use ingest_server::*;
+pub use bandwidth::*;
+pub use session::*;
+pub use process::*;
+pub use health::*;
+pub use ffmpeg_error::*;
@@ -2,7 +2,7 @@
 // Encoding profile for stream targets
 
 use serde::{Deserialize, Serialize};
-use crate::models::StreamTarget;
+use crate::models::{StreamTarget, TargetProtocol};
 
 /// Video encoding settings
 /// Default uses "copy" for passthrough (no re-encoding)
@@ -106,6 +106,54 @@ impl Default for ContainerSettings {
     }
 }
 
+/// Additional video rendition encoded from the same decode as the group's main video
+///
+/// Renditions share the group's audio encode; targets pick one via `rendition_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rendition {
+    /// Identifier referenced by `StreamTarget::rendition_id` (unique within the group)
+    pub id: String,
+
+    /// Display name (e.g., "720p30")
+    #[serde(default)]
+    pub name: String,
+
+    /// Video encoding settings (must re-encode; "copy" is not allowed)
+    pub video: VideoSettings,
+}
+
+/// I/O scheduling class, as set by `ionice`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IoPriorityClass {
+    /// Served before everything else (needs root)
+    Realtime,
+    /// Normal scheduling, ordered by level
+    BestEffort,
+    /// Only served when no other process needs the disk
+    Idle,
+}
+
+/// CPU and I/O priority of a group's FFmpeg processes
+///
+/// Applied on Linux (niceness on any Unix); ignored on Windows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessPriority {
+    /// Niceness from -20 (highest) to 19 (lowest); values below 0 need privileges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+
+    /// I/O scheduling class (best-effort when only a level is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_class: Option<IoPriorityClass>,
+
+    /// Level within the realtime or best-effort class, 0 (highest) to 7
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_level: Option<u8>,
+}
+
 /// An output group defines encoding settings for a set of stream targets
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -135,8 +183,16 @@ pub struct OutputGroup {
     /// Container/muxing settings
     pub container: ContainerSettings,
 
+    /// Extra video renditions (ABR ladder) split from the group's single decode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renditions: Vec<Rendition>,
+
     /// Stream destinations
     pub stream_targets: Vec<StreamTarget>,
+
+    /// Niceness and I/O priority of the group's FFmpeg processes (unset = inherit the server's)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ProcessPriority>,
 }
 
 impl OutputGroup {
@@ -150,10 +206,124 @@ impl OutputGroup {
             video: VideoSettings::default(),
             audio: AudioSettings::default(),
             container: ContainerSettings::default(),
+            renditions: Vec::new(),
             stream_targets: Vec::new(),
+            priority: None,
         }
     }
 
+    /// Look up a rendition by ID
+    pub fn rendition(&self, id: &str) -> Option<&Rendition> {
+        self.renditions.iter().find(|r| r.id == id)
+    }
+
+    /// Video settings a target is encoded with (its rendition or the group's main video)
+    pub fn video_for_target(&self, target: &StreamTarget) -> &VideoSettings {
+        target
+            .rendition_id
+            .as_deref()
+            .and_then(|id| self.rendition(id))
+            .map_or(&self.video, |r| &r.video)
+    }
+
+    /// Validate targets and the codec constraints their protocols impose
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_renditions()?;
+        self.validate_priority()?;
+
+        for target in &self.stream_targets {
+            target.validate()?;
+
+            if let Some(rendition_id) = target.rendition_id.as_deref() {
+                if self.rendition(rendition_id).is_none() {
+                    return Err(format!(
+                        "Target '{}' uses rendition '{rendition_id}', which does not exist in output group '{}'",
+                        target.name, self.name
+                    ));
+                }
+            }
+
+            if target.protocol == TargetProtocol::Whip {
+                self.validate_whip_codecs(self.video_for_target(target))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_priority(&self) -> Result<(), String> {
+        let Some(priority) = &self.priority else {
+            return Ok(());
+        };
+        if priority.nice.is_some_and(|nice| !(-20..=19).contains(&nice)) {
+            return Err(format!("Output group '{}' niceness must be between -20 and 19", self.name));
+        }
+        if priority.io_level.is_some_and(|level| level > 7) {
+            return Err(format!("Output group '{}' I/O priority level must be between 0 and 7", self.name));
+        }
+        Ok(())
+    }
+
+    /// Renditions are scaled from the decoded input, so each needs a real encoder
+    fn validate_renditions(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for rendition in &self.renditions {
+            if rendition.id.trim().is_empty() {
+                return Err(format!("Output group '{}' has a rendition without an ID", self.name));
+            }
+            if !seen.insert(rendition.id.as_str()) {
+                return Err(format!(
+                    "Output group '{}' has more than one rendition with ID '{}'",
+                    self.name, rendition.id
+                ));
+            }
+
+            let video = &rendition.video;
+            if video.codec.trim().is_empty() || video.codec.eq_ignore_ascii_case("copy") {
+                return Err(format!(
+                    "Rendition '{}' in output group '{}' must use a video encoder, not passthrough",
+                    rendition.name, self.name
+                ));
+            }
+            if video.width == 0 || video.height == 0 || video.fps == 0 {
+                return Err(format!(
+                    "Rendition '{}' in output group '{}' needs a resolution and frame rate",
+                    rendition.name, self.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// WebRTC ingest only carries H.264/AV1 video and Opus audio
+    fn validate_whip_codecs(&self, video: &VideoSettings) -> Result<(), String> {
+        let codec = video.codec.to_ascii_lowercase();
+        // Passthrough video is rejected: encoders commonly send H.264 with B-frames
+        // or HEVC, neither of which WebRTC can carry
+        if codec == "copy" {
+            return Err(format!(
+                "Output group '{}' passes video through; WHIP targets need it encoded to H.264 or AV1",
+                self.name
+            ));
+        }
+        if !(codec.contains("264") || codec.contains("av1")) {
+            return Err(format!(
+                "Output group '{}' uses video codec '{}'; WHIP targets require H.264 or AV1",
+                self.name, video.codec
+            ));
+        }
+
+        // Passthrough audio is rejected: encoders publish AAC, which WebRTC cannot carry
+        if !self.audio.codec.to_ascii_lowercase().contains("opus") {
+            return Err(format!(
+                "Output group '{}' uses audio codec '{}'; WHIP targets require Opus",
+                self.name, self.audio.codec
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for OutputGroup {
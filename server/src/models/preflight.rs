@@ -0,0 +1,100 @@
+// Preflight Model
+// Go-live checklist results for a profile
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one check; ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    /// Stable check identifier (e.g., "ffmpeg", "encoders", "target", "oauth")
+    pub id: String,
+
+    /// What was checked (e.g., "FFmpeg", "Target: Twitch")
+    pub label: String,
+
+    pub status: PreflightStatus,
+
+    /// Result details or the reason for a warning or failure
+    pub message: String,
+
+    /// Output group the check applies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+
+    /// Stream target the check applies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+}
+
+impl PreflightCheck {
+    pub fn new(id: &str, label: impl Into<String>, status: PreflightStatus, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.into(),
+            status,
+            message: message.into(),
+            group_id: None,
+            target_id: None,
+        }
+    }
+
+    pub fn pass(id: &str, label: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(id, label, PreflightStatus::Pass, message)
+    }
+
+    pub fn warn(id: &str, label: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(id, label, PreflightStatus::Warn, message)
+    }
+
+    pub fn fail(id: &str, label: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(id, label, PreflightStatus::Fail, message)
+    }
+
+    /// Attach the target (and its group) the check is about
+    pub fn for_target(mut self, group_id: &str, target_id: &str) -> Self {
+        self.group_id = Some(group_id.to_string());
+        self.target_id = Some(target_id.to_string());
+        self
+    }
+}
+
+/// Result of `run_preflight`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub profile_name: String,
+
+    /// Worst status of all checks
+    pub status: PreflightStatus,
+
+    pub checks: Vec<PreflightCheck>,
+
+    /// When the checks finished (Unix milliseconds)
+    pub checked_at: i64,
+}
+
+impl PreflightReport {
+    pub fn new(profile_name: &str, checks: Vec<PreflightCheck>) -> Self {
+        let status = checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(PreflightStatus::Pass);
+        Self {
+            profile_name: profile_name.to_string(),
+            status,
+            checks,
+            checked_at: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
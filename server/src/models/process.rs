@@ -0,0 +1,55 @@
+// FFmpeg Process Model
+// Child processes spawned by the FFmpeg handler
+
+use serde::{Deserialize, Serialize};
+
+/// What a child process does for the stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FFmpegProcessRole {
+    /// FFmpeg listener for SRT or RTMPS ingest
+    Relay,
+    /// Encoder of an output group
+    Group,
+    /// Copy of a group's output to one stream target
+    Target,
+    /// Backup slate shown while the ingest is missing
+    Slate,
+}
+
+impl FFmpegProcessRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FFmpegProcessRole::Relay => "relay",
+            FFmpegProcessRole::Group => "group",
+            FFmpegProcessRole::Target => "target",
+            FFmpegProcessRole::Slate => "slate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FFmpegProcess {
+    pub role: FFmpegProcessRole,
+    /// Group or target ID (empty for the relay and slate)
+    pub id: String,
+    pub pid: u32,
+}
+
+/// Resource usage of an FFmpeg child process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FFmpegProcessStats {
+    #[serde(flatten)]
+    pub process: FFmpegProcess,
+    /// Since the previous reading (100 = one core)
+    pub cpu_percent: f64,
+    /// Total CPU time since the process started
+    pub cpu_seconds: f64,
+    /// Resident memory
+    pub rss_bytes: u64,
+    pub threads: u32,
+    /// Niceness (-20 highest to 19 lowest)
+    pub nice: i32,
+}
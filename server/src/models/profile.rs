@@ -3,13 +3,126 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, HashMap};
-use crate::models::{OutputGroup, Platform, ProfileSettings};
+use crate::models::{IpNetwork, OutputGroup, Platform, ProfileSettings};
 
-/// RTMP Input configuration - where the stream enters the system
+/// SRT listener options (only used when the input type is "srt")
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SrtInputOptions {
+    /// Receiver latency in milliseconds (None = libsrt default of 120ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+
+    /// AES passphrase (10-79 characters, empty = unencrypted)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub passphrase: String,
+
+    /// Expected stream ID (empty = accept any)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stream_id: String,
+}
+
+/// What the backup slate shows while the publisher is disconnected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlateSource {
+    /// Generated card with a text message
+    #[default]
+    Card,
+    /// Still image file
+    Image,
+    /// Video file played in a loop (its audio is replaced by silence)
+    Video,
+}
+
+/// Backup slate played to every target while the primary ingest is missing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlateSettings {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub source: SlateSource,
+
+    /// Image or video file (unused for generated cards)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+
+    /// Text shown on generated cards
+    #[serde(default = "default_slate_message")]
+    pub message: String,
+
+    #[serde(default = "default_slate_width")]
+    pub width: u32,
+
+    #[serde(default = "default_slate_height")]
+    pub height: u32,
+
+    #[serde(default = "default_slate_fps")]
+    pub fps: u32,
+
+    /// How long the ingest must be silent before the slate takes over
+    #[serde(default = "default_slate_delay_ms")]
+    pub delay_ms: u64,
+}
+
+impl Default for SlateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: SlateSource::Card,
+            path: String::new(),
+            message: default_slate_message(),
+            width: default_slate_width(),
+            height: default_slate_height(),
+            fps: default_slate_fps(),
+            delay_ms: default_slate_delay_ms(),
+        }
+    }
+}
+
+impl SlateSettings {
+    /// Validate the slate source (only when enabled)
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.source != SlateSource::Card && self.path.trim().is_empty() {
+            return Err("Backup slate needs an image or video file".to_string());
+        }
+        if self.width == 0 || self.height == 0 || self.fps == 0 {
+            return Err("Backup slate resolution and frame rate must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_slate_message() -> String {
+    "We'll be right back".to_string()
+}
+
+fn default_slate_width() -> u32 {
+    1280
+}
+
+fn default_slate_height() -> u32 {
+    720
+}
+
+fn default_slate_fps() -> u32 {
+    30
+}
+
+fn default_slate_delay_ms() -> u64 {
+    1500
+}
+
+/// Stream input configuration - where the stream enters the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RtmpInput {
-    /// Input type (RTMP only)
+    /// Input type ("rtmp" or "srt")
     #[serde(rename = "type")]
     pub input_type: String,
 
@@ -19,8 +132,24 @@ pub struct RtmpInput {
     /// TCP port to listen on (e.g., 1935)
     pub port: u16,
 
-    /// RTMP application/path (e.g., "live", "ingest")
+    /// RTMP application/path (e.g., "live", "ingest"); ignored for SRT
     pub application: String,
+
+    /// SRT listener options
+    #[serde(default)]
+    pub srt: SrtInputOptions,
+
+    /// Stream key RTMP publishers must use (empty = any key); stored encrypted
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub ingest_key: String,
+
+    /// Networks allowed to publish over RTMP, in CIDR notation (empty = any address)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_networks: Vec<String>,
+
+    /// Fallback source while the publisher is disconnected
+    #[serde(default)]
+    pub slate: SlateSettings,
 }
 
 impl Default for RtmpInput {
@@ -30,7 +159,74 @@ impl Default for RtmpInput {
             bind_address: "0.0.0.0".to_string(),
             port: 1935,
             application: "live".to_string(),
+            srt: SrtInputOptions::default(),
+            ingest_key: String::new(),
+            allowed_networks: Vec::new(),
+            slate: SlateSettings::default(),
+        }
+    }
+}
+
+impl RtmpInput {
+    /// Whether this input is an SRT listener
+    pub fn is_srt(&self) -> bool {
+        self.input_type.eq_ignore_ascii_case("srt")
+    }
+
+    /// Validate the input type and protocol-specific options
+    pub fn validate(&self) -> Result<(), String> {
+        match self.input_type.to_ascii_lowercase().as_str() {
+            "rtmp" => {}
+            "srt" => {
+                let len = self.srt.passphrase.chars().count();
+                if len > 0 && !(10..=79).contains(&len) {
+                    return Err("SRT passphrase must be between 10 and 79 characters".to_string());
+                }
+                if self.srt.stream_id.len() > 512 {
+                    return Err("SRT stream ID must be at most 512 characters".to_string());
+                }
+            }
+            other => return Err(format!("Unsupported input type: {other}")),
+        }
+
+        if self.port == 0 {
+            return Err("Input port must be greater than 0".to_string());
+        }
+
+        if self.is_srt() && (!self.ingest_key.is_empty() || !self.allowed_networks.is_empty()) {
+            return Err(
+                "Ingest key and allowed networks apply to RTMP inputs; use the SRT passphrase and stream ID"
+                    .to_string(),
+            );
+        }
+        if self.ingest_key.chars().any(|c| c.is_whitespace() || matches!(c, '/' | '?')) {
+            return Err("Ingest key cannot contain spaces, '/' or '?'".to_string());
+        }
+        for network in &self.allowed_networks {
+            IpNetwork::parse(network)?;
+        }
+
+        self.slate.validate()
+    }
+
+    /// Build the URL the relay listens on for this input
+    pub fn listen_url(&self) -> String {
+        if !self.is_srt() {
+            return format!("rtmp://{}:{}/{}", self.bind_address, self.port, self.application);
+        }
+
+        let mut url = format!("srt://{}:{}?mode=listener", self.bind_address, self.port);
+        if let Some(latency_ms) = self.srt.latency_ms {
+            // libsrt's URL option is expressed in microseconds
+            url.push_str(&format!("&latency={}", u64::from(latency_ms) * 1000));
+        }
+        if !self.srt.passphrase.is_empty() {
+            url.push_str(&format!("&passphrase={}", urlencoding::encode(&self.srt.passphrase)));
+        }
+        if !self.srt.stream_id.is_empty() {
+            url.push_str(&format!("&streamid={}", urlencoding::encode(&self.srt.stream_id)));
         }
+        url
     }
 }
 
@@ -55,6 +55,58 @@ fn default_discord_cooldown_seconds() -> u32 {
 fn default_chat_visibility_panel_collapsed() -> bool {
     true
 }
+
+fn default_reconnect_enabled() -> bool {
+    true
+}
+
+fn default_reconnect_max_retries() -> u32 {
+    5
+}
+
+fn default_reconnect_initial_delay_secs() -> u64 {
+    5
+}
+
+fn default_reconnect_max_delay_secs() -> u64 {
+    120
+}
+
+fn default_reconnect_jitter_percent() -> u8 {
+    20
+}
+
+fn default_health_enabled() -> bool {
+    true
+}
+
+fn default_health_window_secs() -> u32 {
+    10
+}
+
+fn default_health_speed_degraded() -> f64 {
+    0.95
+}
+
+fn default_health_speed_critical() -> f64 {
+    0.8
+}
+
+fn default_health_dropped_degraded_percent() -> f64 {
+    1.0
+}
+
+fn default_health_dropped_critical_percent() -> f64 {
+    5.0
+}
+
+fn default_health_bitrate_degraded_percent() -> f64 {
+    75.0
+}
+
+fn default_health_bitrate_critical_percent() -> f64 {
+    40.0
+}
 // ============================================================================
 // Backend/Remote Access Settings
 // ============================================================================
@@ -274,6 +326,155 @@ impl Default for ChatSettings {
     }
 }
 
+// ============================================================================
+// Reconnection Settings
+// ============================================================================
+
+/// Automatic reconnection of output groups whose FFmpeg process exits unexpectedly
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconnectSettings {
+    /// Reconnect automatically (otherwise the UI offers a manual retry)
+    #[serde(default = "default_reconnect_enabled")]
+    pub enabled: bool,
+
+    /// Attempts before giving up (reset once a stream stays up)
+    #[serde(default = "default_reconnect_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before the first attempt; doubles with each attempt
+    #[serde(default = "default_reconnect_initial_delay_secs")]
+    pub initial_delay_secs: u64,
+
+    /// Upper bound for the backoff delay
+    #[serde(default = "default_reconnect_max_delay_secs")]
+    pub max_delay_secs: u64,
+
+    /// Random +/- spread applied to each delay, as a percentage (0-100)
+    #[serde(default = "default_reconnect_jitter_percent")]
+    pub jitter_percent: u8,
+}
+
+impl Default for ReconnectSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_reconnect_enabled(),
+            max_retries: default_reconnect_max_retries(),
+            initial_delay_secs: default_reconnect_initial_delay_secs(),
+            max_delay_secs: default_reconnect_max_delay_secs(),
+            jitter_percent: default_reconnect_jitter_percent(),
+        }
+    }
+}
+
+/// Longest reconnect delay a profile may configure (one hour)
+pub const MAX_RECONNECT_DELAY_SECS: u64 = 3600;
+
+impl ReconnectSettings {
+    /// Validate the backoff bounds
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_delay_secs > MAX_RECONNECT_DELAY_SECS {
+            return Err(format!(
+                "Reconnect delay must be at most {MAX_RECONNECT_DELAY_SECS} seconds"
+            ));
+        }
+        if self.initial_delay_secs > self.max_delay_secs {
+            return Err("Initial reconnect delay must not exceed the maximum delay".to_string());
+        }
+        if self.jitter_percent > 100 {
+            return Err("Reconnect jitter must be between 0 and 100 percent".to_string());
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Stream Health Settings
+// ============================================================================
+
+/// Thresholds for rating output groups and what to do when a rating changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSettings {
+    /// Rate groups and emit `stream_health` events
+    #[serde(default = "default_health_enabled")]
+    pub enabled: bool,
+
+    /// Stats are averaged over this window; a group must stay better for this long to recover
+    #[serde(default = "default_health_window_secs")]
+    pub window_secs: u32,
+
+    /// Encoding speed below which a group is degraded (1.0 = real-time)
+    #[serde(default = "default_health_speed_degraded")]
+    pub speed_degraded: f64,
+
+    /// Encoding speed below which a group is critical
+    #[serde(default = "default_health_speed_critical")]
+    pub speed_critical: f64,
+
+    /// Share of frames dropped within the window that makes a group degraded
+    #[serde(default = "default_health_dropped_degraded_percent")]
+    pub dropped_degraded_percent: f64,
+
+    /// Share of frames dropped within the window that makes a group critical
+    #[serde(default = "default_health_dropped_critical_percent")]
+    pub dropped_critical_percent: f64,
+
+    /// Output bitrate, as a percentage of the configured bitrate, below which a group is degraded
+    #[serde(default = "default_health_bitrate_degraded_percent")]
+    pub bitrate_degraded_percent: f64,
+
+    /// Output bitrate, as a percentage of the configured bitrate, below which a group is critical
+    #[serde(default = "default_health_bitrate_critical_percent")]
+    pub bitrate_critical_percent: f64,
+
+    /// Post rating changes to the profile's Discord webhook
+    #[serde(default)]
+    pub discord_alerts: bool,
+
+    /// OBS scene to switch to when a group turns critical (empty = none)
+    #[serde(default)]
+    pub obs_critical_scene: String,
+
+    /// OBS scene to switch back to once every group is good again (empty = none)
+    #[serde(default)]
+    pub obs_recovered_scene: String,
+}
+
+impl HealthSettings {
+    /// Validate that each critical threshold is at least as bad as its degraded one
+    pub fn validate(&self) -> Result<(), String> {
+        if self.speed_critical > self.speed_degraded {
+            return Err("Critical encoding speed must not be above the degraded speed".to_string());
+        }
+        if self.dropped_critical_percent < self.dropped_degraded_percent {
+            return Err("Critical dropped frames must not be below the degraded percentage".to_string());
+        }
+        if self.bitrate_critical_percent > self.bitrate_degraded_percent {
+            return Err("Critical bitrate must not be above the degraded percentage".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_health_enabled(),
+            window_secs: default_health_window_secs(),
+            speed_degraded: default_health_speed_degraded(),
+            speed_critical: default_health_speed_critical(),
+            dropped_degraded_percent: default_health_dropped_degraded_percent(),
+            dropped_critical_percent: default_health_dropped_critical_percent(),
+            bitrate_degraded_percent: default_health_bitrate_degraded_percent(),
+            bitrate_critical_percent: default_health_bitrate_critical_percent(),
+            discord_alerts: false,
+            obs_critical_scene: String::new(),
+            obs_recovered_scene: String::new(),
+        }
+    }
+}
+
 // ============================================================================
 // OAuth Settings (per-profile)
 // ============================================================================
@@ -362,6 +563,14 @@ pub struct ProfileSettings {
     /// OAuth tokens + account info (per profile)
     #[serde(default)]
     pub oauth: OAuthSettings,
+
+    /// Automatic reconnection of crashed output groups
+    #[serde(default)]
+    pub reconnect: ReconnectSettings,
+
+    /// Stream health thresholds and alerts
+    #[serde(default)]
+    pub health: HealthSettings,
 }
 
 impl Default for ProfileSettings {
@@ -376,11 +585,19 @@ impl Default for ProfileSettings {
             discord: DiscordSettings::default(),
             chat: ChatSettings::default(),
             oauth: OAuthSettings::default(),
+            reconnect: ReconnectSettings::default(),
+            health: HealthSettings::default(),
         }
     }
 }
 
 impl ProfileSettings {
+    /// Validate settings that feed arithmetic or process options
+    pub fn validate(&self) -> Result<(), String> {
+        self.reconnect.validate()?;
+        self.health.validate()
+    }
+
     /// Check if these settings are at their defaults (for migration detection)
     pub fn is_default(&self) -> bool {
         // Check if all fields are at default values
@@ -432,6 +649,8 @@ impl ProfileSettings {
             && self.oauth.youtube.user_id.is_empty()
             && self.oauth.youtube.username.is_empty()
             && self.oauth.youtube.display_name.is_empty()
+            && self.reconnect == ReconnectSettings::default()
+            && self.health == HealthSettings::default()
     }
 
     /// Merge legacy settings into this profile, only filling values that are still at defaults.
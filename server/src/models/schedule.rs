@@ -0,0 +1,108 @@
+// Schedule Model
+// Planned stream starts and stops for a profile
+
+use serde::{Deserialize, Serialize};
+
+/// A one-off or recurring stream schedule entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    /// Unique identifier (assigned on save when empty)
+    #[serde(default)]
+    pub id: String,
+
+    /// Profile whose output groups are started
+    pub profile_name: String,
+
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// One-off start time (Unix ms), used when `cron` is not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<i64>,
+
+    /// Recurring start as a five-field cron expression in server local time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+
+    /// Stop this many minutes after the start (None = keep streaming until stopped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<u32>,
+
+    /// Output groups to start (empty = every group with targets)
+    #[serde(default)]
+    pub group_ids: Vec<String>,
+
+    /// When the entry was created or its timing last changed (Unix ms)
+    #[serde(default)]
+    pub created_at: i64,
+
+    /// Start time of the last occurrence that was started or missed (Unix ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_occurrence: Option<i64>,
+
+    /// Start time of the occurrence currently live (Unix ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running_since: Option<i64>,
+
+    /// Output groups the live occurrence started (groups already running are not included)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub started_group_ids: Vec<String>,
+}
+
+impl ScheduleEntry {
+    /// Validate the entry's fields (the cron syntax is checked by the scheduler)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.profile_name.trim().is_empty() {
+            return Err("Schedule needs a profile".to_string());
+        }
+        match (&self.start_at, &self.cron) {
+            (None, None) => return Err("Schedule needs a start time or a cron expression".to_string()),
+            (Some(_), Some(_)) => {
+                return Err("Schedule cannot have both a start time and a cron expression".to_string())
+            }
+            _ => {}
+        }
+        if self.duration_minutes == Some(0) {
+            return Err("Schedule duration must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the timing differs from another version of the entry
+    pub fn same_timing(&self, other: &ScheduleEntry) -> bool {
+        self.start_at == other.start_at && self.cron == other.cron
+    }
+
+    /// When an occurrence starting at `occurrence` should stop (Unix ms)
+    pub fn stop_time(&self, occurrence: i64) -> Option<i64> {
+        self.duration_minutes
+            .map(|minutes| occurrence + i64::from(minutes) * 60_000)
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Schedule event payload (`schedule_upcoming`, `schedule_started`, `schedule_missed`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEvent {
+    pub schedule_id: String,
+    pub profile_name: String,
+
+    /// Start time of the occurrence (Unix ms)
+    pub occurrence: i64,
+
+    /// Planned stop time (Unix ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_at: Option<i64>,
+
+    /// Groups the schedule starts (empty = every group with targets)
+    pub group_ids: Vec<String>,
+
+    /// Why a start was missed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
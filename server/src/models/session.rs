@@ -0,0 +1,189 @@
+// Stream Session Model
+// History of one streaming run: what was live, its metrics over time and what went wrong
+
+use serde::{Deserialize, Serialize};
+use crate::models::{Platform, TargetProtocol};
+
+/// One run of output groups, from the first start until everything stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSession {
+    pub id: String,
+    pub profile_name: String,
+
+    /// Unix ms
+    pub started_at: i64,
+
+    /// Unix ms (None while the session is live)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<i64>,
+
+    /// Groups that took part, including ones started later in the session
+    pub groups: Vec<SessionGroup>,
+
+    /// Errors, reconnects and group ends in order
+    #[serde(default)]
+    pub events: Vec<SessionEvent>,
+
+    /// Downsampled per-group metrics in time order
+    #[serde(default)]
+    pub samples: Vec<SessionSample>,
+}
+
+/// Output group as it was when it joined the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionGroup {
+    pub id: String,
+    pub name: String,
+    pub targets: Vec<SessionTarget>,
+}
+
+/// Stream target of a session group (no URL or key is kept)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTarget {
+    pub id: String,
+    pub name: String,
+    pub service: Platform,
+    pub protocol: TargetProtocol,
+}
+
+/// What happened to a group during a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    Error,
+    Reconnecting,
+    Reconnected,
+    GaveUp,
+    Ended,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    /// Unix ms
+    pub at: i64,
+    pub group_id: String,
+    pub kind: SessionEventKind,
+    pub message: String,
+}
+
+/// Averaged `StreamStats` of one group over a sample interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSample {
+    /// Start of the interval (Unix ms)
+    pub at: i64,
+    pub group_id: String,
+    pub fps: f64,
+    /// kbps
+    pub bitrate: f64,
+    pub speed: f64,
+    /// FFmpeg's dropped frame count at the end of the interval (restarts with each reconnect)
+    pub dropped_frames: u64,
+}
+
+/// Session overview for listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSessionSummary {
+    pub id: String,
+    pub profile_name: String,
+    pub started_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<i64>,
+    pub group_count: usize,
+    pub target_count: usize,
+    pub errors: usize,
+    pub reconnects: usize,
+    /// Dropped frames summed across every FFmpeg run of every group
+    pub dropped_frames: u64,
+    /// Mean of the samples' bitrates (kbps)
+    pub average_bitrate: f64,
+}
+
+impl StreamSession {
+    pub fn summary(&self) -> StreamSessionSummary {
+        let count_events = |kind| self.events.iter().filter(|event| event.kind == kind).count();
+        let average_bitrate = if self.samples.is_empty() {
+            0.0
+        } else {
+            self.samples.iter().map(|sample| sample.bitrate).sum::<f64>() / self.samples.len() as f64
+        };
+
+        StreamSessionSummary {
+            id: self.id.clone(),
+            profile_name: self.profile_name.clone(),
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            group_count: self.groups.len(),
+            target_count: self.groups.iter().map(|group| group.targets.len()).sum(),
+            errors: count_events(SessionEventKind::Error),
+            reconnects: count_events(SessionEventKind::Reconnected),
+            dropped_frames: self.total_dropped_frames(),
+            average_bitrate,
+        }
+    }
+
+    /// Dropped frames over the whole session
+    ///
+    /// FFmpeg counts from zero again after a reconnect, so each run's last count is added up.
+    pub fn total_dropped_frames(&self) -> u64 {
+        let mut last: std::collections::HashMap<&str, u64> = std::collections::HashMap::new();
+        let mut finished_runs = 0;
+        for sample in &self.samples {
+            let previous = last.insert(sample.group_id.as_str(), sample.dropped_frames).unwrap_or(0);
+            if sample.dropped_frames < previous {
+                finished_runs += previous;
+            }
+        }
+        finished_runs + last.values().sum::<u64>()
+    }
+
+    /// Last moment anything was recorded (Unix ms)
+    pub fn last_activity(&self) -> i64 {
+        let last_sample = self.samples.last().map(|sample| sample.at);
+        let last_event = self.events.last().map(|event| event.at);
+        last_sample.max(last_event).unwrap_or(self.started_at)
+    }
+
+    /// Samples as CSV, one row per group and interval
+    pub fn samples_csv(&self) -> String {
+        let group_name = |id: &str| {
+            self.groups
+                .iter()
+                .find(|group| group.id == id)
+                .map(|group| group.name.clone())
+                .unwrap_or_default()
+        };
+
+        let mut csv = String::from("time,elapsed_secs,group_id,group_name,fps,bitrate_kbps,speed,dropped_frames\n");
+        for sample in &self.samples {
+            let time = chrono::DateTime::from_timestamp_millis(sample.at)
+                .map(|time| time.to_rfc3339())
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{time},{:.1},{},{},{:.2},{:.1},{:.3},{}\n",
+                (sample.at - self.started_at) as f64 / 1000.0,
+                csv_field(&sample.group_id),
+                csv_field(&group_name(&sample.group_id)),
+                sample.fps,
+                sample.bitrate,
+                sample.speed,
+                sample.dropped_frames,
+            ));
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -33,6 +33,33 @@ pub struct StreamStats {
 
     /// Number of duplicate frames
     pub dup_frames: u64,
+
+    /// Per-rendition figures for groups with an ABR ladder (empty otherwise)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renditions: Vec<RenditionStats>,
+}
+
+/// Statistics for one video rendition of an output group
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RenditionStats {
+    /// Rendition ID (None for the group's main video settings)
+    pub rendition_id: Option<String>,
+
+    /// Display name
+    pub name: String,
+
+    /// Configured output width in pixels (0 for passthrough)
+    pub width: u32,
+
+    /// Configured output height in pixels (0 for passthrough)
+    pub height: u32,
+
+    /// Configured frame rate (0 for passthrough)
+    pub fps: u32,
+
+    /// Measured video bitrate in kbps (audio is shared and excluded)
+    pub bitrate: f64,
 }
 
 impl StreamStats {
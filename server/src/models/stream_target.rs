@@ -1,12 +1,228 @@
 // StreamTarget Model
-// RTMP destination configuration
+// Destination configuration (RTMP, SRT, RIST, WHIP, local recording)
 
 use serde::{Deserialize, Serialize};
 
-// Platform enum auto-generated from data/streaming-platforms.json at build time
-include!(concat!(env!("OUT_DIR"), "/generated_platforms.rs"));
+/// Streaming service a target is set up for, by its name in the platform registry
+///
+/// Any name is accepted so profiles keep loading when a service is renamed or
+/// removed from the registry; unknown names are treated as Custom.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Platform(String);
 
-/// A stream target represents an RTMP destination
+impl Platform {
+    /// Name of the generic RTMP service
+    pub const CUSTOM: &'static str = "Custom";
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::new(Self::CUSTOM)
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Transport protocol used to deliver a target's stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetProtocol {
+    /// RTMP/RTMPS push (FLV muxed)
+    #[default]
+    Rtmp,
+    /// SRT caller (MPEG-TS muxed)
+    Srt,
+    /// RIST sender (MPEG-TS muxed)
+    Rist,
+    /// WebRTC-HTTP ingest (WHIP muxer, H.264/AV1 + Opus only)
+    Whip,
+    /// Local segmented recording under the app data dir (segment muxer)
+    Record,
+    /// Local HLS/LL-HLS output served by the backend
+    Hls,
+}
+
+impl TargetProtocol {
+    /// URL scheme expected for this protocol
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            TargetProtocol::Rtmp => "rtmp",
+            TargetProtocol::Srt => "srt",
+            TargetProtocol::Rist => "rist",
+            TargetProtocol::Whip => "https",
+            TargetProtocol::Record | TargetProtocol::Hls => "file",
+        }
+    }
+
+    /// Muxer forced for this protocol, or None to use the group's container format
+    pub fn muxer(&self) -> Option<&'static str> {
+        match self {
+            TargetProtocol::Rtmp => None,
+            TargetProtocol::Srt | TargetProtocol::Rist => Some("mpegts"),
+            TargetProtocol::Whip => Some("whip"),
+            TargetProtocol::Record => Some("segment"),
+            TargetProtocol::Hls => Some("hls"),
+        }
+    }
+}
+
+/// SRT caller options (the target's stream key is used as the passphrase)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SrtOutputOptions {
+    /// Sender latency in milliseconds (None = libsrt default of 120ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+
+    /// Stream ID sent to the listener (empty = none)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stream_id: String,
+}
+
+/// RIST sender options (the target's stream key is used as the shared secret)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RistOutputOptions {
+    /// Retransmission buffer in milliseconds (None = librist default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer_ms: Option<u32>,
+
+    /// AES key size when a secret is set (128 or 256)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<u16>,
+
+    /// RIST profile ("simple" or "main")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// Container used for recorded files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    /// Fragmented MP4 (playable even if the recording is interrupted)
+    Mp4,
+    /// Matroska
+    #[default]
+    Mkv,
+    /// MPEG transport stream
+    Ts,
+}
+
+impl RecordingFormat {
+    /// FFmpeg muxer name
+    pub fn muxer(&self) -> &'static str {
+        match self {
+            RecordingFormat::Mp4 => "mp4",
+            RecordingFormat::Mkv => "matroska",
+            RecordingFormat::Ts => "mpegts",
+        }
+    }
+
+    /// File extension (without dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordingFormat::Mp4 => "mp4",
+            RecordingFormat::Mkv => "mkv",
+            RecordingFormat::Ts => "ts",
+        }
+    }
+}
+
+/// Local recording options (only used when protocol is "record")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordOptions {
+    /// Container for recorded files
+    #[serde(default)]
+    pub format: RecordingFormat,
+
+    /// Start a new file every N seconds (None = no duration split)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_seconds: Option<u32>,
+
+    /// Start a new file after roughly N megabytes (estimated from the group bitrate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_size_mb: Option<u32>,
+
+    /// Filename template; supports {profile}, {group}, {target} and {timestamp}
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+
+    /// Refuse to start (and warn while recording) below this much free space
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            format: RecordingFormat::default(),
+            segment_seconds: None,
+            segment_size_mb: None,
+            filename_template: default_filename_template(),
+            min_free_space_mb: default_min_free_space_mb(),
+        }
+    }
+}
+
+fn default_filename_template() -> String {
+    "{profile}_{group}_{timestamp}".to_string()
+}
+
+fn default_min_free_space_mb() -> u64 {
+    1024
+}
+
+/// Local HLS options (only used when protocol is "hls")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HlsOptions {
+    /// Use low-latency HLS (fMP4 parts via the dash muxer) instead of MPEG-TS segments
+    #[serde(default)]
+    pub low_latency: bool,
+
+    /// Target segment duration in seconds
+    #[serde(default = "default_hls_segment_seconds")]
+    pub segment_seconds: u32,
+
+    /// Number of segments kept in the live playlist
+    #[serde(default = "default_hls_playlist_size")]
+    pub playlist_size: u32,
+}
+
+impl Default for HlsOptions {
+    fn default() -> Self {
+        Self {
+            low_latency: false,
+            segment_seconds: default_hls_segment_seconds(),
+            playlist_size: default_hls_playlist_size(),
+        }
+    }
+}
+
+fn default_hls_segment_seconds() -> u32 {
+    2
+}
+
+fn default_hls_playlist_size() -> u32 {
+    6
+}
+
+/// A stream target represents a destination (network endpoint or local recording)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamTarget {
@@ -21,10 +237,138 @@ pub struct StreamTarget {
     #[serde(default)]
     pub name: String,
 
-    /// RTMP server URL
+    /// Transport protocol (defaults to RTMP for existing profiles)
+    #[serde(default)]
+    pub protocol: TargetProtocol,
+
+    /// Server URL (rtmp://, srt://, rist:// or WHIP endpoint; unused for recordings)
     pub url: String,
 
     /// Stream key (authentication) - supports ${ENV_VAR} syntax
+    /// For SRT this is the passphrase, for RIST the shared secret, for WHIP the bearer token,
+    /// for HLS the optional share token for unauthenticated viewers
     pub stream_key: String,
+
+    /// SRT-specific options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub srt: Option<SrtOutputOptions>,
+
+    /// RIST-specific options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rist: Option<RistOutputOptions>,
+
+    /// Recording options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<RecordOptions>,
+
+    /// HLS options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hls: Option<HlsOptions>,
+
+    /// Output group rendition to send (None uses the group's main video settings)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendition_id: Option<String>,
+
+    /// Name of the platform ingest server `url` was picked from by latency probing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingest_server: Option<String>,
+}
+
+impl StreamTarget {
+    /// Validate protocol-specific settings
+    pub fn validate(&self) -> Result<(), String> {
+        let url = self.url.trim();
+        let scheme = self.protocol.scheme();
+        // WHIP endpoints may be plain HTTP when pointed at a local server
+        let scheme_matches = url.starts_with(&format!("{scheme}://"))
+            || (self.protocol == TargetProtocol::Whip && url.starts_with("http://"));
+        let needs_url = !matches!(
+            self.protocol,
+            TargetProtocol::Rtmp | TargetProtocol::Record | TargetProtocol::Hls
+        );
+        if needs_url && !scheme_matches {
+            return Err(format!(
+                "Target '{}' uses {} but its URL does not start with {scheme}://",
+                self.name,
+                scheme.to_uppercase()
+            ));
+        }
+
+        match self.protocol {
+            TargetProtocol::Srt => {
+                // Env var references are resolved at start time, so only check literal values
+                let len = self.stream_key.chars().count();
+                if !self.stream_key.starts_with("${") && len > 0 && !(10..=79).contains(&len) {
+                    return Err(format!(
+                        "Target '{}': SRT passphrase must be between 10 and 79 characters",
+                        self.name
+                    ));
+                }
+            }
+            TargetProtocol::Rist => {
+                if let Some(bits) = self.rist.as_ref().and_then(|r| r.encryption) {
+                    if bits != 128 && bits != 256 {
+                        return Err(format!(
+                            "Target '{}': RIST encryption must be 128 or 256",
+                            self.name
+                        ));
+                    }
+                }
+            }
+            TargetProtocol::Record => {
+                let options = self.record.clone().unwrap_or_default();
+                let template = options.filename_template.as_str();
+                if !template.contains("{timestamp}") {
+                    return Err(format!(
+                        "Target '{}': recording filename template must include {{timestamp}}",
+                        self.name
+                    ));
+                }
+                // The template names a file inside the profile's recordings folder, never a path
+                let has_drive = template.as_bytes().get(1) == Some(&b':');
+                if template.contains(['/', '\\']) || template.contains("..") || has_drive
+                    || std::path::Path::new(template).has_root()
+                {
+                    return Err(format!(
+                        "Target '{}': recording filename template must be a file name, not a path",
+                        self.name
+                    ));
+                }
+                if options.segment_seconds == Some(0) || options.segment_size_mb == Some(0) {
+                    return Err(format!(
+                        "Target '{}': recording segment length must be greater than 0",
+                        self.name
+                    ));
+                }
+            }
+            TargetProtocol::Hls => {
+                // Share tokens appear in URLs, so require enough entropy to resist guessing
+                let len = self.stream_key.chars().count();
+                if !self.stream_key.starts_with("${") && len > 0 && len < 16 {
+                    return Err(format!(
+                        "Target '{}': HLS share token must be at least 16 characters",
+                        self.name
+                    ));
+                }
+                if self.hls.as_ref().is_some_and(|hls| hls.segment_seconds == 0) {
+                    return Err(format!(
+                        "Target '{}': HLS segment duration must be greater than 0",
+                        self.name
+                    ));
+                }
+            }
+            TargetProtocol::Rtmp | TargetProtocol::Whip => {}
+        }
+
+        Ok(())
+    }
 }
 
+/// A target whose encode exceeds what its platform accepts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformWarning {
+    pub group_id: String,
+    pub target_id: String,
+    pub message: String,
+}
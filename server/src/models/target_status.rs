@@ -0,0 +1,49 @@
+// TargetStatus Model
+// Per-target connection state within an output group
+
+use serde::{Deserialize, Serialize};
+use crate::models::{Platform, TargetProtocol};
+
+/// Connection state of a single stream target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetState {
+    /// FFmpeg is opening the output (handshake/publish in progress)
+    Connecting,
+    /// Output opened and media is flowing
+    Live,
+    /// Output dropped or could not be opened; the rest of the group keeps running
+    Failed,
+    /// Waiting to reconnect after a failure (the whole group, or just this target)
+    Retrying,
+}
+
+/// Current status of a stream target, emitted as the `target_status` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetStatus {
+    /// Output group the target belongs to
+    pub group_id: String,
+
+    /// Stream target ID
+    pub target_id: String,
+
+    /// Display name of the target
+    pub name: String,
+
+    /// Streaming service/platform
+    pub service: Platform,
+
+    /// Transport protocol
+    pub protocol: TargetProtocol,
+
+    /// Connection state
+    pub state: TargetState,
+
+    /// Failure reason parsed from FFmpeg stderr (redacted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Time of the last state change (Unix milliseconds)
+    pub updated_at: i64,
+}
@@ -0,0 +1,164 @@
+// Cron Service
+// Parses five-field cron expressions and finds their next occurrence
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+
+/// Days searched for a match before giving up (covers leap-day-only expressions)
+const SEARCH_DAYS: i64 = 366 * 8;
+
+/// Parsed cron expression: minute, hour, day of month, month, day of week
+///
+/// Supports `*`, numbers, lists (`1,15`), ranges (`1-5`) and steps (`*/15`, `0-30/10`).
+/// Day of week is 0-7 with both 0 and 7 meaning Sunday. As in classic cron, when both
+/// day fields are restricted a day matching either of them fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse an expression such as `0 19 * * 1-5`
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Cron expression must have 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, "weekday")?;
+        // 7 is an alias for Sunday
+        if days_of_week.contains(&7) {
+            days_of_week.retain(|&day| day != 7);
+            if !days_of_week.contains(&0) {
+                days_of_week.insert(0, 0);
+            }
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_field(fields[2], 1, 31, "day")?,
+            months: parse_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start_date = after.date();
+        for offset in 0..SEARCH_DAYS {
+            let date = start_date + Duration::days(offset);
+            if !self.matches_day(date) {
+                continue;
+            }
+            for &hour in &self.hours {
+                for &minute in &self.minutes {
+                    let candidate = date.and_hms_opt(hour, minute, 0)?;
+                    if candidate > after {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&date.day());
+        let dow = self.days_of_week.contains(&date.weekday().num_days_from_sunday());
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+/// Parse one field into the sorted list of values it allows
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<u32>, String> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid {name} step '{step}'"))?;
+                if step == 0 {
+                    return Err(format!("Invalid {name} step '0'"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, max, name)?, parse_value(end, min, max, name)?)
+        } else {
+            let value = parse_value(range, min, max, name)?;
+            // `5/10` means every 10th value starting at 5
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("Invalid {name} range '{range}'"));
+        }
+
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, name: &str) -> Result<u32, String> {
+    let parsed: u32 = value
+        .parse()
+        .map_err(|_| format!("Invalid {name} '{value}'"))?;
+    if parsed < min || parsed > max {
+        return Err(format!("{name} {parsed} is outside {min}-{max}"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_next_weekday_evening() {
+        let cron = CronSchedule::parse("0 19 * * 1-5").unwrap();
+        // Friday 2026-10-16 20:00 -> Monday 19:00
+        assert_eq!(cron.next_after(at("2026-10-16 20:00")), Some(at("2026-10-19 19:00")));
+        assert_eq!(cron.next_after(at("2026-10-19 18:59")), Some(at("2026-10-19 19:00")));
+        assert_eq!(cron.next_after(at("2026-10-19 19:00")), Some(at("2026-10-20 19:00")));
+    }
+
+    #[test]
+    fn test_steps_lists_and_sunday_alias() {
+        let cron = CronSchedule::parse("*/20 9,21 * * 7").unwrap();
+        // Sunday 2026-10-18
+        assert_eq!(cron.next_after(at("2026-10-16 00:00")), Some(at("2026-10-18 09:00")));
+        assert_eq!(cron.next_after(at("2026-10-18 09:40")), Some(at("2026-10-18 21:00")));
+        assert!(CronSchedule::parse("0 24 * * *").is_err());
+        assert!(CronSchedule::parse("0 19 * *").is_err());
+    }
+}
@@ -166,6 +166,18 @@ impl DiscordWebhookService {
         }
     }
 
+    /// Send a plain message, ignoring the go-live cooldown (used for alerts)
+    pub async fn send_message(&self, webhook_url: &str, message: &str) -> Result<(), String> {
+        check_webhook_url(webhook_url)?;
+
+        let payload = WebhookPayload {
+            content: message.to_string(),
+            username: Some("SpiritStream".to_string()),
+            avatar_url: None,
+        };
+        self.send_webhook(webhook_url, &payload).await
+    }
+
     /// Send a webhook request to Discord
     async fn send_webhook(&self, url: &str, payload: &WebhookPayload) -> Result<(), String> {
         let response = self
@@ -283,20 +295,10 @@ impl DiscordWebhookService {
     /// Test a webhook URL by sending a test message
     pub async fn test_webhook(&self, webhook_url: &str) -> WebhookResult {
         // Validate webhook URL
-        if webhook_url.is_empty() {
-            return WebhookResult {
-                success: false,
-                message: "Webhook URL is empty".to_string(),
-                skipped_cooldown: false,
-            };
-        }
-
-        if !webhook_url.starts_with("https://discord.com/api/webhooks/")
-            && !webhook_url.starts_with("https://discordapp.com/api/webhooks/")
-        {
+        if let Err(message) = check_webhook_url(webhook_url) {
             return WebhookResult {
                 success: false,
-                message: "Invalid Discord webhook URL format".to_string(),
+                message,
                 skipped_cooldown: false,
             };
         }
@@ -327,6 +329,33 @@ impl DiscordWebhookService {
         }
     }
 
+    /// Check that a webhook exists without posting to the channel
+    ///
+    /// Discord answers a GET on the webhook URL with the webhook's details.
+    pub async fn validate_webhook(&self, webhook_url: &str) -> WebhookResult {
+        if let Err(message) = check_webhook_url(webhook_url) {
+            return WebhookResult {
+                success: false,
+                message,
+                skipped_cooldown: false,
+            };
+        }
+
+        let result = match self.client.get(webhook_url).send().await {
+            Ok(response) => self.handle_response(response).await,
+            Err(e) => Err(format!("Request failed: {}", e)),
+        };
+        let (success, message) = match result {
+            Ok(()) => (true, "Webhook is valid".to_string()),
+            Err(e) => (false, e),
+        };
+        WebhookResult {
+            success,
+            message,
+            skipped_cooldown: false,
+        }
+    }
+
     /// Reset the cooldown timer (useful for testing)
     pub async fn reset_cooldown(&self) {
         let mut last_send = self.last_send_time.write().await;
@@ -335,6 +364,19 @@ impl DiscordWebhookService {
     }
 }
 
+/// Reject empty and non-Discord webhook URLs before making a request
+fn check_webhook_url(webhook_url: &str) -> Result<(), String> {
+    if webhook_url.is_empty() {
+        return Err("Webhook URL is empty".to_string());
+    }
+    if !webhook_url.starts_with("https://discord.com/api/webhooks/")
+        && !webhook_url.starts_with("https://discordapp.com/api/webhooks/")
+    {
+        return Err("Invalid Discord webhook URL format".to_string());
+    }
+    Ok(())
+}
+
 impl Default for DiscordWebhookService {
     fn default() -> Self {
         Self::new()
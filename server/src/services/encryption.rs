@@ -688,6 +688,15 @@ impl Encryption {
             }
         }
 
+        // Ingest credentials (RTMP ingest key, SRT passphrase)
+        for secret in [&mut profile.input.ingest_key, &mut profile.input.srt.passphrase] {
+            if Self::is_stream_key_encrypted(secret) {
+                let plaintext = Self::decrypt_stream_key_with_key(secret, old_key)?;
+                *secret = Self::encrypt_stream_key_with_key(&plaintext, new_key)?;
+                keys_updated += 1;
+            }
+        }
+
         // Save updated profile
         let json = serde_json::to_string_pretty(&profile)
             .map_err(|e| format!("Failed to serialize profile: {e}"))?;
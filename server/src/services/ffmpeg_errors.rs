@@ -0,0 +1,235 @@
+// FFmpeg Error Classification
+// Recognizes why an FFmpeg process failed from its last stderr lines
+
+use std::collections::VecDeque;
+use crate::models::{FFmpegError, FFmpegErrorKind};
+
+/// A failure recognized when a line contains every fragment of one of `patterns`
+///
+/// Fragments are lowercase; lines are compared case-insensitively.
+struct Rule {
+    kind: FFmpegErrorKind,
+    patterns: &'static [&'static [&'static str]],
+    message: &'static str,
+}
+
+/// Rules naming a specific cause, checked in order for each line
+const RULES: &[Rule] = &[
+    Rule {
+        kind: FFmpegErrorKind::AuthRejected,
+        patterns: &[
+            &["invalid stream key"],
+            &["authentication failed"],
+            &["authorization failed"],
+            &["unauthorized"],
+            &["server returned 401"],
+            &["server returned 403"],
+            &["netstream.publish.badname"],
+            &["netstream.publish.rejected"],
+            &["bad secret"],
+            &["passphrase"],
+        ],
+        message: "Server rejected the stream key or credentials",
+    },
+    Rule {
+        kind: FFmpegErrorKind::DiskFull,
+        patterns: &[&["no space left on device"], &["not enough space on the disk"], &["disk quota exceeded"]],
+        message: "Disk is full",
+    },
+    Rule {
+        kind: FFmpegErrorKind::DnsFailure,
+        patterns: &[
+            &["failed to resolve hostname"],
+            &["name or service not known"],
+            &["temporary failure in name resolution"],
+            &["nodename nor servname"],
+            &["no such host"],
+            &["-11001"],
+            &["wsahost_not_found"],
+        ],
+        message: "Could not resolve the server's hostname",
+    },
+    Rule {
+        kind: FFmpegErrorKind::TlsError,
+        patterns: &[
+            &["certificate"],
+            &["tls handshake"],
+            &["ssl handshake"],
+            &["error in the pull function"],
+            &["error in the push function"],
+            &["gnutls"],
+            &["openssl"],
+            &["schannel"],
+        ],
+        message: "Secure (TLS) connection to the server failed",
+    },
+    Rule {
+        kind: FFmpegErrorKind::EncoderUnavailable,
+        patterns: &[
+            &["unknown encoder"],
+            &["encoder not found"],
+            &["error while opening encoder"],
+            &["no nvenc capable devices"],
+            &["no capable devices found"],
+            &["cannot load nvcuda"],
+            &["cannot load libcuda"],
+            &["failed to initialise vaapi"],
+            &["device creation failed"],
+        ],
+        message: "Encoder is not available",
+    },
+    // Only option parsing: a bare "Invalid argument" is FFmpeg's generic EINVAL
+    // text, also printed for runtime write failures that a restart can fix
+    Rule {
+        kind: FFmpegErrorKind::InvalidArgument,
+        patterns: &[
+            &["unrecognized option"],
+            &["option not found"],
+            &["error parsing options"],
+            &["error splitting the argument list"],
+        ],
+        message: "FFmpeg rejected an option",
+    },
+    Rule {
+        kind: FFmpegErrorKind::InputTimeout,
+        patterns: &[&["in#", "timed out"], &["demux", "timed out"], &["opening input", "timed out"]],
+        message: "No data arrived from the ingest",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionRefused,
+        patterns: &[&["connection refused"], &["-10061"], &["wsaeconnrefused"]],
+        message: "Server refused the connection",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionTimeout,
+        patterns: &[&["connection timed out"], &["-10060"], &["wsaetimedout"]],
+        message: "Server connection timed out",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionLost,
+        patterns: &[&["connection reset"], &["-10054"], &["wsaeconnreset"]],
+        message: "Connection reset by remote server",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionLost,
+        patterns: &[&["software caused connection abort"], &["-10053"], &["wsaeconnaborted"]],
+        message: "Connection aborted by network",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionLost,
+        patterns: &[&["no route to host"], &["network is unreachable"], &["-10065"], &["wsaehostunreach"]],
+        message: "No route to the server",
+    },
+    Rule {
+        kind: FFmpegErrorKind::ConnectionLost,
+        patterns: &[&["broken pipe"]],
+        message: "Server closed the connection",
+    },
+];
+
+/// Symptoms that follow most network failures; only used when no line names the cause
+const FALLBACK_RULES: &[Rule] = &[Rule {
+    kind: FFmpegErrorKind::ConnectionLost,
+    patterns: &[&["error code: -5"], &["code -5"], &["error muxing packet"], &["error muxing a packet"]],
+    message: "Failed to send packets to the server (network connection lost)",
+}];
+
+/// Classify a failure from the process's most recent stderr lines
+///
+/// The newest line naming a cause wins. FFmpeg usually ends with generic
+/// lines ("Error muxing a packet") after the one that explains the failure,
+/// so those only count when nothing more specific was logged.
+pub fn classify_ffmpeg_error(lines: &VecDeque<String>) -> Option<FFmpegError> {
+    find_rule(lines, RULES).or_else(|| find_rule(lines, FALLBACK_RULES))
+}
+
+fn find_rule(lines: &VecDeque<String>, rules: &[Rule]) -> Option<FFmpegError> {
+    lines
+        .iter()
+        .rev()
+        // `-progress` output (key=value without spaces) carries counters, not errors
+        .filter(|line| line.contains(char::is_whitespace) || !line.contains('='))
+        .find_map(|line| {
+            let lower = line.to_ascii_lowercase();
+            let rule = rules.iter().find(|rule| {
+                rule.patterns
+                    .iter()
+                    .any(|fragments| fragments.iter().all(|fragment| lower.contains(fragment)))
+            })?;
+            Some(FFmpegError {
+                kind: rule.kind,
+                message: rule.message.to_string(),
+                line: line.trim().to_string(),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> VecDeque<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn kind(log: &[&str]) -> Option<FFmpegErrorKind> {
+        classify_ffmpeg_error(&lines(log)).map(|error| error.kind)
+    }
+
+    #[test]
+    fn test_specific_cause_beats_later_generic_lines() {
+        let log = lines(&[
+            "frame=  300 fps= 30 q=23.0 size=    1024kB time=00:00:10.00 bitrate= 838.9kbits/s speed=1.00x",
+            "[rtmp @ 0x55d1] Server error: Invalid stream key",
+            "[out#0/flv @ 0x55d2] Error muxing a packet",
+            "Conversion failed!",
+        ]);
+        let error = classify_ffmpeg_error(&log).unwrap();
+        assert_eq!(error.kind, FFmpegErrorKind::AuthRejected);
+        assert_eq!(error.line, "[rtmp @ 0x55d1] Server error: Invalid stream key");
+        assert!(!error.kind.retryable());
+
+        assert_eq!(kind(&["[out#0/flv @ 0x1] Error muxing a packet"]), Some(FFmpegErrorKind::ConnectionLost));
+    }
+
+    #[test]
+    fn test_classifies_common_failures() {
+        let cases = [
+            ("[tcp @ 0x1] Failed to resolve hostname live.twitch.tv: Name or service not known", FFmpegErrorKind::DnsFailure),
+            ("[tcp @ 0x1] Connection to tcp://127.0.0.1:1935 failed: Connection refused", FFmpegErrorKind::ConnectionRefused),
+            ("[tls @ 0x1] Error in the pull function.", FFmpegErrorKind::TlsError),
+            ("[vost#0:0 @ 0x1] Unknown encoder 'h264_nvenc'", FFmpegErrorKind::EncoderUnavailable),
+            ("Unrecognized option 'presett'.", FFmpegErrorKind::InvalidArgument),
+            ("[in#0/flv @ 0x1] Error during demuxing: Connection timed out", FFmpegErrorKind::InputTimeout),
+            ("[segment @ 0x1] Failed to open segment: No space left on device", FFmpegErrorKind::DiskFull),
+            ("av_interleaved_write_frame(): Error number -10054 occurred", FFmpegErrorKind::ConnectionLost),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(kind(&[line]), Some(expected), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_runtime_invalid_argument_stays_retryable() {
+        for line in [
+            "[flv @ 0x1] av_interleaved_write_frame(): Invalid argument",
+            "[out#0/flv @ 0x1] Error writing trailer of rtmp://live.example.com/app: Invalid argument",
+        ] {
+            let error = classify_ffmpeg_error(&lines(&[line]));
+            assert!(error.map_or(true, |error| error.kind.retryable()), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_serialized_kind_is_the_code() {
+        for kind in [FFmpegErrorKind::AuthRejected, FFmpegErrorKind::InputTimeout, FFmpegErrorKind::Unknown] {
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.code());
+        }
+    }
+
+    #[test]
+    fn test_ignores_progress_counters() {
+        assert_eq!(kind(&["out_time_us=10060000", "total_size=10054", "progress=continue"]), None);
+        assert_eq!(kind(&[]), None);
+    }
+}
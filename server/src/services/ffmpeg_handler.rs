@@ -2,103 +2,338 @@
 // Manages FFmpeg processes for streaming with real-time stats
 
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::io::{BufRead, BufReader, Write};
-use std::net::UdpSocket;
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicU16, AtomicU64, AtomicUsize, Ordering};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStderr, Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 use crate::services::{emit_event, EventSink};
-use crate::models::{OutputGroup, StreamStats};
-use crate::services::PlatformRegistry;
-
-/// Reconnection configuration and state
-#[derive(Debug, Clone)]
-struct ReconnectionConfig {
-    max_retries: u32,
-    initial_delay_secs: u64,
-    max_delay_secs: u64,
+use crate::models::{
+    FFmpegErrorKind, FFmpegProcess, FFmpegProcessRole, FFmpegProcessStats, IngestStats, IngestStatus, IngestWarning, OutputGroup, PlatformWarning, Rendition,
+    RenditionStats, RtmpInput, SlateSettings, SlateSource, StreamStats, StreamTarget, TargetProtocol, TargetStatus, VideoSettings,
+};
+use crate::services::rtmp::RtmpServer;
+use crate::services::{
+    apply_priority, check_priority, classify_ffmpeg_error, ProcessSampler, build_slate_args, check_slate_source, lists_filter, describe_target_failure, passthrough_warnings,
+    read_flv_tags, run_reconnect_supervisor, spawn_stdin_writer, GroupExit, HlsPlan, IngestAccess,
+    IngestAnalyzer, IngestSource, IngestSwitcher, NativeIngest, OutputFanout, PlatformRegistry,
+    ReconnectionConfig, ReconnectionState, RecordingPlan, TargetStatusTracker, TS_PACKET_SIZE,
+};
+use crate::models::ReconnectSettings;
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+
+// Windows: Hide console windows for spawned processes
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Process info for tracking active streams
+struct ProcessInfo {
+    child: Child,
+    start_time: Instant,
+    group_id: String,
+    reconnection_state: ReconnectionState,
+    /// Feeds the group's encoded output to its target processes
+    fanout: Arc<OutputFanout>,
+    /// Renditions encoded by this process, in output video stream order after the main video
+    renditions: Vec<String>,
 }
 
-impl Default for ReconnectionConfig {
-    fn default() -> Self {
-        Self {
-            max_retries: 5,
-            initial_delay_secs: 5,
-            max_delay_secs: 120,
+/// Per-target FFmpeg process copying its group's output to one destination
+struct TargetProcess {
+    child: Child,
+    start_time: Instant,
+    group_id: String,
+    /// Target settings the process was started with
+    target: StreamTarget,
+    fanout: Arc<OutputFanout>,
+    reconnection_state: ReconnectionState,
+}
+
+/// A target's output ready to spawn
+struct TargetOutput {
+    target: StreamTarget,
+    muxer: String,
+    /// Muxer options passed as `-key value`
+    options: Vec<(String, String)>,
+    destination: String,
+    recording: Option<RecordingPlan>,
+}
+
+/// Cached configuration for restarting crashed groups and the relay
+struct ActiveGroupConfig {
+    group: OutputGroup,
+    incoming_url: String,
+}
+
+/// Shared ingest every group reads from
+struct RelayProcess {
+    listener: IngestListener,
+    incoming_url: String,
+}
+
+/// What accepts the publisher
+enum IngestListener {
+    /// Built-in RTMP server (plain `rtmp://` inputs)
+    Native(RtmpServer),
+    /// FFmpeg listening for SRT or RTMPS and writing FLV to stdout
+    Process(Child),
+}
+
+impl RelayProcess {
+    /// Whether the listener is gone (an FFmpeg relay exits with its publisher)
+    fn has_exited(&mut self) -> bool {
+        match &mut self.listener {
+            IngestListener::Native(_) => false,
+            IngestListener::Process(child) => matches!(child.try_wait(), Ok(Some(_))),
+        }
+    }
+
+    fn stop(&mut self) {
+        match &mut self.listener {
+            IngestListener::Native(server) => server.stop(),
+            IngestListener::Process(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
         }
     }
 }
 
-/// Tracks reconnection attempts for a group
-#[derive(Debug, Clone)]
-struct ReconnectionState {
-    attempt: u32,
-    last_attempt: Option<Instant>,
+/// Byte counters fed with a group's output as it is fanned out
+struct BitrateMeter {
+    total: AtomicU64,
+    /// Bytes per output video stream, tracked when the group encodes renditions
+    video_streams: Vec<AtomicU64>,
 }
 
-impl ReconnectionState {
-    fn new() -> Self {
+impl BitrateMeter {
+    /// PID the mpegts muxer gives output stream 0; later streams follow in order
+    const TS_START_PID: u16 = 0x100;
+
+    fn new(video_streams: usize) -> Self {
         Self {
-            attempt: 0,
-            last_attempt: None,
+            total: AtomicU64::new(0),
+            video_streams: (0..video_streams).map(|_| AtomicU64::new(0)).collect(),
         }
     }
 
-    fn increment(&mut self) {
-        self.attempt += 1;
-        self.last_attempt = Some(Instant::now());
+    fn record(&self, packets: &[u8]) {
+        self.total.fetch_add(packets.len() as u64, Ordering::Relaxed);
+        if self.video_streams.is_empty() {
+            return;
+        }
+
+        // Video streams come first in the output, so their index matches the PID offset
+        for packet in packets.chunks_exact(TS_PACKET_SIZE) {
+            if let Some(counter) = Self::stream_index(packet).and_then(|i| self.video_streams.get(i)) {
+                counter.fetch_add(TS_PACKET_SIZE as u64, Ordering::Relaxed);
+            }
+        }
     }
 
-    fn reset(&mut self) {
-        self.attempt = 0;
-        self.last_attempt = None;
+    /// Output stream index of an MPEG-TS packet, if it carries an elementary stream
+    fn stream_index(packet: &[u8]) -> Option<usize> {
+        if packet.len() < 3 || packet[0] != 0x47 {
+            return None;
+        }
+        let pid = (u16::from(packet[1] & 0x1f) << 8) | u16::from(packet[2]);
+        pid.checked_sub(Self::TS_START_PID).map(usize::from)
     }
+}
+
+/// Background thread switching running groups to the backup slate while the ingest is missing
+struct SlateWatch {
+    ffmpeg_path: String,
+    relay: Arc<Mutex<Option<RelayProcess>>>,
+    ingest_status: Arc<Mutex<Option<IngestStatus>>>,
+    ingest_access: Arc<Mutex<IngestAccess>>,
+    ingest_analyzer: Arc<IngestAnalyzer>,
+    processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    active_groups: Arc<Mutex<HashMap<String, ActiveGroupConfig>>>,
+    switcher: Arc<IngestSwitcher>,
+    slate: Arc<Mutex<SlateSettings>>,
+    slate_process: Arc<Mutex<Option<Child>>>,
+    running: Arc<AtomicBool>,
+    event_sink: Arc<dyn EventSink>,
+}
+
+impl SlateWatch {
+    /// Run until no group process is left
+    fn run(self) {
+        let mut on_air = false;
+        let mut slate_failed = false;
+        let mut last_relay_spawn: Option<Instant> = None;
+        let mut last_stats = Instant::now();
+        let mut reported_warnings: Vec<IngestWarning> = Vec::new();
+
+        loop {
+            thread::sleep(FFmpegHandler::SLATE_WATCH_INTERVAL);
+
+            if last_stats.elapsed() >= FFmpegHandler::INGEST_STATS_INTERVAL {
+                last_stats = Instant::now();
+                self.report_ingest_stats(&mut reported_warnings);
+            }
+
+            let has_groups = self.processes.lock().map(|procs| !procs.is_empty()).unwrap_or(false);
+            if !has_groups {
+                self.running.store(false, Ordering::SeqCst);
+                // A group may have started between the check and clearing the flag
+                let restarted = self.processes.lock().map(|procs| !procs.is_empty()).unwrap_or(false);
+                if restarted && !self.running.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+
+            let settings = self.slate.lock().map(|s| s.clone()).unwrap_or_default();
+            let relay_running = self.relay_running();
+            if settings.enabled && !relay_running
+                && last_relay_spawn.map_or(true, |at| at.elapsed() >= FFmpegHandler::RELAY_RESPAWN_INTERVAL)
+            {
+                // An FFmpeg relay exits with the publisher; bring it back for the publisher to reconnect
+                last_relay_spawn = Some(Instant::now());
+                self.respawn_relay();
+            }
+
+            let primary_missing = self.switcher.primary_missing(Duration::from_millis(settings.delay_ms));
+            if !primary_missing {
+                slate_failed = false;
+            }
+
+            if settings.enabled && primary_missing && !slate_failed {
+                if !self.slate_running() {
+                    match FFmpegHandler::spawn_slate(&self.ffmpeg_path, &settings, &self.switcher) {
+                        Ok(child) => {
+                            if let Ok(mut guard) = self.slate_process.lock() {
+                                *guard = Some(child);
+                            }
+                        }
+                        Err(error) => {
+                            log::error!("Backup slate failed to start: {error}");
+                            slate_failed = true;
+                            self.emit(false, Some(error));
+                            continue;
+                        }
+                    }
+                }
+                if !on_air {
+                    log::info!("Ingest missing, switching outputs to the backup slate");
+                    self.switcher.switch_to_slate();
+                    on_air = true;
+                    self.emit(true, None);
+                }
+            } else if on_air {
+                if primary_missing {
+                    // Slate turned off while on air: end the groups' input like a lost ingest
+                    log::info!("Backup slate disabled while on air");
+                    self.switcher.switch_to_primary();
+                    self.switcher.close_all();
+                } else {
+                    log::info!("Ingest is back, leaving the backup slate");
+                }
+                FFmpegHandler::stop_slate(&self.slate_process);
+                on_air = false;
+                self.emit(false, None);
+            }
+        }
 
-    fn should_retry(&self, config: &ReconnectionConfig) -> bool {
-        self.attempt < config.max_retries
+        FFmpegHandler::stop_slate(&self.slate_process);
+        self.switcher.reset();
+        if on_air {
+            self.emit(false, None);
+        }
     }
 
-    fn next_delay(&self, config: &ReconnectionConfig) -> Duration {
-        // Exponential backoff: initial * 2^attempt, capped at max_delay
-        let delay_secs = config.initial_delay_secs * (1 << self.attempt.min(6));
-        Duration::from_secs(delay_secs.min(config.max_delay_secs))
+    /// Emit `ingest_stats`, logging passthrough warnings the first time they appear
+    fn report_ingest_stats(&self, reported: &mut Vec<IngestWarning>) {
+        let Some(stats) = FFmpegHandler::measure_ingest(&self.ingest_analyzer, &self.active_groups) else {
+            reported.clear();
+            return;
+        };
+        for warning in stats.warnings.iter().filter(|w| !reported.contains(w)) {
+            log::warn!(
+                "Passthrough target {} in group {} may reject the ingest: {}",
+                warning.target_id, warning.group_id, warning.message
+            );
+        }
+        reported.clone_from(&stats.warnings);
+        emit_event(self.event_sink.as_ref(), "ingest_stats", &stats);
     }
-}
 
-// Windows: Hide console windows for spawned processes
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
-#[cfg(windows)]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+    fn relay_running(&self) -> bool {
+        let Ok(mut guard) = self.relay.lock() else {
+            return false;
+        };
+        if guard.as_mut().is_some_and(RelayProcess::has_exited) {
+            *guard = None;
+        }
+        guard.is_some()
+    }
 
-/// Process info for tracking active streams
-struct ProcessInfo {
-    child: Child,
-    start_time: Instant,
-    group_id: String,
-    reconnection_state: ReconnectionState,
-}
+    fn slate_running(&self) -> bool {
+        let Ok(mut guard) = self.slate_process.lock() else {
+            return false;
+        };
+        if let Some(child) = guard.as_mut() {
+            if let Ok(Some(status)) = child.try_wait() {
+                log::warn!("Backup slate exited ({status})");
+                *guard = None;
+            }
+        }
+        guard.is_some()
+    }
 
-/// Cached configuration for restarting groups when relay output set changes
-struct ActiveGroupConfig {
-    group: OutputGroup,
-    incoming_url: String,
-}
+    fn respawn_relay(&self) {
+        let incoming_url = self.active_groups.lock()
+            .ok()
+            .and_then(|active| active.values().next().map(|cfg| cfg.incoming_url.clone()));
+        let Some(incoming_url) = incoming_url else {
+            return;
+        };
+        let Ok(mut guard) = self.relay.lock() else {
+            return;
+        };
+        if guard.is_some() {
+            return;
+        }
+        match FFmpegHandler::spawn_relay(
+            &self.ffmpeg_path,
+            &incoming_url,
+            &self.switcher,
+            &self.slate,
+            &self.ingest_access,
+            &self.ingest_status,
+            &self.ingest_analyzer,
+            &self.event_sink,
+        ) {
+            Ok(relay) => *guard = Some(relay),
+            Err(error) => log::warn!("Failed to restart relay for the publisher: {error}"),
+        }
+    }
 
-/// FFmpeg relay process for shared ingest
-struct RelayProcess {
-    child: Child,
-    incoming_url: String,
-    output_groups: HashSet<String>,
+    fn emit(&self, active: bool, error: Option<String>) {
+        emit_event(
+            self.event_sink.as_ref(),
+            "backup_slate",
+            &serde_json::json!({
+                "active": active,
+                "error": error,
+            }),
+        );
+    }
 }
 
 /// Manages FFmpeg streaming processes
 pub struct FFmpegHandler {
     ffmpeg_path: String,
     processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    /// Output processes of running groups (target_id -> process)
+    target_processes: Arc<Mutex<HashMap<String, TargetProcess>>>,
     stopping_groups: Arc<Mutex<HashSet<String>>>,
     disabled_targets: Arc<Mutex<HashSet<String>>>,
     relay: Arc<Mutex<Option<RelayProcess>>>,
@@ -108,70 +343,61 @@ pub struct FFmpegHandler {
     relay_refcount: Arc<AtomicUsize>,
     /// Platform registry for URL normalization and redaction
     platform_registry: PlatformRegistry,
-    /// Reconnection configuration
-    reconnection_config: ReconnectionConfig,
-    /// Port assignments for groups (group_id -> port_offset)
-    /// Simplified sequential port allocation instead of hash-based
-    port_assignments: Arc<Mutex<HashMap<String, u16>>>,
-    /// Next available port offset for new groups
-    next_port_offset: Arc<AtomicU16>,
+    /// Reconnection configuration (from the active profile's settings)
+    reconnection_config: Arc<Mutex<ReconnectionConfig>>,
+    /// Channel to the reconnect supervisor, set once it is started
+    reconnect_tx: Arc<OnceLock<mpsc::UnboundedSender<GroupExit>>>,
+    /// Pending automatic reconnect attempts (group_id -> backoff task)
+    reconnect_tasks: Arc<Mutex<HashMap<String, AbortHandle>>>,
+    /// Feeds every group from the relay, or from the backup slate while the ingest is missing
+    switcher: Arc<IngestSwitcher>,
+    /// Backup slate configuration (from the active profile's ingest settings)
+    slate: Arc<Mutex<SlateSettings>>,
+    /// Slate FFmpeg process, running only while the slate is on air
+    slate_process: Arc<Mutex<Option<Child>>>,
+    /// Publisher currently live on the built-in RTMP server
+    ingest_status: Arc<Mutex<Option<IngestStatus>>>,
+    /// Ingest key and allowlist (from the active profile's input settings)
+    ingest_access: Arc<Mutex<IngestAccess>>,
+    /// Codec, GOP and bitrate measured from the primary ingest
+    ingest_analyzer: Arc<IngestAnalyzer>,
+    /// Whether the slate watch thread is running
+    slate_watch_running: Arc<AtomicBool>,
+    /// Base directory for record targets (app data dir/recordings)
+    recordings_dir: PathBuf,
+    /// Base directory for HLS targets (app data dir/hls), served by the HTTP server
+    hls_dir: PathBuf,
+    /// Active profile name, used in recording filenames
+    profile_name: Arc<Mutex<Option<String>>>,
+    /// Per-target connection state parsed from each target process's output
+    target_statuses: TargetStatusTracker,
+    /// CPU readings of child processes between `process_stats` calls
+    process_sampler: ProcessSampler,
 }
 
 impl FFmpegHandler {
-    // Simplified sequential port allocation for relay fan-out
-    // Since only one profile is active at a time, we can use simple sequential ports
-    // Each output group gets: relay_port = BASE + (index * 2), meter_port = BASE + (index * 2) + 1
-    const RELAY_HOST: &'static str = "localhost";
-    const RELAY_PORT_BASE: u16 = 20000;  // First group: 20000 (relay), 20001 (meter)
-    const RELAY_TCP_OUT_QUERY: &'static str = "tcp_nodelay=1";
-    const RELAY_TCP_IN_QUERY: &'static str = "listen=1&tcp_nodelay=1";
+    // RTMP inputs are served by the built-in RTMP server; SRT and RTMPS inputs
+    // use an FFmpeg relay writing FLV to stdout. Either way the ingest switcher
+    // copies the FLV tags into the stdin of every group process
     const RELAY_RTMP_TIMEOUT_SECS: u32 = 604_800;
     const RELAY_RTMP_TCP_NODELAY: &'static str = "1";
-    const RELAY_TEE_FIFO_OPTIONS: &'static str =
-        "fifo_format=mpegts:queue_size=512:drop_pkts_on_overflow=1:attempt_recovery=1:recover_any_error=1";
-    const METER_HOST: &'static str = "127.0.0.1";
-    const METER_UDP_QUERY: &'static str = "pkt_size=1316";
-
-    /// Parse Windows error codes and FFmpeg error codes from log lines
-    fn parse_error_details(lines: &VecDeque<String>) -> Option<String> {
-        for line in lines.iter().rev() {
-            // Windows socket error codes
-            if line.contains("10054") {
-                return Some("Connection reset by remote server (10054 - WSAECONNRESET)".to_string());
-            }
-            if line.contains("10053") {
-                return Some("Connection aborted by network (10053 - WSAECONNABORTED)".to_string());
-            }
-            if line.contains("10060") {
-                return Some("Connection timed out (10060 - WSAETIMEDOUT)".to_string());
-            }
-            if line.contains("10061") {
-                return Some("Connection refused by server (10061 - WSAECONNREFUSED)".to_string());
-            }
-            if line.contains("10065") {
-                return Some("No route to host (10065 - WSAEHOSTUNREACH)".to_string());
-            }
-
-            // FFmpeg error codes
-            if line.contains("error code: -5") || line.contains("code -5") {
-                return Some("I/O error (-5 - EIO): Network connection lost".to_string());
-            }
-            if line.contains("Connection refused") {
-                return Some("RTMP server refused connection".to_string());
-            }
-            if line.contains("Connection timed out") {
-                return Some("RTMP server connection timed out".to_string());
-            }
-            if line.contains("error muxing packet") {
-                return Some("Failed to send packet to server (possible network issue)".to_string());
-            }
-        }
-        None
-    }
+    /// How often the slate watch checks the ingest
+    const SLATE_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+    /// Minimum time between relay restarts while waiting for the publisher
+    const RELAY_RESPAWN_INTERVAL: Duration = Duration::from_secs(1);
+    /// How often `ingest_stats` is emitted while groups are running
+    const INGEST_STATS_INTERVAL: Duration = Duration::from_secs(2);
+    /// How long a group process may take to exit once its input is closed
+    const GROUP_STOP_TIMEOUT: Duration = Duration::from_secs(2);
+    /// How long a target process may take to send what it has queued and write trailers
+    const TARGET_STOP_TIMEOUT: Duration = Duration::from_secs(10);
 
     /// Create FFmpegHandler with optional custom FFmpeg path from settings
     /// Falls back to auto-discovery if custom path is empty or invalid
     pub fn new_with_custom_path(app_data_dir: PathBuf, custom_path: Option<String>) -> Self {
+        let recordings_dir = app_data_dir.join("recordings");
+        let hls_dir = app_data_dir.join("hls");
+        let platform_registry = PlatformRegistry::load(&app_data_dir);
         let ffmpeg_path = match custom_path {
             Some(ref path) if !path.is_empty() && std::path::Path::new(path).exists() => {
                 log::info!("Using custom FFmpeg path from settings: {path}");
@@ -186,15 +412,28 @@ impl FFmpegHandler {
         Self {
             ffmpeg_path,
             processes: Arc::new(Mutex::new(HashMap::new())),
+            target_processes: Arc::new(Mutex::new(HashMap::new())),
             stopping_groups: Arc::new(Mutex::new(HashSet::new())),
             disabled_targets: Arc::new(Mutex::new(HashSet::new())),
             relay: Arc::new(Mutex::new(None)),
             active_groups: Arc::new(Mutex::new(HashMap::new())),
             relay_refcount: Arc::new(AtomicUsize::new(0)),
-            platform_registry: PlatformRegistry::new(),
-            reconnection_config: ReconnectionConfig::default(),
-            port_assignments: Arc::new(Mutex::new(HashMap::new())),
-            next_port_offset: Arc::new(AtomicU16::new(0)),
+            platform_registry,
+            reconnection_config: Arc::new(Mutex::new(ReconnectionConfig::default())),
+            reconnect_tx: Arc::new(OnceLock::new()),
+            reconnect_tasks: Arc::new(Mutex::new(HashMap::new())),
+            switcher: Arc::new(IngestSwitcher::new()),
+            slate: Arc::new(Mutex::new(SlateSettings::default())),
+            slate_process: Arc::new(Mutex::new(None)),
+            ingest_status: Arc::new(Mutex::new(None)),
+            ingest_access: Arc::new(Mutex::new(IngestAccess::default())),
+            ingest_analyzer: Arc::new(IngestAnalyzer::new()),
+            slate_watch_running: Arc::new(AtomicBool::new(false)),
+            recordings_dir,
+            hls_dir,
+            profile_name: Arc::new(Mutex::new(None)),
+            target_statuses: TargetStatusTracker::new(),
+            process_sampler: ProcessSampler::new(),
         }
     }
 
@@ -203,18 +442,306 @@ impl FFmpegHandler {
         Self {
             ffmpeg_path: Self::find_ffmpeg(),
             processes: Arc::new(Mutex::new(HashMap::new())),
+            target_processes: Arc::new(Mutex::new(HashMap::new())),
             stopping_groups: Arc::new(Mutex::new(HashSet::new())),
             disabled_targets: Arc::new(Mutex::new(HashSet::new())),
             relay: Arc::new(Mutex::new(None)),
             active_groups: Arc::new(Mutex::new(HashMap::new())),
             relay_refcount: Arc::new(AtomicUsize::new(0)),
             platform_registry: PlatformRegistry::new(),
-            reconnection_config: ReconnectionConfig::default(),
-            port_assignments: Arc::new(Mutex::new(HashMap::new())),
-            next_port_offset: Arc::new(AtomicU16::new(0)),
+            reconnection_config: Arc::new(Mutex::new(ReconnectionConfig::default())),
+            reconnect_tx: Arc::new(OnceLock::new()),
+            reconnect_tasks: Arc::new(Mutex::new(HashMap::new())),
+            switcher: Arc::new(IngestSwitcher::new()),
+            slate: Arc::new(Mutex::new(SlateSettings::default())),
+            slate_process: Arc::new(Mutex::new(None)),
+            ingest_status: Arc::new(Mutex::new(None)),
+            ingest_access: Arc::new(Mutex::new(IngestAccess::default())),
+            ingest_analyzer: Arc::new(IngestAnalyzer::new()),
+            slate_watch_running: Arc::new(AtomicBool::new(false)),
+            recordings_dir: PathBuf::from("recordings"),
+            hls_dir: PathBuf::from("hls"),
+            profile_name: Arc::new(Mutex::new(None)),
+            target_statuses: TargetStatusTracker::new(),
+            process_sampler: ProcessSampler::new(),
+        }
+    }
+
+    /// Per-target connection state of running groups (optionally one group)
+    pub fn target_statuses(&self, group_id: Option<&str>) -> Vec<TargetStatus> {
+        self.target_statuses.statuses(group_id)
+    }
+
+    /// Apply the active profile's reconnection settings
+    pub fn set_reconnect_settings(&self, settings: &ReconnectSettings) {
+        if let Ok(mut config) = self.reconnection_config.lock() {
+            *config = ReconnectionConfig::from(settings);
+        }
+    }
+
+    /// Current reconnection configuration
+    pub fn reconnection_config(&self) -> ReconnectionConfig {
+        self.reconnection_config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Start the background task that reconnects groups after unexpected exits
+    pub fn start_reconnect_supervisor(self: &Arc<Self>, event_sink: Arc<dyn EventSink>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if self.reconnect_tx.set(tx.clone()).is_err() {
+            log::warn!("Reconnect supervisor is already running");
+            return;
+        }
+        tokio::spawn(run_reconnect_supervisor(Arc::downgrade(self), rx, tx, event_sink));
+    }
+
+    /// Whether a group is still meant to be streaming (not stopped by the user)
+    pub fn is_group_active(&self, group_id: &str) -> bool {
+        self.active_groups
+            .lock()
+            .map(|active| active.contains_key(group_id))
+            .unwrap_or(false)
+    }
+
+    /// Mark a group's targets as waiting to reconnect
+    pub fn mark_group_retrying(&self, group_id: &str, event_sink: &dyn EventSink) {
+        self.target_statuses.mark_group_retrying(group_id, event_sink);
+    }
+
+    /// Mark one target as waiting to reconnect while its group keeps running
+    pub fn mark_target_retrying(&self, target_id: &str, event_sink: &dyn EventSink) {
+        self.target_statuses.mark_target_retrying(target_id, event_sink);
+    }
+
+    /// Mark a target that will not be reconnected again
+    pub fn mark_target_gave_up(&self, target_id: &str, error: String, event_sink: &dyn EventSink) {
+        self.target_statuses.mark_target_gave_up(target_id, error, event_sink);
+    }
+
+    /// Track a pending reconnect attempt so stopping the group can abort it
+    ///
+    /// `key` is the exit's [`GroupExit::label`]: the group ID, or `group/target` for one target.
+    pub fn register_reconnect_task(&self, key: &str, task: AbortHandle) {
+        if let Ok(mut tasks) = self.reconnect_tasks.lock() {
+            if let Some(previous) = tasks.insert(key.to_string(), task) {
+                previous.abort();
+            }
         }
     }
 
+    /// Forget a reconnect attempt whose backoff has elapsed
+    pub fn finish_reconnect_task(&self, key: &str) {
+        if let Ok(mut tasks) = self.reconnect_tasks.lock() {
+            tasks.remove(key);
+        }
+    }
+
+    /// Abort the pending reconnect attempts of a group and its targets, if any
+    pub fn cancel_reconnect(&self, group_id: &str) {
+        let target_prefix = format!("{group_id}/");
+        self.cancel_reconnects_where(|key| key == group_id || key.starts_with(&target_prefix));
+    }
+
+    /// Abort a target's pending reconnect attempt, if any
+    fn cancel_target_reconnect(&self, target_id: &str) {
+        self.cancel_reconnects_where(|key| key.rsplit_once('/').is_some_and(|(_, target)| target == target_id));
+    }
+
+    fn cancel_reconnects_where(&self, matches: impl Fn(&str) -> bool) {
+        if let Ok(mut tasks) = self.reconnect_tasks.lock() {
+            tasks.retain(|key, task| {
+                if !matches(key) {
+                    return true;
+                }
+                log::info!("[FFmpeg:{key}] Cancelled pending reconnect");
+                task.abort();
+                false
+            });
+        }
+    }
+
+    /// Whether any group or target is waiting for a reconnect attempt
+    pub fn has_pending_reconnects(&self) -> bool {
+        self.reconnect_tasks.lock().map(|tasks| !tasks.is_empty()).unwrap_or(false)
+    }
+
+    fn cancel_all_reconnects(&self) {
+        if let Ok(mut tasks) = self.reconnect_tasks.lock() {
+            for (_, task) in tasks.drain() {
+                task.abort();
+            }
+        }
+    }
+
+    /// Restart a group after an unexpected exit, bringing the relay back if it stopped
+    pub fn restart_crashed_group(
+        &self,
+        group_id: &str,
+        reconnection_state: ReconnectionState,
+        event_sink: Arc<dyn EventSink>,
+    ) -> Result<u32, String> {
+        let (group, incoming_url) = {
+            let active = self.active_groups.lock()
+                .map_err(|e| format!("Lock poisoned: {e}"))?;
+            let config = active.get(group_id)
+                .ok_or_else(|| "Group was stopped".to_string())?;
+            (config.group.clone(), config.incoming_url.clone())
+        };
+
+        if let Some(pid) = self.get_group_pid(group_id) {
+            return Ok(pid);
+        }
+
+        let pid = self.start_group_process(&group, Arc::clone(&event_sink))?;
+        if let Ok(mut processes) = self.processes.lock() {
+            if let Some(info) = processes.get_mut(group_id) {
+                info.reconnection_state = reconnection_state;
+            }
+        }
+
+        // The relay stops when its last group exits, so it may need to come back too
+        self.ensure_relay_running(&incoming_url, &event_sink)?;
+        self.ensure_slate_watch(&event_sink);
+        Ok(pid)
+    }
+
+    /// Respawn the output process of a target that failed while its group kept running
+    ///
+    /// Returns None when there is nothing to restart: the group is not running
+    /// (its own restart brings every target back) or the target was removed or disabled.
+    pub fn restart_failed_target(
+        &self,
+        group_id: &str,
+        target_id: &str,
+        reconnection_state: ReconnectionState,
+        event_sink: Arc<dyn EventSink>,
+    ) -> Result<Option<u32>, String> {
+        let Some(group) = self.active_group(group_id) else {
+            return Ok(None);
+        };
+        let Some(target) = self.enabled_targets(&group).into_iter().find(|t| t.id == target_id).cloned() else {
+            return Ok(None);
+        };
+        if let Some(pid) = self.target_processes.lock()
+            .map_err(|e| format!("Lock poisoned: {e}"))?
+            .get(target_id)
+            .map(|process| process.child.id())
+        {
+            return Ok(Some(pid));
+        }
+
+        let running = self.processes.lock()
+            .map_err(|e| format!("Lock poisoned: {e}"))?
+            .get(group_id)
+            .map(|info| (info.child.id(), Arc::clone(&info.fanout), info.renditions.clone()));
+        let Some((group_pid, fanout, encoded_renditions)) = running else {
+            return Ok(None);
+        };
+
+        self.target_statuses.add_target(group_id, group_pid, &target, event_sink.as_ref());
+        let video_index = Self::target_video_index(&target, &encoded_renditions).unwrap_or(0);
+        let output = self.prepare_target_output(&group, &target)?;
+        let pid = self.spawn_target_process(&group, output, video_index, group_pid, &fanout, &event_sink)?;
+        if let Ok(mut targets) = self.target_processes.lock() {
+            if let Some(process) = targets.get_mut(target_id) {
+                process.reconnection_state = reconnection_state;
+            }
+        }
+        Ok(Some(pid))
+    }
+
+    /// Set the active profile name (used for recording filenames)
+    pub fn set_profile_name(&self, name: Option<String>) {
+        if let Ok(mut guard) = self.profile_name.lock() {
+            *guard = name;
+        }
+    }
+
+    /// Directory record targets write into
+    pub fn recordings_dir(&self) -> &Path {
+        &self.recordings_dir
+    }
+
+    /// Directory HLS targets write into
+    pub fn hls_dir(&self) -> &Path {
+        &self.hls_dir
+    }
+
+    /// HLS targets of the currently active groups, as (group_id, target)
+    pub fn active_hls_targets(&self) -> Vec<(String, StreamTarget)> {
+        let active = match self.active_groups.lock() {
+            Ok(active) => active,
+            Err(_) => return Vec::new(),
+        };
+        let disabled = self.disabled_targets.lock().unwrap_or_else(|e| e.into_inner());
+
+        active.values()
+            .flat_map(|cfg| {
+                cfg.group.stream_targets.iter()
+                    .filter(|target| target.protocol == TargetProtocol::Hls && !disabled.contains(&target.id))
+                    .map(|target| (cfg.group.id.clone(), target.clone()))
+            })
+            .collect()
+    }
+
+    /// Resolved share token of an active HLS target (None if unset or not streaming)
+    pub fn hls_share_token(&self, target_id: &str) -> Option<String> {
+        self.active_hls_targets()
+            .into_iter()
+            .find(|(_, target)| target.id == target_id)
+            .map(|(_, target)| Self::resolve_stream_key(&target.stream_key))
+            .filter(|token| !token.is_empty())
+    }
+
+    /// Renditions referenced by at least one enabled target, in group order
+    ///
+    /// Unused renditions are not encoded, so disabling a target frees its encoder.
+    fn renditions_in_use(&self, group: &OutputGroup) -> Vec<Rendition> {
+        let disabled = self.disabled_targets.lock().unwrap_or_else(|e| {
+            log::warn!("Disabled targets mutex poisoned (renditions_in_use), recovering: {e}");
+            e.into_inner()
+        });
+        group
+            .renditions
+            .iter()
+            .filter(|rendition| {
+                group.stream_targets.iter().any(|target| {
+                    !disabled.contains(&target.id)
+                        && target.rendition_id.as_deref() == Some(rendition.id.as_str())
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Per-rendition stats entries in output stream order (empty without renditions)
+    fn rendition_stats_layout(group: &OutputGroup, renditions: &[Rendition]) -> Vec<RenditionStats> {
+        if renditions.is_empty() {
+            return Vec::new();
+        }
+
+        let main = RenditionStats {
+            rendition_id: None,
+            name: group.name.clone(),
+            width: group.video.width,
+            height: group.video.height,
+            fps: group.video.fps,
+            bitrate: 0.0,
+        };
+        std::iter::once(main)
+            .chain(renditions.iter().map(|rendition| RenditionStats {
+                rendition_id: Some(rendition.id.clone()),
+                name: rendition.name.clone(),
+                width: rendition.video.width,
+                height: rendition.video.height,
+                fps: rendition.video.fps,
+                bitrate: 0.0,
+            }))
+            .collect()
+    }
+
     /// Normalize an RTMP URL for consistency
     fn normalize_rtmp_url(url: &str) -> String {
         let mut url = url.trim().to_string();
@@ -238,23 +765,31 @@ impl FFmpegHandler {
     }
 
 
+    /// URL schemes that may carry credentials in FFmpeg arguments and log lines
+    const REDACTED_SCHEMES: [&'static str; 4] = ["rtmp://", "rtmps://", "srt://", "rist://"];
+
     /// Sanitize a single argument with platform context for accurate redaction
     fn sanitize_arg_with_context(&self, arg: &str, group: &OutputGroup) -> String {
-        if !(arg.contains("rtmp://") || arg.contains("rtmps://")) {
+        let arg = &PlatformRegistry::redact_bearer_tokens(arg);
+        if !Self::REDACTED_SCHEMES.iter().any(|scheme| arg.contains(scheme)) {
             return arg.to_string();
         }
 
         let mut parts = Vec::new();
         for segment in arg.split('|') {
-            let redacted = if let Some(pos) = segment.find("rtmp://").or_else(|| segment.find("rtmps://")) {
-                let prefix = &segment[..pos];
-                let url_start = pos;
+            let url_start = Self::REDACTED_SCHEMES.iter().filter_map(|scheme| segment.find(scheme)).min();
+            let redacted = if let Some(url_start) = url_start {
+                let prefix = &segment[..url_start];
                 let url_end = segment[url_start..].find(' ').map(|i| url_start + i).unwrap_or(segment.len());
                 let url = &segment[url_start..url_end];
                 let suffix = &segment[url_end..];
 
+                // Platform templates only describe RTMP URLs; SRT/RIST secrets live in the query
+                let is_rtmp = url.starts_with("rtmp://") || url.starts_with("rtmps://");
+
                 // Try to find matching target to get platform
                 let platform_redacted = group.stream_targets.iter()
+                    .filter(|_| is_rtmp)
                     .find(|target| {
                         // Check if this URL belongs to this target by matching the base URL
                         let normalized = Self::normalize_rtmp_url(&target.url);
@@ -283,28 +818,32 @@ impl FFmpegHandler {
 
     /// Sanitize all FFmpeg arguments (redact stream keys) with platform-aware redaction
     fn sanitize_ffmpeg_args(&self, args: &[String], group: &OutputGroup) -> Vec<String> {
-        args.iter().map(|arg| self.sanitize_arg_with_context(arg, group)).collect()
+        args.iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                // WHIP bearer tokens are passed as the value of -authorization
+                if index > 0 && args[index - 1] == "-authorization" {
+                    "***".to_string()
+                } else {
+                    self.sanitize_arg_with_context(arg, group)
+                }
+            })
+            .collect()
     }
 
     /// Static version of sanitize_arg for use in background threads
     /// Uses generic platform-agnostic redaction
     fn sanitize_arg_static(arg: &str) -> String {
-        if !(arg.contains("rtmp://") || arg.contains("rtmps://")) {
+        let arg = &PlatformRegistry::redact_bearer_tokens(arg);
+        if !Self::REDACTED_SCHEMES.iter().any(|scheme| arg.contains(scheme)) {
             return arg.to_string();
         }
 
         let mut parts = Vec::new();
         for segment in arg.split('|') {
-            let redacted = if let Some(pos) = segment.find("rtmp://") {
-                let prefix = &segment[..pos];
-                let url_start = pos;
-                let url_end = segment[url_start..].find(' ').map(|i| url_start + i).unwrap_or(segment.len());
-                let url = &segment[url_start..url_end];
-                let suffix = &segment[url_end..];
-                format!("{prefix}{}{suffix}", PlatformRegistry::generic_redact(url))
-            } else if let Some(pos) = segment.find("rtmps://") {
-                let prefix = &segment[..pos];
-                let url_start = pos;
+            let url_start = Self::REDACTED_SCHEMES.iter().filter_map(|scheme| segment.find(scheme)).min();
+            let redacted = if let Some(url_start) = url_start {
+                let prefix = &segment[..url_start];
                 let url_end = segment[url_start..].find(' ').map(|i| url_start + i).unwrap_or(segment.len());
                 let url = &segment[url_start..url_end];
                 let suffix = &segment[url_end..];
@@ -366,85 +905,160 @@ impl FFmpegHandler {
         }
     }
 
-    fn collect_active_group_ids(&self) -> Result<HashSet<String>, String> {
-        let active = self.active_groups.lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        Ok(active.keys().cloned().collect())
-    }
-
-    fn resolve_active_incoming_url(&self) -> Result<String, String> {
-        let active = self.active_groups.lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        let mut incoming_url: Option<String> = None;
-        for cfg in active.values() {
-            if let Some(existing) = &incoming_url {
-                if existing != &cfg.incoming_url {
-                    return Err("Incoming URL differs across active groups".to_string());
-                }
-            } else {
-                incoming_url = Some(cfg.incoming_url.clone());
-            }
-        }
-        incoming_url.ok_or_else(|| "No active groups available".to_string())
-    }
-
     fn get_group_pid(&self, group_id: &str) -> Option<u32> {
         self.processes.lock().ok()
             .and_then(|procs| procs.get(group_id).map(|info| info.child.id()))
     }
 
-    fn relay_needs_restart(&self, desired_group_ids: &HashSet<String>) -> Result<bool, String> {
-        let relay_guard = self.relay.lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        if let Some(relay) = relay_guard.as_ref() {
-            if !relay.output_groups.is_superset(desired_group_ids) {
-                let processes = self.processes.lock()
-                    .map_err(|e| format!("Lock poisoned: {e}"))?;
-                return Ok(!processes.is_empty());
-            }
-        }
-        Ok(false)
-    }
+    fn start_group_process(
+        &self,
+        group: &OutputGroup,
+        event_sink: Arc<dyn EventSink>,
+    ) -> Result<u32, String> {
+        let renditions = self.renditions_in_use(group);
+        let outputs = self.enabled_targets(group)
+            .into_iter()
+            .map(|target| self.prepare_target_output(group, target))
+            .collect::<Result<Vec<_>, String>>()?;
+        let args = self.build_args(group, &renditions);
+        let sanitized = self.sanitize_ffmpeg_args(&args, group);
+        log::info!(
+            "Starting FFmpeg group {}: {} {}",
+            group.id,
+            self.ffmpeg_path,
+            sanitized.join(" ")
+        );
 
-    fn is_relay_active(&self) -> Result<bool, String> {
-        let mut relay_guard = self.relay.lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        if let Some(relay) = relay_guard.as_mut() {
-            if let Ok(Some(_)) = relay.child.try_wait() {
-                *relay_guard = None;
-                return Ok(false);
-            }
-            return Ok(true);
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        if let Some(priority) = &group.priority {
+            apply_priority(&mut cmd, priority);
         }
-        Ok(false)
-    }
+        let mut child = cmd.spawn()
+            .map_err(|e| format!("Failed to start FFmpeg: {e}"))?;
 
-    fn stop_group_for_restart(&self, group_id: &str) -> Result<(), String> {
-        if let Ok(mut stopping) = self.stopping_groups.lock() {
-            stopping.insert(group_id.to_string());
+        let pid = child.id();
+        let group_id = group.id.clone();
+        if let Some(priority) = &group.priority {
+            check_priority(pid, &format!("group {group_id}"), priority);
         }
-        let removed = {
+
+        let stderr = child.stderr.take()
+            .ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+        let stdout = child.stdout.take()
+            .ok_or_else(|| "Failed to capture FFmpeg output".to_string())?;
+        let stdin = child.stdin.take()
+            .ok_or_else(|| "Failed to capture FFmpeg input".to_string())?;
+        spawn_stdin_writer(self.switcher.subscribe(&group_id), stdin, group_id.clone());
+
+        let fanout = Arc::new(OutputFanout::new());
+        let rendition_ids: Vec<String> = renditions.iter().map(|r| r.id.clone()).collect();
+        {
             let mut processes = self.processes.lock()
                 .map_err(|e| format!("Lock poisoned: {e}"))?;
-            processes.remove(group_id)
-        };
+            processes.insert(group_id.clone(), ProcessInfo {
+                child,
+                start_time: Instant::now(),
+                group_id: group_id.clone(),
+                reconnection_state: ReconnectionState::new(),
+                fanout: Arc::clone(&fanout),
+                renditions: rendition_ids.clone(),
+            });
+        }
 
-        if let Some(mut info) = removed {
-            self.stop_child(&mut info.child);
+        self.relay_refcount.fetch_add(1, Ordering::SeqCst);
+
+        let targets: Vec<&StreamTarget> = outputs.iter().map(|output| &output.target).collect();
+        self.target_statuses.begin_process(&group_id, pid, &targets, event_sink.as_ref());
+
+        // The meter counts the encoded output as it passes through the fanout
+        let rendition_stats = Self::rendition_stats_layout(group, &renditions);
+        let meter = Arc::new(BitrateMeter::new(rendition_stats.len()));
+        let pump_meter = Arc::clone(&meter);
+        let pump_fanout = Arc::clone(&fanout);
+        let pump_group_id = group_id.clone();
+        thread::spawn(move || {
+            pump_fanout.pump(stdout, &pump_group_id, |packets| pump_meter.record(packets));
+        });
+
+        for output in outputs {
+            let target_id = output.target.id.clone();
+            let video_index = Self::target_video_index(&output.target, &rendition_ids).unwrap_or(0);
+            if let Err(error) = self.spawn_target_process(group, output, video_index, pid, &fanout, &event_sink) {
+                log::error!("[FFmpeg:{group_id}] Failed to start target {target_id}: {error}");
+                self.target_statuses.mark_failed(&target_id, pid, error, event_sink.as_ref());
+            }
         }
-        Ok(())
+
+        let event_sink_clone = Arc::clone(&event_sink);
+        let processes_clone = Arc::clone(&self.processes);
+        let relay_clone = Arc::clone(&self.relay);
+        let stopping_clone = Arc::clone(&self.stopping_groups);
+        let relay_refcount_clone = Arc::clone(&self.relay_refcount);
+        let target_statuses = self.target_statuses.clone();
+        // Resolved per process so a settings change applies from the next start
+        let reconnect_tx = self.reconnection_config()
+            .enabled
+            .then(|| self.reconnect_tx.get().cloned())
+            .flatten();
+        let group_id_clone = group_id.clone();
+
+        thread::spawn(move || {
+            Self::stats_reader(
+                stderr,
+                pid,
+                group_id_clone,
+                target_statuses,
+                reconnect_tx,
+                Some(meter),
+                rendition_stats,
+                event_sink_clone,
+                processes_clone,
+                stopping_clone,
+                relay_clone,
+                relay_refcount_clone,
+            );
+        });
+
+        Ok(pid)
     }
 
-    fn start_group_process(
+    /// Targets of a group that are not disabled
+    fn enabled_targets<'a>(&self, group: &'a OutputGroup) -> Vec<&'a StreamTarget> {
+        let disabled = self.disabled_targets.lock().unwrap_or_else(|e| {
+            log::warn!("Disabled targets mutex poisoned (enabled_targets), recovering: {e}");
+            e.into_inner()
+        });
+        group.stream_targets.iter()
+            .filter(|target| !disabled.contains(&target.id))
+            .collect()
+    }
+
+    /// Spawn the output process of one target and subscribe it to its group's output
+    fn spawn_target_process(
         &self,
         group: &OutputGroup,
-        event_sink: Arc<dyn EventSink>,
+        output: TargetOutput,
+        video_index: usize,
+        group_pid: u32,
+        fanout: &Arc<OutputFanout>,
+        event_sink: &Arc<dyn EventSink>,
     ) -> Result<u32, String> {
-        let args = self.build_args(group);
+        let target_id = output.target.id.clone();
+        // Never run two processes writing to the same destination
+        self.stop_target_process(&target_id);
+
+        let args = Self::build_target_args(group, &output, video_index);
         let sanitized = self.sanitize_ffmpeg_args(&args, group);
         log::info!(
-            "Starting FFmpeg group {}: {} {}",
+            "Starting FFmpeg target {}/{}: {} {}",
             group.id,
+            target_id,
             self.ffmpeg_path,
             sanitized.join(" ")
         );
@@ -456,83 +1070,295 @@ impl FFmpegHandler {
             .stderr(Stdio::piped());
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
+        if let Some(priority) = &group.priority {
+            apply_priority(&mut cmd, priority);
+        }
         let mut child = cmd.spawn()
             .map_err(|e| format!("Failed to start FFmpeg: {e}"))?;
 
-        let pid = child.id();
-        let group_id = group.id.clone();
-
-        let stderr = child.stderr.take()
-            .ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+        let pid = child.id();
+        let label = format!("{}/{target_id}", group.id);
+        if let Some(priority) = &group.priority {
+            check_priority(pid, &format!("target {label}"), priority);
+        }
+        let (Some(stdin), Some(stderr)) = (child.stdin.take(), child.stderr.take()) else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Failed to capture target FFmpeg pipes".to_string());
+        };
+        spawn_stdin_writer(fanout.subscribe(&target_id), stdin, label);
 
+        let protocol = output.target.protocol;
         {
-            let mut processes = self.processes.lock()
+            let mut targets = self.target_processes.lock()
                 .map_err(|e| format!("Lock poisoned: {e}"))?;
-            processes.insert(group_id.clone(), ProcessInfo {
+            targets.insert(target_id.clone(), TargetProcess {
                 child,
                 start_time: Instant::now(),
-                group_id: group_id.clone(),
+                group_id: group.id.clone(),
+                target: output.target,
+                fanout: Arc::clone(fanout),
                 reconnection_state: ReconnectionState::new(),
             });
         }
 
-        self.relay_refcount.fetch_add(1, Ordering::SeqCst);
-
-        let event_sink_clone = Arc::clone(&event_sink);
-        let processes_clone = Arc::clone(&self.processes);
-        let meter_bytes = self.start_bitrate_meter(&group_id, Arc::clone(&processes_clone));
-        let relay_clone = Arc::clone(&self.relay);
-        let stopping_clone = Arc::clone(&self.stopping_groups);
-        let relay_refcount_clone = Arc::clone(&self.relay_refcount);
-        let port_assignments_clone = Arc::clone(&self.port_assignments);
-        let next_port_offset_clone = Arc::clone(&self.next_port_offset);
-        let group_id_clone = group_id.clone();
+        // Watch recordings until this specific process is gone (restarts spawn new watchers)
+        if let Some(plan) = &output.recording {
+            let target_processes = Arc::clone(&self.target_processes);
+            let watched_target = target_id.clone();
+            plan.spawn_watcher(&group.id, Arc::clone(event_sink), move || {
+                target_processes.lock()
+                    .map(|targets| targets.get(&watched_target).is_some_and(|t| t.child.id() == pid))
+                    .unwrap_or(false)
+            });
+        }
 
+        let target_processes = Arc::clone(&self.target_processes);
+        let target_statuses = self.target_statuses.clone();
+        let reconnect_tx = self.reconnection_config()
+            .enabled
+            .then(|| self.reconnect_tx.get().cloned())
+            .flatten();
+        let event_sink = Arc::clone(event_sink);
+        let group_id = group.id.clone();
         thread::spawn(move || {
-            Self::stats_reader(
+            Self::target_reader(
                 stderr,
-                group_id_clone,
-                meter_bytes,
-                event_sink_clone,
-                processes_clone,
-                stopping_clone,
-                relay_clone,
-                relay_refcount_clone,
-                port_assignments_clone,
-                next_port_offset_clone,
+                pid,
+                group_pid,
+                group_id,
+                target_id,
+                protocol,
+                target_processes,
+                target_statuses,
+                reconnect_tx,
+                event_sink,
             );
         });
 
         Ok(pid)
     }
 
-    fn restart_relay_with_groups(
+    /// Background thread that follows a target process and reports its connection state
+    #[allow(clippy::too_many_arguments)]
+    fn target_reader(
+        stderr: ChildStderr,
+        pid: u32,
+        group_pid: u32,
+        group_id: String,
+        target_id: String,
+        protocol: TargetProtocol,
+        target_processes: Arc<Mutex<HashMap<String, TargetProcess>>>,
+        target_statuses: TargetStatusTracker,
+        reconnect_tx: Option<mpsc::UnboundedSender<GroupExit>>,
+        event_sink: Arc<dyn EventSink>,
+    ) {
+        let reader = BufReader::new(stderr);
+        let mut recent_lines: VecDeque<String> = VecDeque::with_capacity(20);
+
+        for line in reader.lines().map_while(Result::ok) {
+            let sanitized_line = Self::sanitize_arg_static(&line);
+            if line.trim_start().starts_with("progress=") {
+                target_statuses.mark_live(&target_id, group_pid, event_sink.as_ref());
+                continue;
+            }
+
+            if recent_lines.len() == 20 {
+                recent_lines.pop_front();
+            }
+            recent_lines.push_back(sanitized_line.clone());
+
+            if line.contains("[error]")
+                || line.contains("[warning]")
+                || line.contains("Error")
+                || line.contains("error")
+            {
+                log::warn!("[FFmpeg:{group_id}/{target_id}] {sanitized_line}");
+            }
+        }
+
+        // A process still registered under this PID exited on its own
+        let exited = target_processes.lock().ok().and_then(|mut targets| {
+            if targets.get(&target_id).is_some_and(|t| t.child.id() == pid) {
+                targets.remove(&target_id)
+            } else {
+                None
+            }
+        });
+        let Some(mut process) = exited else {
+            return;
+        };
+        process.fanout.unsubscribe(&target_id);
+
+        match process.child.wait() {
+            // Input ended because the group process stopped; the group reports that itself
+            Ok(status) if status.success() => {}
+            status => {
+                let classified = classify_ffmpeg_error(&recent_lines);
+                let kind = classified.as_ref().map_or(FFmpegErrorKind::Unknown, |error| error.kind);
+                let reason = classified.map(|error| error.message).unwrap_or_else(|| match status {
+                    Ok(status) => format!("FFmpeg exited with code {}", status.code().unwrap_or(-1)),
+                    Err(_) => "FFmpeg process terminated unexpectedly".to_string(),
+                });
+                let error = describe_target_failure(&reason, protocol, &recent_lines);
+                log::error!("[FFmpeg:{group_id}/{target_id}] Output failed: {error}");
+                target_statuses.mark_failed(&target_id, group_pid, error.clone(), event_sink.as_ref());
+
+                // Only this target is restarted; the group and its other targets keep running
+                if let Some(tx) = reconnect_tx.as_ref() {
+                    let _ = tx.send(GroupExit {
+                        group_id,
+                        target_id: Some(target_id),
+                        error,
+                        kind,
+                        uptime: process.start_time.elapsed(),
+                        reconnection_state: process.reconnection_state,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Stop a target's output process, letting it finish writing what it has
+    fn stop_target_process(&self, target_id: &str) -> bool {
+        self.cancel_target_reconnect(target_id);
+        let removed = self.target_processes.lock()
+            .ok()
+            .and_then(|mut targets| targets.remove(target_id));
+        let Some(mut process) = removed else {
+            return false;
+        };
+
+        // Ending the subscription closes stdin, so FFmpeg writes trailers and exits
+        process.fanout.unsubscribe(target_id);
+        Self::stop_child(&mut process.child, Instant::now() + Self::TARGET_STOP_TIMEOUT);
+        true
+    }
+
+    /// Stop every output process of a group
+    fn stop_group_targets(&self, group_id: &str) {
+        let mut removed: Vec<(String, TargetProcess)> = match self.target_processes.lock() {
+            Ok(mut targets) => {
+                let ids: Vec<String> = targets.iter()
+                    .filter(|(_, process)| process.group_id == group_id)
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                ids.into_iter()
+                    .filter_map(|id| targets.remove(&id).map(|process| (id, process)))
+                    .collect()
+            }
+            Err(_) => return,
+        };
+
+        // Close every input first so the processes wind down in parallel
+        for (target_id, process) in &removed {
+            process.fanout.unsubscribe(target_id);
+        }
+        let deadline = Instant::now() + Self::TARGET_STOP_TIMEOUT;
+        for (_, process) in &mut removed {
+            Self::stop_child(&mut process.child, deadline);
+        }
+    }
+
+    /// Apply target changes to a live group without interrupting its other outputs
+    ///
+    /// Added, enabled or edited targets get a new output process; removed or
+    /// disabled ones are stopped. Encoding changes, or enabling a target whose
+    /// rendition the group is not encoding, restart the group instead.
+    /// Returns None if the group has no running process.
+    pub fn update_live_targets(
         &self,
-        requested_group_id: &str,
+        group: &OutputGroup,
         event_sink: Arc<dyn EventSink>,
-    ) -> Result<u32, String> {
-        let incoming_url = self.resolve_active_incoming_url()?;
-        let desired_group_ids = self.collect_active_group_ids()?;
+    ) -> Result<Option<u32>, String> {
+        group.validate()?;
 
-        let running_group_ids = self.get_active_group_ids();
-        for group_id in running_group_ids {
-            let _ = self.stop_group_for_restart(&group_id);
+        let (previous, incoming_url) = {
+            let active = self.active_groups.lock()
+                .map_err(|e| format!("Lock poisoned: {e}"))?;
+            match active.get(&group.id) {
+                Some(config) => (config.group.clone(), config.incoming_url.clone()),
+                None => return Ok(None),
+            }
+        };
+
+        let running = self.processes.lock()
+            .map_err(|e| format!("Lock poisoned: {e}"))?
+            .get(&group.id)
+            .map(|info| (info.child.id(), Arc::clone(&info.fanout), info.renditions.clone()));
+        let Some((pid, fanout, encoded_renditions)) = running else {
+            // Pending reconnect: the next start picks up the new targets
+            self.record_active_group(group, &incoming_url)?;
+            return Ok(None);
+        };
+
+        let desired = self.enabled_targets(group);
+        let needs_restart = !Self::same_encoding(&previous, group)
+            || desired.iter().any(|t| Self::target_video_index(t, &encoded_renditions).is_none());
+        if needs_restart {
+            log::info!("[FFmpeg:{}] Encoding changed, restarting group", group.id);
+            return self.restart_group(&group.id, group, &incoming_url, event_sink).map(Some);
         }
-        self.stop_relay();
+        self.record_active_group(group, &incoming_url)?;
 
-        let active_groups = self.active_groups.lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        let mut requested_pid: Option<u32> = None;
-        for (group_id, cfg) in active_groups.iter() {
-            let pid = self.start_group_process(&cfg.group, Arc::clone(&event_sink))?;
-            if group_id == requested_group_id {
-                requested_pid = Some(pid);
+        let running_targets: HashMap<String, StreamTarget> = self.target_processes.lock()
+            .map_err(|e| format!("Lock poisoned: {e}"))?
+            .iter()
+            .filter(|(_, process)| process.group_id == group.id)
+            .map(|(id, process)| (id.clone(), process.target.clone()))
+            .collect();
+
+        for (target_id, running_target) in &running_targets {
+            let keep = desired.iter().any(|t| &t.id == target_id && Self::same_settings(*t, running_target));
+            if !keep {
+                log::info!("[FFmpeg:{}] Stopping output {target_id}", group.id);
+                self.stop_target_process(target_id);
+                self.target_statuses.remove_target(target_id);
+            }
+        }
+
+        let mut first_error: Option<String> = None;
+        for target in desired {
+            let unchanged = running_targets
+                .get(&target.id)
+                .is_some_and(|running_target| Self::same_settings(target, running_target));
+            if unchanged {
+                continue;
+            }
+
+            log::info!("[FFmpeg:{}] Starting output {}", group.id, target.id);
+            self.stop_target_process(&target.id);
+            self.target_statuses.add_target(&group.id, pid, target, event_sink.as_ref());
+            let video_index = Self::target_video_index(target, &encoded_renditions).unwrap_or(0);
+            let started = self.prepare_target_output(group, target)
+                .and_then(|output| self.spawn_target_process(group, output, video_index, pid, &fanout, &event_sink));
+            if let Err(error) = started {
+                log::error!("[FFmpeg:{}] Failed to start target {}: {error}", group.id, target.id);
+                self.target_statuses.mark_failed(&target.id, pid, error.clone(), event_sink.as_ref());
+                first_error.get_or_insert(format!("Failed to start '{}': {error}", target.name));
             }
         }
 
-        self.ensure_relay_running(&incoming_url, &desired_group_ids)?;
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(Some(pid)),
+        }
+    }
+
+    /// Whether two groups encode identically (everything but their targets matches)
+    fn same_encoding(a: &OutputGroup, b: &OutputGroup) -> bool {
+        let strip = |group: &OutputGroup| OutputGroup {
+            stream_targets: Vec::new(),
+            ..group.clone()
+        };
+        Self::same_settings(&strip(a), &strip(b))
+    }
 
-        requested_pid.ok_or_else(|| "Requested group not started".to_string())
+    fn same_settings<T: serde::Serialize>(a: &T, b: &T) -> bool {
+        matches!(
+            (serde_json::to_value(a), serde_json::to_value(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
     }
 
     /// Start streaming for an output group with stats monitoring
@@ -542,25 +1368,19 @@ impl FFmpegHandler {
         incoming_url: &str,
         event_sink: Arc<dyn EventSink>,
     ) -> Result<u32, String> {
+        group.validate()?;
+        self.report_platform_warnings(std::slice::from_ref(group), &event_sink);
         self.record_active_group(group, incoming_url)?;
 
         if let Some(pid) = self.get_group_pid(&group.id) {
             return Ok(pid);
         }
 
-        let desired_group_ids = self.collect_active_group_ids()?;
-        if self.relay_needs_restart(&desired_group_ids)? {
-            return self.restart_relay_with_groups(&group.id, event_sink);
-        }
-
-        if !self.is_relay_active()? {
-            let pid = self.start_group_process(group, Arc::clone(&event_sink))?;
-            self.ensure_relay_running(incoming_url, &desired_group_ids)?;
-            return Ok(pid);
-        }
-
-        self.ensure_relay_running(incoming_url, &desired_group_ids)?;
-        self.start_group_process(group, event_sink)
+        // Groups subscribe to the switcher, so one can join a running relay
+        let pid = self.start_group_process(group, Arc::clone(&event_sink))?;
+        self.ensure_relay_running(incoming_url, &event_sink)?;
+        self.ensure_slate_watch(&event_sink);
+        Ok(pid)
     }
 
     /// Start streaming for multiple output groups in one batch
@@ -574,18 +1394,22 @@ impl FFmpegHandler {
             return Err("Streams already running".to_string());
         }
 
+        // Reject invalid protocol settings before anything is spawned
+        for group in groups {
+            group.validate()?;
+        }
+        self.report_platform_warnings(groups, &event_sink);
+
         if let Ok(mut active) = self.active_groups.lock() {
             active.clear();
         }
 
-        let mut desired_group_ids: HashSet<String> = HashSet::new();
         let mut start_groups: Vec<OutputGroup> = Vec::new();
         for group in groups {
             if group.stream_targets.is_empty() {
                 continue;
             }
             self.record_active_group(group, incoming_url)?;
-            desired_group_ids.insert(group.id.clone());
             start_groups.push(group.clone());
         }
 
@@ -599,87 +1423,40 @@ impl FFmpegHandler {
             pids.push(pid);
         }
 
-        self.ensure_relay_running(incoming_url, &desired_group_ids)?;
+        self.ensure_relay_running(incoming_url, &event_sink)?;
+        self.ensure_slate_watch(&event_sink);
 
         Ok(pids)
     }
 
-    fn start_bitrate_meter(
-        &self,
-        group_id: &str,
-        processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
-    ) -> Option<Arc<AtomicU64>> {
-        let port = self.meter_port_for_group(group_id);
-        let bind_addr = format!("{}:{}", Self::METER_HOST, port);
-        let socket = match UdpSocket::bind(&bind_addr) {
-            Ok(socket) => socket,
-            Err(err) => {
-                log::warn!(
-                    "Failed to bind bitrate meter for group {group_id} on {bind_addr}: {err}"
-                );
-                return None;
-            }
-        };
-
-        if let Err(err) = socket.set_read_timeout(Some(Duration::from_millis(250))) {
-            log::warn!(
-                "Failed to set meter read timeout for group {group_id} on {bind_addr}: {err}"
-            );
-        }
-
-        let bytes = Arc::new(AtomicU64::new(0));
-        let bytes_clone = Arc::clone(&bytes);
-        let group_id = group_id.to_string();
-
-        thread::spawn(move || {
-            let mut buffer = [0u8; 2048];
-            loop {
-                match socket.recv_from(&mut buffer) {
-                    Ok((len, _)) => {
-                        bytes_clone.fetch_add(len as u64, Ordering::Relaxed);
-                    }
-                    Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {}
-                    Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
-                    Err(_) => break,
-                }
-
-                if let Ok(procs) = processes.lock() {
-                    if !procs.contains_key(&group_id) {
-                        break;
-                    }
-                } else {
-                    break;
-                }
-            }
-        });
-
-        Some(bytes)
-    }
-
     /// Background thread that reads FFmpeg stderr and emits stats events
     #[allow(clippy::too_many_arguments)]
     fn stats_reader(
         stderr: std::process::ChildStderr,
+        pid: u32,
         group_id: String,
-        meter_bytes: Option<Arc<AtomicU64>>,
+        target_statuses: TargetStatusTracker,
+        reconnect_tx: Option<mpsc::UnboundedSender<GroupExit>>,
+        meter: Option<Arc<BitrateMeter>>,
+        renditions: Vec<RenditionStats>,
         event_sink: Arc<dyn EventSink>,
         processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
         stopping_groups: Arc<Mutex<HashSet<String>>>,
         relay: Arc<Mutex<Option<RelayProcess>>>,
         relay_refcount: Arc<AtomicUsize>,
-        port_assignments: Arc<Mutex<HashMap<String, u16>>>,
-        next_port_offset: Arc<AtomicU16>,
     ) {
         let reader = BufReader::new(stderr);
         let mut stats = StreamStats::new(group_id.clone());
+        stats.renditions = renditions;
         let mut last_emit = Instant::now();
         let emit_interval = Duration::from_millis(1000); // Emit every second
         let mut was_intentionally_stopped = false;
         let mut recent_lines: VecDeque<String> = VecDeque::with_capacity(40);
-        let mut last_meter_bytes = meter_bytes
+        let mut last_meter_bytes = meter
             .as_ref()
-            .map(|bytes| bytes.load(Ordering::Relaxed))
+            .map(|meter| meter.total.load(Ordering::Relaxed))
             .unwrap_or(0);
+        let mut last_stream_bytes: Vec<u64> = Vec::new();
         let mut last_meter_instant = Instant::now();
         let mut has_meter_sample = false;
         let mut smoothed_bitrate = 0.0;
@@ -724,23 +1501,39 @@ impl FFmpegHandler {
                     }
                 }
 
-                if meter_bytes.is_none() && stats.bitrate == 0.0 && stats.size > 0 && stats.time > 0.0 {
+                if meter.is_none() && stats.bitrate == 0.0 && stats.size > 0 && stats.time > 0.0 {
                     let avg_kbps = (stats.size as f64 * 8.0) / 1000.0 / stats.time;
                     if avg_kbps.is_finite() && avg_kbps > 0.0 {
                         stats.bitrate = avg_kbps;
                     }
                 }
 
-                if let Some(bytes) = meter_bytes.as_ref() {
+                if let Some(meter) = meter.as_ref() {
                     let now = Instant::now();
-                    let current_bytes = bytes.load(Ordering::Relaxed);
+                    let current_bytes = meter.total.load(Ordering::Relaxed);
+                    let current_stream_bytes: Vec<u64> = meter
+                        .video_streams
+                        .iter()
+                        .map(|counter| counter.load(Ordering::Relaxed))
+                        .collect();
                     if has_meter_sample {
                         let elapsed = now.duration_since(last_meter_instant).as_secs_f64();
                         let delta_bytes = current_bytes.saturating_sub(last_meter_bytes);
+                        let alpha = 0.2;
                         if elapsed > 0.0 {
+                            // Per-rendition bitrates use the same smoothing as the group total
+                            let stream_deltas = current_stream_bytes.iter().zip(&last_stream_bytes);
+                            for (rendition, (current, last)) in stats.renditions.iter_mut().zip(stream_deltas) {
+                                let kbps = (current.saturating_sub(*last) as f64 * 8.0) / 1000.0 / elapsed;
+                                rendition.bitrate = if has_smoothed_bitrate {
+                                    rendition.bitrate * (1.0 - alpha) + kbps * alpha
+                                } else {
+                                    kbps
+                                };
+                            }
+
                             let kbps = (delta_bytes as f64 * 8.0) / 1000.0 / elapsed;
                             if kbps.is_finite() {
-                                let alpha = 0.2;
                                 if has_smoothed_bitrate {
                                     smoothed_bitrate = smoothed_bitrate * (1.0 - alpha) + kbps * alpha;
                                 } else {
@@ -756,6 +1549,7 @@ impl FFmpegHandler {
                         has_meter_sample = true;
                     }
                     last_meter_bytes = current_bytes;
+                    last_stream_bytes = current_stream_bytes;
                     last_meter_instant = now;
                 }
 
@@ -783,24 +1577,16 @@ impl FFmpegHandler {
                 stopping.remove(&group_id);
             }
             // Intentional stop via stop() - process already removed
+            target_statuses.clear_process(pid);
             emit_event(event_sink.as_ref(), "stream_ended", &group_id);
         } else {
             // Process ended unexpectedly (crash, connection loss, etc.)
             // Remove from HashMap and check exit status
+            let mut exited_after: Option<(Duration, ReconnectionState)> = None;
             let exit_status = {
                 if let Ok(mut procs) = processes.lock() {
                     if let Some(mut info) = procs.remove(&group_id) {
-                        // Free the port assignment for this crashed group
-                        if let Ok(mut assignments) = port_assignments.lock() {
-                            if let Some(offset) = assignments.remove(&group_id) {
-                                log::debug!("Freed port offset {offset} from crashed group {group_id}");
-                                // Reset counter if all assignments are freed
-                                if assignments.is_empty() {
-                                    next_port_offset.store(0, Ordering::SeqCst);
-                                    log::debug!("All ports freed after crash, reset counter to 0");
-                                }
-                            }
-                        }
+                        exited_after = Some((info.start_time.elapsed(), info.reconnection_state.clone()));
 
                         // Try to get exit status
                         match info.child.try_wait() {
@@ -818,13 +1604,15 @@ impl FFmpegHandler {
 
             if let Ok(mut stopping) = stopping_groups.lock() {
                 if stopping.remove(&group_id) {
+                    target_statuses.clear_process(pid);
                     emit_event(event_sink.as_ref(), "stream_ended", &group_id);
                     return;
                 }
             }
 
-            // Parse error details from recent log lines
-            let error_details = Self::parse_error_details(&recent_lines);
+            // Classify the failure from recent log lines
+            let classified = classify_ffmpeg_error(&recent_lines);
+            let kind = classified.as_ref().map_or(FFmpegErrorKind::Unknown, |error| error.kind);
 
             // Determine if this was a crash or normal exit
             let error_message = match exit_status {
@@ -836,25 +1624,16 @@ impl FFmpegHandler {
                 Some(status) => {
                     // FFmpeg exited with error
                     let code = status.code().unwrap_or(-1);
-                    let base_msg = format!("FFmpeg exited with code {code}");
-
-                    // Append detailed error if available
-                    if let Some(details) = error_details {
-                        Some(format!("{base_msg}: {details}"))
-                    } else {
-                        Some(base_msg)
-                    }
+                    Some(format!("FFmpeg exited with code {code}"))
                 }
-                None => {
-                    // Couldn't get exit status
-                    let base_msg = "FFmpeg process terminated unexpectedly".to_string();
-                    if let Some(details) = error_details {
-                        Some(format!("{base_msg}: {details}"))
-                    } else {
-                        Some(base_msg)
-                    }
-                }
-            };
+                // Couldn't get exit status
+                None => Some("FFmpeg process terminated unexpectedly".to_string()),
+            }
+            // Append the classified cause if one was recognized
+            .map(|base_msg| match &classified {
+                Some(error) => format!("{base_msg}: {}", error.message),
+                None => base_msg,
+            });
 
             if let Some(error) = error_message {
                 log::error!("[FFmpeg:{group_id}] Stream crashed: {error}");
@@ -865,19 +1644,45 @@ impl FFmpegHandler {
                     }
                 }
 
-                // Emit stream_error event with group_id, error message, and reconnection hint
+                target_statuses.fail_process(pid, &error, event_sink.as_ref());
+
+                // Emit stream_error event with group_id, error message, and reconnection hint.
+                // Errors that would fail the same way again are not reconnected automatically.
+                let auto_reconnect = reconnect_tx.is_some() && kind.retryable();
+                let suggestion = if auto_reconnect {
+                    format!("{} Reconnecting automatically.", kind.suggestion())
+                } else if kind.retryable() {
+                    format!("{} Click retry to reconnect.", kind.suggestion())
+                } else {
+                    format!("{} Then start the stream again.", kind.suggestion())
+                };
                 emit_event(
                     event_sink.as_ref(),
                     "stream_error",
                     &serde_json::json!({
                         "groupId": group_id,
                         "error": error,
-                        "canRetry": true,
-                        "suggestion": "Stream connection lost. Click retry to reconnect automatically."
+                        "code": kind,
+                        "detail": classified.as_ref().map(|error| &error.line),
+                        "canRetry": kind.retryable(),
+                        "autoReconnect": auto_reconnect,
+                        "suggestion": suggestion
                     }),
                 );
+
+                if let (Some(tx), Some((uptime, reconnection_state))) = (reconnect_tx.as_ref(), exited_after) {
+                    let _ = tx.send(GroupExit {
+                        group_id: group_id.clone(),
+                        target_id: None,
+                        error,
+                        kind,
+                        uptime,
+                        reconnection_state,
+                    });
+                }
             } else {
                 // Clean exit (input ended)
+                target_statuses.clear_process(pid);
                 emit_event(event_sink.as_ref(), "stream_ended", &group_id);
             }
         }
@@ -893,9 +1698,8 @@ impl FFmpegHandler {
             }
             if let Ok(mut relay_guard) = relay.lock() {
                 if let Some(mut relay_proc) = relay_guard.take() {
-                    log::info!("Stopping relay process (no active groups)");
-                    let _ = relay_proc.child.kill();
-                    let _ = relay_proc.child.wait();
+                    log::info!("Stopping ingest (no active groups)");
+                    relay_proc.stop();
                 }
             }
         }
@@ -903,7 +1707,11 @@ impl FFmpegHandler {
 
     /// Stop streaming for an output group
     pub fn stop(&self, group_id: &str) -> Result<(), String> {
+        // Deactivate first: a reconnect registered after this point sees the group
+        // inactive, and one registered before it is aborted here
         self.remove_active_group(group_id);
+        self.cancel_reconnect(group_id);
+        self.target_statuses.clear_group(group_id);
         if let Ok(mut stopping) = self.stopping_groups.lock() {
             stopping.insert(group_id.to_string());
         }
@@ -915,17 +1723,19 @@ impl FFmpegHandler {
             (removed, should_stop_relay)
         };
 
+        self.switcher.unsubscribe(group_id);
         if let Some(mut info) = removed {
-            self.stop_child(&mut info.child);
-            // Free the port assignment for this group
-            self.free_port_offset(group_id);
+            Self::stop_child(&mut info.child, Instant::now() + Self::GROUP_STOP_TIMEOUT);
         }
+        self.stop_group_targets(group_id);
 
         // Only stop relay when ALL groups are stopped
         // Don't restart relay when stopping individual groups - this would interrupt
         // the input for remaining groups and cause them to fail
         if should_stop_relay {
             self.stop_relay();
+            Self::stop_slate(&self.slate_process);
+            self.switcher.reset();
         }
 
         Ok(())
@@ -936,22 +1746,26 @@ impl FFmpegHandler {
         if let Ok(mut active) = self.active_groups.lock() {
             active.clear();
         }
+        self.target_statuses.clear_all();
+        self.cancel_all_reconnects();
         let mut processes = self.processes.lock()
             .map_err(|e| format!("Lock poisoned: {e}"))?;
         let mut stopping = self.stopping_groups.lock()
             .map_err(|e| format!("Lock poisoned: {e}"))?;
+        self.switcher.close_all();
+        let deadline = Instant::now() + Self::GROUP_STOP_TIMEOUT;
+        let mut group_ids = Vec::with_capacity(processes.len());
         for (group_id, mut info) in processes.drain() {
-            stopping.insert(group_id);
-            self.stop_child(&mut info.child);
+            stopping.insert(group_id.clone());
+            Self::stop_child(&mut info.child, deadline);
+            group_ids.push(group_id);
         }
-        self.stop_relay();
-
-        // Clear all port assignments since all groups are stopped
-        if let Ok(mut assignments) = self.port_assignments.lock() {
-            assignments.clear();
-            self.next_port_offset.store(0, Ordering::SeqCst);
-            log::debug!("All groups stopped, cleared all port assignments");
+        for group_id in &group_ids {
+            self.stop_group_targets(group_id);
         }
+        self.stop_relay();
+        Self::stop_slate(&self.slate_process);
+        self.switcher.reset();
 
         Ok(())
     }
@@ -977,6 +1791,67 @@ impl FFmpegHandler {
             .unwrap_or_default()
     }
 
+    /// Settings a running group was started with
+    pub fn active_group(&self, group_id: &str) -> Option<OutputGroup> {
+        self.active_groups
+            .lock()
+            .ok()
+            .and_then(|groups| groups.get(group_id).map(|config| config.group.clone()))
+    }
+
+    /// Whether the shared ingest listener is up
+    pub fn is_relay_running(&self) -> bool {
+        self.relay.lock().map(|relay| relay.is_some()).unwrap_or(false)
+    }
+
+    /// Port the ingest listener is bound to, while it is up (with or without groups running)
+    pub fn ingest_listen_port(&self) -> Option<u16> {
+        let mut relay = self.relay.lock().ok()?;
+        let relay = relay.as_mut()?;
+        if relay.has_exited() {
+            return None;
+        }
+        match &relay.listener {
+            IngestListener::Native(server) => Some(server.local_addr().port()),
+            IngestListener::Process(_) => url::Url::parse(&relay.incoming_url).ok()?.port(),
+        }
+    }
+
+    /// CPU, memory and thread usage of each running FFmpeg process (Linux only; empty elsewhere)
+    pub fn process_stats(&self) -> Vec<FFmpegProcessStats> {
+        self.process_sampler.sample(self.child_processes())
+    }
+
+    /// FFmpeg processes currently running (the built-in RTMP server has none)
+    pub fn child_processes(&self) -> Vec<FFmpegProcess> {
+        let mut children = Vec::new();
+        if let Ok(relay) = self.relay.lock() {
+            if let Some(IngestListener::Process(child)) = relay.as_ref().map(|relay| &relay.listener) {
+                children.push(FFmpegProcess { role: FFmpegProcessRole::Relay, id: String::new(), pid: child.id() });
+            }
+        }
+        if let Ok(slate) = self.slate_process.lock() {
+            if let Some(child) = slate.as_ref() {
+                children.push(FFmpegProcess { role: FFmpegProcessRole::Slate, id: String::new(), pid: child.id() });
+            }
+        }
+        if let Ok(processes) = self.processes.lock() {
+            children.extend(processes.values().map(|info| FFmpegProcess {
+                role: FFmpegProcessRole::Group,
+                id: info.group_id.clone(),
+                pid: info.child.id(),
+            }));
+        }
+        if let Ok(targets) = self.target_processes.lock() {
+            children.extend(targets.iter().map(|(target_id, process)| FFmpegProcess {
+                role: FFmpegProcessRole::Target,
+                id: target_id.clone(),
+                pid: process.child.id(),
+            }));
+        }
+        children
+    }
+
     /// Enable a specific stream target (removes from disabled set)
     pub fn enable_target(&self, target_id: &str) {
         let mut disabled = self.disabled_targets.lock().unwrap_or_else(|e| {
@@ -1004,125 +1879,321 @@ impl FFmpegHandler {
         disabled.contains(target_id)
     }
 
-    /// Ensure relay process is running for shared ingest
-    fn ensure_relay_running(
-        &self,
-        incoming_url: &str,
-        requested_groups: &HashSet<String>,
-    ) -> Result<(), String> {
+    /// Ensure the shared ingest is listening
+    fn ensure_relay_running(&self, incoming_url: &str, event_sink: &Arc<dyn EventSink>) -> Result<(), String> {
         let mut relay_guard = self.relay.lock()
             .map_err(|e| format!("Lock poisoned: {e}"))?;
 
-        if let Some(relay) = relay_guard.as_mut() {
-            if let Ok(Some(_)) = relay.child.try_wait() {
-                *relay_guard = None;
-            }
+        if relay_guard.as_mut().is_some_and(RelayProcess::has_exited) {
+            *relay_guard = None;
         }
 
         if let Some(relay) = relay_guard.as_ref() {
             if relay.incoming_url != incoming_url {
                 return Err("Incoming URL differs from active relay input".to_string());
             }
+            return Ok(());
+        }
+
+        *relay_guard = Some(Self::spawn_relay(
+            &self.ffmpeg_path,
+            incoming_url,
+            &self.switcher,
+            &self.slate,
+            &self.ingest_access,
+            &self.ingest_status,
+            &self.ingest_analyzer,
+            event_sink,
+        )?);
+        Ok(())
+    }
+
+    /// Start listening for the publisher and feed it into the ingest switcher
+    ///
+    /// Plain RTMP is served in-process and keeps listening across publisher
+    /// reconnects. Other inputs use an FFmpeg relay, which exits when the publisher
+    /// disconnects. Without a backup slate the groups' inputs are closed when the
+    /// publisher goes away so they end as before; with one, the slate watch takes
+    /// over and restarts an FFmpeg relay for the publisher to come back to.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_relay(
+        ffmpeg_path: &str,
+        incoming_url: &str,
+        switcher: &Arc<IngestSwitcher>,
+        slate: &Arc<Mutex<SlateSettings>>,
+        ingest_access: &Arc<Mutex<IngestAccess>>,
+        ingest_status: &Arc<Mutex<Option<IngestStatus>>>,
+        ingest_analyzer: &Arc<IngestAnalyzer>,
+        event_sink: &Arc<dyn EventSink>,
+    ) -> Result<RelayProcess, String> {
+        if let Some((address, app)) = Self::native_listen_address(incoming_url) {
+            let handler = NativeIngest::new(
+                Arc::clone(switcher),
+                Arc::clone(slate),
+                Arc::clone(ingest_access),
+                Arc::clone(ingest_status),
+                Arc::clone(ingest_analyzer),
+                Arc::clone(event_sink),
+            );
+            let server = RtmpServer::bind(&address, &app, Arc::new(handler))?;
+            return Ok(RelayProcess {
+                listener: IngestListener::Native(server),
+                incoming_url: incoming_url.to_string(),
+            });
+        }
+
+        let args = Self::build_relay_args(incoming_url);
+        let sanitized: Vec<String> = args.iter().map(|arg| Self::sanitize_arg_static(arg)).collect();
+        log::info!(
+            "Starting FFmpeg relay: {} {}",
+            ffmpeg_path,
+            sanitized.join(" ")
+        );
+        let mut cmd = Command::new(ffmpeg_path);
+        cmd.args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        let mut child = cmd.spawn()
+            .map_err(|e| format!("Failed to start FFmpeg relay: {e}"))?;
 
-            // Only skip restart if relay has EXACTLY the requested groups
-            // (not just a superset, to handle group removal)
-            if relay.output_groups == *requested_groups {
-                return Ok(());
+        let stdout = child.stdout.take()
+            .ok_or_else(|| "Failed to capture FFmpeg relay output".to_string())?;
+        let pump_switcher = Arc::clone(switcher);
+        let pump_slate = Arc::clone(slate);
+        let pump_analyzer = Arc::clone(ingest_analyzer);
+        thread::spawn(move || {
+            pump_switcher.begin_primary();
+            pump_analyzer.reset();
+            read_flv_tags(stdout, "relay", |tag| {
+                pump_analyzer.observe(&tag);
+                pump_switcher.feed_primary(tag);
+            });
+            pump_analyzer.reset();
+            let slate_enabled = pump_slate.lock().map(|s| s.enabled).unwrap_or(false);
+            if !slate_enabled {
+                pump_switcher.close_all();
             }
+        });
+
+        if let Some(stderr) = child.stderr.take() {
+            thread::spawn(move || {
+                let reader = BufReader::new(stderr);
+                for line in reader.lines().map_while(Result::ok) {
+                    let sanitized = Self::sanitize_arg_static(&line);
+                    if line.contains("[error]")
+                        || line.contains("[warning]")
+                        || line.contains("Error")
+                        || line.contains("error")
+                        || line.contains("Failed")
+                        || line.contains("failed")
+                        || line.contains("Connection")
+                        || line.contains("connection")
+                        || line.contains("listen")
+                    {
+                        log::warn!("[FFmpeg:relay] {sanitized}");
+                    }
+                }
+            });
+        }
+
+        Ok(RelayProcess {
+            listener: IngestListener::Process(child),
+            incoming_url: incoming_url.to_string(),
+        })
+    }
+
+    /// Stop the relay process if running
+    fn stop_relay(&self) {
+        let mut relay_guard = match self.relay.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+
+        if let Some(mut relay) = relay_guard.take() {
+            relay.stop();
+        }
+    }
+
+    /// Apply the active profile's backup slate settings
+    pub fn set_slate_settings(&self, settings: &SlateSettings) {
+        if let Ok(mut slate) = self.slate.lock() {
+            *slate = settings.clone();
+        }
+    }
+
+    /// Apply the active profile's ingest key and allowlist (checked on each new publisher)
+    pub fn set_ingest_access(&self, input: &RtmpInput) -> Result<(), String> {
+        let access = IngestAccess::from_input(input)?;
+        if let Ok(mut guard) = self.ingest_access.lock() {
+            *guard = access;
         }
+        Ok(())
+    }
+
+    /// Publisher currently live on the built-in RTMP server
+    pub fn ingest_status(&self) -> Option<IngestStatus> {
+        self.ingest_status.lock().ok().and_then(|status| status.clone())
+    }
+
+    /// What the ingest is carrying, with warnings for running passthrough groups
+    pub fn ingest_stats(&self) -> Option<IngestStats> {
+        Self::measure_ingest(&self.ingest_analyzer, &self.active_groups)
+    }
+
+    fn measure_ingest(
+        analyzer: &IngestAnalyzer,
+        active_groups: &Mutex<HashMap<String, ActiveGroupConfig>>,
+    ) -> Option<IngestStats> {
+        let mut stats = analyzer.snapshot()?;
+        let groups: Vec<OutputGroup> = active_groups
+            .lock()
+            .map(|active| active.values().map(|cfg| cfg.group.clone()).collect())
+            .unwrap_or_default();
+        stats.warnings = passthrough_warnings(&stats, &groups);
+        Some(stats)
+    }
 
-        if requested_groups.is_empty() {
-            return Err("No output groups provided for relay fan-out".to_string());
+    /// Platform services used for target URLs, redaction and limits
+    pub fn platform_registry(&self) -> &PlatformRegistry {
+        &self.platform_registry
+    }
+
+    /// Targets whose encode exceeds the limits of their platform
+    pub fn platform_warnings(&self, groups: &[OutputGroup]) -> Vec<PlatformWarning> {
+        groups
+            .iter()
+            .flat_map(|group| self.platform_registry.check_limits(group))
+            .collect()
+    }
+
+    /// Log and emit `platform_warnings` before groups go live (warnings do not block the start)
+    fn report_platform_warnings(&self, groups: &[OutputGroup], event_sink: &Arc<dyn EventSink>) {
+        let warnings = self.platform_warnings(groups);
+        if warnings.is_empty() {
+            return;
+        }
+        for warning in &warnings {
+            log::warn!("Target {} in group {}: {}", warning.target_id, warning.group_id, warning.message);
         }
+        emit_event(event_sink.as_ref(), "platform_warnings", &warnings);
+    }
 
-        // Use the exact requested groups list (don't extend existing)
-        let relay_groups = requested_groups.clone();
+    /// Whether the backup slate is currently on air
+    pub fn is_slate_on_air(&self) -> bool {
+        self.switcher.source() == IngestSource::Slate
+    }
 
-        if let Some(mut relay) = relay_guard.take() {
-            let _ = relay.child.kill();
-            let _ = relay.child.wait();
-        }
+    /// Start the slate watch thread unless it is already running
+    fn ensure_slate_watch(&self, event_sink: &Arc<dyn EventSink>) {
+        if self.slate_watch_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let watch = SlateWatch {
+            ffmpeg_path: self.ffmpeg_path.clone(),
+            relay: Arc::clone(&self.relay),
+            ingest_status: Arc::clone(&self.ingest_status),
+            ingest_access: Arc::clone(&self.ingest_access),
+            ingest_analyzer: Arc::clone(&self.ingest_analyzer),
+            processes: Arc::clone(&self.processes),
+            active_groups: Arc::clone(&self.active_groups),
+            switcher: Arc::clone(&self.switcher),
+            slate: Arc::clone(&self.slate),
+            slate_process: Arc::clone(&self.slate_process),
+            running: Arc::clone(&self.slate_watch_running),
+            event_sink: Arc::clone(event_sink),
+        };
+        thread::spawn(move || watch.run());
+    }
 
-        let args = self.build_relay_args(incoming_url, &relay_groups)?;
-        let sanitized: Vec<String> = args.iter().map(|arg| Self::sanitize_arg_static(arg)).collect();
-        log::info!(
-            "Starting FFmpeg relay: {} {}",
-            self.ffmpeg_path,
-            sanitized.join(" ")
-        );
-        let mut cmd = Command::new(&self.ffmpeg_path);
+    /// Spawn the slate process, feeding its output to the switcher
+    fn spawn_slate(
+        ffmpeg_path: &str,
+        settings: &SlateSettings,
+        switcher: &Arc<IngestSwitcher>,
+    ) -> Result<Child, String> {
+        check_slate_source(settings)?;
+        let drawtext = settings.source != SlateSource::Card
+            || settings.message.trim().is_empty()
+            || Self::has_filter(ffmpeg_path, "drawtext");
+        if !drawtext {
+            log::warn!("FFmpeg has no drawtext filter (built without libfreetype); the backup slate card will show no message");
+        }
+        let args = build_slate_args(settings, drawtext);
+        log::info!("Starting FFmpeg backup slate: {} {}", ffmpeg_path, args.join(" "));
+
+        let mut cmd = Command::new(ffmpeg_path);
         cmd.args(&args)
             .stdin(Stdio::null())
-            .stdout(Stdio::null())
+            .stdout(Stdio::piped())
             .stderr(Stdio::piped());
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
         let mut child = cmd.spawn()
-            .map_err(|e| format!("Failed to start FFmpeg relay: {e}"))?;
+            .map_err(|e| format!("Failed to start FFmpeg backup slate: {e}"))?;
+
+        let stdout = child.stdout.take()
+            .ok_or_else(|| "Failed to capture FFmpeg slate output".to_string())?;
+        let pump_switcher = Arc::clone(switcher);
+        thread::spawn(move || {
+            pump_switcher.begin_slate();
+            read_flv_tags(stdout, "slate", |tag| pump_switcher.feed_slate(tag));
+        });
 
         if let Some(stderr) = child.stderr.take() {
             thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines().map_while(Result::ok) {
-                    let sanitized = Self::sanitize_arg_static(&line);
-                    if line.contains("[error]")
-                        || line.contains("[warning]")
-                        || line.contains("Error")
-                        || line.contains("error")
-                        || line.contains("Failed")
-                        || line.contains("failed")
-                        || line.contains("Connection")
-                        || line.contains("connection")
-                        || line.contains("listen")
-                    {
-                        log::warn!("[FFmpeg:relay] {sanitized}");
+                    if line.contains("Error") || line.contains("error") {
+                        log::warn!("[FFmpeg:slate] {line}");
                     }
                 }
             });
         }
 
-        *relay_guard = Some(RelayProcess {
-            child,
-            incoming_url: incoming_url.to_string(),
-            output_groups: relay_groups,
-        });
-
-        Ok(())
+        Ok(child)
     }
 
-    /// Stop the relay process if running
-    fn stop_relay(&self) {
-        let mut relay_guard = match self.relay.lock() {
-            Ok(guard) => guard,
-            Err(_) => return,
-        };
-
-        if let Some(mut relay) = relay_guard.take() {
-            let _ = relay.child.kill();
-            let _ = relay.child.wait();
-        }
+    /// Whether this FFmpeg build has the filter `name`
+    fn has_filter(ffmpeg_path: &str, name: &str) -> bool {
+        let mut cmd = Command::new(ffmpeg_path);
+        cmd.args(["-hide_banner", "-filters"]).stdin(Stdio::null());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.output()
+            .map(|output| lists_filter(&String::from_utf8_lossy(&output.stdout), name))
+            .unwrap_or(false)
     }
 
-    fn stop_child(&self, child: &mut Child) {
-        if let Some(stdin) = child.stdin.as_mut() {
-            let _ = stdin.write_all(b"q\n");
-            let _ = stdin.flush();
+    /// Stop the slate process if running
+    fn stop_slate(slate_process: &Mutex<Option<Child>>) {
+        if let Some(mut child) = slate_process.lock().ok().and_then(|mut guard| guard.take()) {
+            let _ = child.kill();
+            let _ = child.wait();
         }
+    }
 
-        let start = Instant::now();
-        while start.elapsed() < Duration::from_secs(2) {
+    /// Wait for a process whose input subscription was dropped to exit, killing it at `deadline`
+    ///
+    /// Group and target processes get stdin from a writer thread, so ending the
+    /// subscription (not a `q` command) is what makes FFmpeg finish and exit.
+    fn stop_child(child: &mut Child, deadline: Instant) {
+        while Instant::now() < deadline {
             if let Ok(Some(_)) = child.try_wait() {
                 return;
             }
             thread::sleep(Duration::from_millis(100));
         }
 
+        log::warn!("FFmpeg process {} did not exit after its input closed, killing it", child.id());
         let _ = child.kill();
         let _ = child.wait();
     }
 
-    /// Restart a specific group (used after toggling targets)
-    /// This stops the group and restarts it with the updated target list
+    /// Restart a specific group with an updated configuration
+    /// This stops the group and restarts it, interrupting all of its targets
     pub fn restart_group(
         &self,
         group_id: &str,
@@ -1146,14 +2217,17 @@ impl FFmpegHandler {
         group_id: &str,
         event_sink: Arc<dyn EventSink>,
     ) -> Result<(u32, Option<Duration>), String> {
+        // A manual retry replaces any pending automatic attempt
+        self.cancel_reconnect(group_id);
+        let config = self.reconnection_config();
+
         // Get the active group configuration
         let active_groups = self.active_groups.lock()
             .map_err(|e| format!("Lock poisoned: {e}"))?;
 
-        let config = active_groups.get(group_id)
+        let group = active_groups.get(group_id)
+            .map(|active| active.group.clone())
             .ok_or_else(|| "Group not found in active groups".to_string())?;
-
-        let group = config.group.clone();
         drop(active_groups);
 
         // Check if the group is already running
@@ -1172,21 +2246,21 @@ impl FFmpegHandler {
         };
 
         // Check if we should retry
-        if !reconnection_state.should_retry(&self.reconnection_config) {
+        if !reconnection_state.should_retry(&config) {
             return Err(format!(
                 "Maximum retry attempts ({}) reached",
-                self.reconnection_config.max_retries
+                config.max_retries
             ));
         }
 
         // Calculate backoff delay
-        let delay = reconnection_state.next_delay(&self.reconnection_config);
+        let delay = reconnection_state.next_delay(&config);
 
         // Log retry attempt
         log::info!(
             "[FFmpeg:{group_id}] Retrying stream (attempt {}/{}) after {} seconds",
             reconnection_state.attempt + 1,
-            self.reconnection_config.max_retries,
+            config.max_retries,
             delay.as_secs()
         );
 
@@ -1197,11 +2271,13 @@ impl FFmpegHandler {
             &serde_json::json!({
                 "groupId": group_id,
                 "attempt": reconnection_state.attempt + 1,
-                "maxAttempts": self.reconnection_config.max_retries,
+                "maxAttempts": config.max_retries,
                 "delaySecs": delay.as_secs()
             }),
         );
 
+        self.target_statuses.mark_group_retrying(group_id, event_sink.as_ref());
+
         // Sleep for backoff delay
         thread::sleep(delay);
 
@@ -1219,8 +2295,8 @@ impl FFmpegHandler {
                 }
 
                 // Calculate next retry delay in case this one fails
-                let next_delay = if reconnection_state.should_retry(&self.reconnection_config) {
-                    Some(reconnection_state.next_delay(&self.reconnection_config))
+                let next_delay = if reconnection_state.should_retry(&config) {
+                    Some(reconnection_state.next_delay(&config))
                 } else {
                     None
                 };
@@ -1269,24 +2345,25 @@ impl FFmpegHandler {
     }
 
     /// Build FFmpeg arguments for the shared relay process
-    fn build_relay_args(
-        &self,
-        incoming_url: &str,
-        group_ids: &HashSet<String>,
-    ) -> Result<Vec<String>, String> {
-        if group_ids.is_empty() {
-            return Err("Relay fan-out requires at least one group".to_string());
-        }
-
-        let outputs = self.relay_tee_output_list(group_ids);
+    fn build_relay_args(incoming_url: &str) -> Vec<String> {
         let listen_url = Self::normalize_relay_input_url(incoming_url);
-        Ok(vec![
-            "-listen".to_string(),
-            "1".to_string(),
-            "-timeout".to_string(),
-            Self::RELAY_RTMP_TIMEOUT_SECS.to_string(),
-            "-tcp_nodelay".to_string(),
-            Self::RELAY_RTMP_TCP_NODELAY.to_string(),
+
+        // SRT listens via the URL's mode=listener option; the RTMP-specific
+        // listen flags would be rejected by the libsrt protocol handler
+        let mut args = if listen_url.starts_with("srt://") {
+            Vec::new()
+        } else {
+            vec![
+                "-listen".to_string(),
+                "1".to_string(),
+                "-timeout".to_string(),
+                Self::RELAY_RTMP_TIMEOUT_SECS.to_string(),
+                "-tcp_nodelay".to_string(),
+                Self::RELAY_RTMP_TCP_NODELAY.to_string(),
+            ]
+        };
+
+        args.extend([
             "-i".to_string(),
             listen_url,
             "-c:v".to_string(),
@@ -1298,129 +2375,40 @@ impl FFmpegHandler {
             "-map".to_string(),
             "0:a".to_string(),
             "-f".to_string(),
-            "tee".to_string(),
-            "-use_fifo".to_string(),
-            "1".to_string(),
-            "-fifo_options".to_string(),
-            Self::RELAY_TEE_FIFO_OPTIONS.to_string(),
-            outputs,
-        ])
-    }
-
-    /// Get or assign a port offset for a group (simplified sequential allocation)
-    fn get_port_offset(&self, group_id: &str) -> u16 {
-        let mut assignments = self.port_assignments.lock()
-            .unwrap_or_else(|e| {
-                log::warn!("Port assignments mutex poisoned, recovering: {e}");
-                e.into_inner()
-            });
+            "flv".to_string(),
+            "pipe:1".to_string(),
+        ]);
+        args
+    }
 
-        // Return existing assignment if found
-        if let Some(&offset) = assignments.get(group_id) {
-            return offset;
+    /// Listen address (`host:port`) and application for inputs the built-in RTMP server handles
+    fn native_listen_address(incoming_url: &str) -> Option<(String, String)> {
+        let scheme_end = incoming_url.find("://")?;
+        if !incoming_url[..scheme_end].eq_ignore_ascii_case("rtmp") {
+            return None;
+        }
+        let rest = &incoming_url[scheme_end + 3..];
+        let rest = rest.split('?').next().unwrap_or(rest);
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if host.is_empty() {
+            return None;
         }
 
-        // Assign next available offset, preventing AtomicU16 wraparound
-        let offset = loop {
-            let current = self.next_port_offset.load(Ordering::SeqCst);
-            if current == u16::MAX {
-                log::error!(
-                    "Port offset counter reached maximum value ({}); \
-                    refusing to allocate new port offset to group {group_id}",
-                    u16::MAX
-                );
-                break current;
-            }
-
-            let next = current + 1;
-            match self.next_port_offset.compare_exchange(
-                current,
-                next,
-                Ordering::SeqCst,
-                Ordering::SeqCst,
-            ) {
-                Ok(_) => break current,
-                Err(_) => continue,
-            }
+        // Default RTMP port unless one is given (IPv6 hosts are bracketed)
+        let has_port = match host.rfind(']') {
+            Some(bracket) => host[bracket..].contains(':'),
+            None => host.contains(':'),
         };
-        assignments.insert(group_id.to_string(), offset);
-
-        log::debug!("Assigned port offset {offset} to group {group_id} (relay: {}, meter: {})",
-            Self::RELAY_PORT_BASE + (offset * 2),
-            Self::RELAY_PORT_BASE + (offset * 2) + 1
-        );
-
-        offset
+        let address = if has_port { host.to_string() } else { format!("{host}:1935") };
+        let app = path.split('/').find(|segment| !segment.is_empty()).unwrap_or_default();
+        Some((address, app.to_string()))
     }
 
-    /// Free port assignment when a group stops
-    fn free_port_offset(&self, group_id: &str) {
-        let mut assignments = self.port_assignments.lock()
-            .unwrap_or_else(|e| {
-                log::warn!("Port assignments mutex poisoned, recovering: {e}");
-                e.into_inner()
-            });
-
-        if let Some(offset) = assignments.remove(group_id) {
-            log::debug!("Freed port offset {offset} from group {group_id}");
-
-            // Reset counter if all assignments are freed
-            if assignments.is_empty() {
-                self.next_port_offset.store(0, Ordering::SeqCst);
-                log::debug!("All ports freed, reset counter to 0");
-            }
+    fn normalize_relay_input_url(url: &str) -> String {
+        if url.starts_with("srt://") {
+            return Self::normalize_srt_listen_url(url);
         }
-    }
-
-    fn relay_port_for_group(&self, group_id: &str) -> u16 {
-        let offset = self.get_port_offset(group_id);
-        // Each group gets: relay_port = BASE + (offset * 2)
-        Self::RELAY_PORT_BASE + (offset * 2)
-    }
-
-    fn relay_output_url_for_group(&self, group_id: &str) -> String {
-        format!(
-            "tcp://{}:{}?{}",
-            Self::RELAY_HOST,
-            self.relay_port_for_group(group_id),
-            Self::RELAY_TCP_OUT_QUERY
-        )
-    }
-
-    fn relay_input_url_for_group(&self, group_id: &str) -> String {
-        format!(
-            "tcp://{}:{}?{}",
-            Self::RELAY_HOST,
-            self.relay_port_for_group(group_id),
-            Self::RELAY_TCP_IN_QUERY
-        )
-    }
-
-    fn meter_port_for_group(&self, group_id: &str) -> u16 {
-        let offset = self.get_port_offset(group_id);
-        // Each group gets: meter_port = BASE + (offset * 2) + 1
-        Self::RELAY_PORT_BASE + (offset * 2) + 1
-    }
-
-    fn meter_output_url_for_group(&self, group_id: &str) -> String {
-        format!(
-            "udp://{}:{}?{}",
-            Self::METER_HOST,
-            self.meter_port_for_group(group_id),
-            Self::METER_UDP_QUERY
-        )
-    }
 
-    fn relay_tee_output_list(&self, group_ids: &HashSet<String>) -> String {
-        let mut ids: Vec<&String> = group_ids.iter().collect();
-        ids.sort();
-        ids.into_iter()
-            .map(|id| format!("[f=mpegts]{}", self.relay_output_url_for_group(id)))
-            .collect::<Vec<String>>()
-            .join("|")
-    }
-    
-    fn normalize_relay_input_url(url: &str) -> String {
         if !(url.starts_with("rtmp://") || url.starts_with("rtmps://")) {
             return url.to_string();
         }
@@ -1458,6 +2446,11 @@ impl FFmpegHandler {
         base_url
     }
 
+    /// Force SRT ingest URLs into listener mode, keeping the other options
+    fn normalize_srt_listen_url(url: &str) -> String {
+        Self::append_query_params(url, vec!["mode=listener".to_string()])
+    }
+
     fn double_bitrate_value(bitrate: &str) -> Option<String> {
         let trimmed = bitrate.trim();
         if trimmed.is_empty() {
@@ -1478,23 +2471,23 @@ impl FFmpegHandler {
         Some(format!("{formatted}{suffix}"))
     }
 
-    fn append_cbr_args(args: &mut Vec<String>, encoder: &str, bitrate: &str) {
+    fn append_cbr_args(args: &mut Vec<String>, encoder: &str, bitrate: &str, stream: &str) {
         let bufsize = Self::double_bitrate_value(bitrate)
             .unwrap_or_else(|| bitrate.to_string());
 
-        args.push("-minrate".to_string()); args.push(bitrate.to_string());
-        args.push("-maxrate".to_string()); args.push(bitrate.to_string());
-        args.push("-bufsize".to_string()); args.push(bufsize);
+        args.push(format!("-minrate:{stream}")); args.push(bitrate.to_string());
+        args.push(format!("-maxrate:{stream}")); args.push(bitrate.to_string());
+        args.push(format!("-bufsize:{stream}")); args.push(bufsize);
 
         if encoder.contains("nvenc") || encoder.contains("qsv") || encoder.contains("amf") {
-            args.push("-rc".to_string()); args.push("cbr".to_string());
+            args.push(format!("-rc:{stream}")); args.push("cbr".to_string());
         }
 
         if encoder == "libx264" {
-            args.push("-x264-params".to_string());
+            args.push(format!("-x264-params:{stream}"));
             args.push("nal-hrd=cbr:force-cfr=1".to_string());
         } else if encoder == "libx265" {
-            args.push("-x265-params".to_string());
+            args.push(format!("-x265-params:{stream}"));
             args.push("nal-hrd=cbr".to_string());
         }
     }
@@ -1558,10 +2551,221 @@ impl FFmpegHandler {
         format!("{url}{separator}{query}")
     }
 
+    /// Build an SRT caller URL; the target's stream key is used as the passphrase
+    fn build_srt_output_url(target: &StreamTarget, passphrase: &str) -> String {
+        // https://ffmpeg.org/ffmpeg-protocols.html#srt
+        let mut params = vec!["mode=caller".to_string()];
+        let options = target.srt.clone().unwrap_or_default();
+        if let Some(latency_ms) = options.latency_ms {
+            // libsrt's URL option is expressed in microseconds
+            params.push(format!("latency={}", u64::from(latency_ms) * 1000));
+        }
+        if !passphrase.is_empty() {
+            params.push(format!("passphrase={}", urlencoding::encode(passphrase)));
+        }
+        if !options.stream_id.is_empty() {
+            params.push(format!("streamid={}", urlencoding::encode(&options.stream_id)));
+        }
+
+        Self::append_query_params(&target.url, params)
+    }
+
+    /// Build a RIST sender URL; the target's stream key is used as the shared secret
+    fn build_rist_output_url(target: &StreamTarget, secret: &str) -> String {
+        // https://ffmpeg.org/ffmpeg-protocols.html#rist
+        let mut params = Vec::new();
+        let options = target.rist.clone().unwrap_or_default();
+        if let Some(buffer_ms) = options.buffer_ms {
+            params.push(format!("buffer_size={buffer_ms}"));
+        }
+        if let Some(profile) = options.profile.as_deref() {
+            params.push(format!("rist_profile={profile}"));
+        }
+        if !secret.is_empty() {
+            params.push(format!("secret={}", urlencoding::encode(secret)));
+            params.push(format!("encryption={}", options.encryption.unwrap_or(128)));
+        }
+
+        Self::append_query_params(&target.url, params)
+    }
+
+    /// Append query parameters, dropping any already present under the same name
+    fn append_query_params(url: &str, params: Vec<String>) -> String {
+        let url = url.trim();
+        let (base, query) = url.split_once('?').unwrap_or((url, ""));
+        let base = base.trim_end_matches('/');
+
+        let overridden: Vec<&str> = params
+            .iter()
+            .filter_map(|param| param.split_once('=').map(|(key, _)| key))
+            .collect();
+        let mut merged: Vec<String> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .filter(|pair| !overridden.contains(&pair.split('=').next().unwrap_or(pair)))
+            .map(str::to_string)
+            .collect();
+        merged.extend(params);
+
+        if merged.is_empty() {
+            base.to_string()
+        } else {
+            format!("{base}?{}", merged.join("&"))
+        }
+    }
+
+    /// Append encoder arguments for one output video stream
+    ///
+    /// `stream` is the output stream specifier ("v", or "v:N" when renditions are encoded).
+    /// Identical keyframe expressions keep renditions GOP-aligned for ABR players.
+    fn append_video_encoder_args(args: &mut Vec<String>, video: &VideoSettings, stream: &str, set_size_and_rate: bool) {
+        args.push(format!("-c:{stream}")); args.push(video.codec.clone());
+        if set_size_and_rate {
+            args.push(format!("-s:{stream}")); args.push(video.resolution());
+        }
+        args.push(format!("-b:{stream}")); args.push(video.bitrate.clone());
+        // Add CBR enforcement for consistent streaming bitrate
+        Self::append_cbr_args(args, &video.codec, &video.bitrate, stream);
+        if set_size_and_rate {
+            args.push(format!("-r:{stream}")); args.push(video.fps.to_string());
+        }
+        // Add video encoder preset if specified
+        if let Some(preset) = &video.preset {
+            let encoder = video.codec.as_str();
+            if encoder.contains("amf") {
+                let mut amf_quality: Option<&str> = None;
+                let mut amf_usage: Option<&str> = None;
+                match preset.as_str() {
+                    "quality" => amf_quality = Some("quality"),
+                    "balanced" => amf_quality = Some("balanced"),
+                    "speed" => amf_quality = Some("speed"),
+                    "performance" | "fast" | "faster" | "veryfast" | "superfast" | "ultrafast" => {
+                        amf_quality = Some("speed");
+                    }
+                    "medium" => amf_quality = Some("balanced"),
+                    "slow" | "slower" | "veryslow" => amf_quality = Some("quality"),
+                    "low_latency" | "low-latency" | "lowLatency" => {
+                        amf_quality = Some("speed");
+                        amf_usage = Some("lowlatency");
+                    }
+                    _ => {}
+                }
+                if let Some(quality) = amf_quality {
+                    args.push(format!("-quality:{stream}")); args.push(quality.to_string());
+                }
+                if let Some(usage) = amf_usage {
+                    args.push(format!("-usage:{stream}")); args.push(usage.to_string());
+                }
+            } else if encoder.contains("nvenc") {
+                let ffmpeg_preset = Self::map_nvenc_preset(preset);
+                args.push(format!("-preset:{stream}")); args.push(ffmpeg_preset);
+            } else {
+                let supports_preset = encoder == "libx264"
+                    || encoder == "libx265";
+                if supports_preset {
+                    let ffmpeg_preset = match preset.as_str() {
+                        "quality" => "slow",
+                        "balanced" => "medium",
+                        "performance" => "fast",
+                        "low_latency" | "low-latency" | "lowLatency" => "ultrafast",
+                        _ => preset.as_str(),
+                    };
+                    args.push(format!("-preset:{stream}")); args.push(ffmpeg_preset.to_string());
+                }
+            }
+        }
+        // Add H.264 profile if specified
+        if let Some(profile) = &video.profile {
+            args.push(format!("-profile:{stream}")); args.push(profile.clone());
+        }
+
+        if let Some(interval_seconds) = video.keyframe_interval_seconds {
+            if interval_seconds > 0 && video.fps > 0 {
+                let gop_size = video.fps.saturating_mul(interval_seconds);
+                if gop_size > 0 {
+                    args.push(format!("-g:{stream}")); args.push(gop_size.to_string());
+
+                    if video.codec == "libx264" || video.codec == "libx265" {
+                        args.push(format!("-keyint_min:{stream}")); args.push(gop_size.to_string());
+                        args.push(format!("-sc_threshold:{stream}")); args.push("0".to_string());
+                    }
+
+                    args.push(format!("-force_key_frames:{stream}"));
+                    args.push(format!("expr:gte(t,n_forced*{interval_seconds})"));
+                }
+            }
+        }
+    }
+
+    fn append_audio_encoder_args(args: &mut Vec<String>, group: &OutputGroup) {
+        args.push("-c:a".to_string()); args.push(group.audio.codec.clone());
+        args.push("-b:a".to_string()); args.push(group.audio.bitrate.clone());
+        args.push("-ac".to_string()); args.push(group.audio.channels.to_string());
+        args.push("-ar".to_string()); args.push(group.audio.sample_rate.to_string());
+    }
+
+    /// Build the filter graph that splits one decode into every encoded rendition
+    ///
+    /// Stream 0 is the group's main video, followed by the renditions in order.
+    /// Returns the graph and the `-map` argument for each output video stream;
+    /// a passthrough main video is mapped straight from the input.
+    fn build_rendition_filter(primary: &VideoSettings, renditions: &[Rendition]) -> (String, Vec<String>) {
+        let videos: Vec<&VideoSettings> = std::iter::once(primary)
+            .chain(renditions.iter().map(|r| &r.video))
+            .collect();
+        let encoded: Vec<usize> = videos
+            .iter()
+            .enumerate()
+            .filter(|(_, video)| !video.codec.eq_ignore_ascii_case("copy"))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut chains = Vec::new();
+        let sources: Vec<String> = if encoded.len() == 1 {
+            vec!["[0:v]".to_string()]
+        } else {
+            let labels: Vec<String> = encoded.iter().map(|index| format!("[split{index}]")).collect();
+            chains.push(format!("[0:v]split={}{}", encoded.len(), labels.concat()));
+            labels
+        };
+
+        for (source, &index) in sources.iter().zip(&encoded) {
+            let video = videos[index];
+            let mut filters = Vec::new();
+            if video.width > 0 && video.height > 0 {
+                filters.push(format!("scale={}:{}", video.width, video.height));
+            }
+            if video.fps > 0 {
+                filters.push(format!("fps={}", video.fps));
+            }
+            if filters.is_empty() {
+                filters.push("null".to_string());
+            }
+            chains.push(format!("{source}{}[v{index}]", filters.join(",")));
+        }
+
+        let maps = videos
+            .iter()
+            .enumerate()
+            .map(|(index, video)| {
+                if video.codec.eq_ignore_ascii_case("copy") {
+                    "0:v".to_string()
+                } else {
+                    format!("[v{index}]")
+                }
+            })
+            .collect();
+
+        (chains.join(";"), maps)
+    }
+
     /// Build FFmpeg arguments for an output group
     ///
-    /// Groups read from the shared TCP relay so they can restart independently.
-    fn build_args(&self, group: &OutputGroup) -> Vec<String> {
+    /// Groups read the shared ingest from stdin, fed by the ingest switcher, so
+    /// they can restart independently and ride through a switch to the slate.
+    /// The encoded streams are written to stdout as MPEG-TS and fanned out to
+    /// one output process per target, so targets can come and go while live.
+    fn build_args(&self, group: &OutputGroup, renditions: &[Rendition]) -> Vec<String> {
         // Determine if we should use stream copy (passthrough mode)
         // When both video and audio codecs are set to "copy", FFmpeg acts as a pure
         // RTMP relay server, accepting the incoming stream and forwarding it to outputs
@@ -1588,9 +2792,11 @@ impl FFmpegHandler {
             args.push("-copyts".to_string());
         }
 
-        // Input source
+        // Input source: ingest (or slate) FLV tags written to stdin
+        args.push("-f".to_string());
+        args.push("flv".to_string());
         args.push("-i".to_string());
-        args.push(self.relay_input_url_for_group(&group.id));
+        args.push("pipe:0".to_string());
 
         // Audio sync when PTS generation is enabled
         // -async 1: resample audio to match timestamps, fixing drift
@@ -1599,169 +2805,384 @@ impl FFmpegHandler {
             args.push("1".to_string());
         }
 
-        if use_stream_copy {
-            args.push("-c:v".to_string()); args.push("copy".to_string());
-            args.push("-c:a".to_string()); args.push("copy".to_string());
+        // Output video streams: the input's video, or one filter graph output per rendition
+        let mut video_maps = vec!["0:v".to_string()];
+        if renditions.is_empty() {
+            if use_stream_copy {
+                args.push("-c:v".to_string()); args.push("copy".to_string());
+                args.push("-c:a".to_string()); args.push("copy".to_string());
+            } else {
+                Self::append_video_encoder_args(&mut args, &group.video, "v", true);
+                Self::append_audio_encoder_args(&mut args, group);
+            }
         } else {
-            // Video settings
-            args.push("-c:v".to_string()); args.push(group.video.codec.clone());
-            args.push("-s".to_string()); args.push(group.video.resolution());
-            args.push("-b:v".to_string()); args.push(group.video.bitrate.clone());
-            // Add CBR enforcement for consistent streaming bitrate
-            Self::append_cbr_args(&mut args, &group.video.codec, &group.video.bitrate);
-            args.push("-r".to_string()); args.push(group.video.fps.to_string());
-            // Audio settings
-            args.push("-c:a".to_string()); args.push(group.audio.codec.clone());
-            args.push("-b:a".to_string()); args.push(group.audio.bitrate.clone());
-            args.push("-ac".to_string()); args.push(group.audio.channels.to_string());
-            args.push("-ar".to_string()); args.push(group.audio.sample_rate.to_string());
-            // Add video encoder preset if specified
-            if let Some(preset) = &group.video.preset {
-                let encoder = group.video.codec.as_str();
-                if encoder.contains("amf") {
-                    let mut amf_quality: Option<&str> = None;
-                    let mut amf_usage: Option<&str> = None;
-                    match preset.as_str() {
-                        "quality" => amf_quality = Some("quality"),
-                        "balanced" => amf_quality = Some("balanced"),
-                        "speed" => amf_quality = Some("speed"),
-                        "performance" | "fast" | "faster" | "veryfast" | "superfast" | "ultrafast" => {
-                            amf_quality = Some("speed");
-                        }
-                        "medium" => amf_quality = Some("balanced"),
-                        "slow" | "slower" | "veryslow" => amf_quality = Some("quality"),
-                        "low_latency" | "low-latency" | "lowLatency" => {
-                            amf_quality = Some("speed");
-                            amf_usage = Some("lowlatency");
-                        }
-                        _ => {}
-                    }
-                    if let Some(quality) = amf_quality {
-                        args.push("-quality".to_string()); args.push(quality.to_string());
-                    }
-                    if let Some(usage) = amf_usage {
-                        args.push("-usage".to_string()); args.push(usage.to_string());
-                    }
-                } else if encoder.contains("nvenc") {
-                    let ffmpeg_preset = Self::map_nvenc_preset(preset);
-                    args.push("-preset".to_string()); args.push(ffmpeg_preset);
+            // One decode feeds every rendition: the decoded video is split and each
+            // branch scaled in the filter graph, so -s/-r are not set per stream
+            let (filter, maps) = Self::build_rendition_filter(&group.video, renditions);
+            args.push("-filter_complex".to_string());
+            args.push(filter);
+            video_maps = maps;
+
+            let videos = std::iter::once(&group.video).chain(renditions.iter().map(|r| &r.video));
+            for (index, video) in videos.enumerate() {
+                let stream = format!("v:{index}");
+                if video.codec.eq_ignore_ascii_case("copy") {
+                    args.push(format!("-c:{stream}")); args.push("copy".to_string());
                 } else {
-                    let supports_preset = encoder == "libx264"
-                        || encoder == "libx265";
-                    if supports_preset {
-                        let ffmpeg_preset = match preset.as_str() {
-                            "quality" => "slow",
-                            "balanced" => "medium",
-                            "performance" => "fast",
-                            "low_latency" | "low-latency" | "lowLatency" => "ultrafast",
-                            _ => preset.as_str(),
-                        };
-                        args.push("-preset".to_string()); args.push(ffmpeg_preset.to_string());
-                    }
+                    Self::append_video_encoder_args(&mut args, video, &stream, false);
                 }
             }
-            // Add H.264 profile if specified
-            if let Some(profile) = &group.video.profile {
-                args.push("-profile:v".to_string()); args.push(profile.clone());
+
+            if group.audio.codec.eq_ignore_ascii_case("copy") {
+                args.push("-c:a".to_string()); args.push("copy".to_string());
+            } else {
+                Self::append_audio_encoder_args(&mut args, group);
             }
+        }
 
-            if let Some(interval_seconds) = group.video.keyframe_interval_seconds {
-                if interval_seconds > 0 && group.video.fps > 0 {
-                    let gop_size = group.video.fps.saturating_mul(interval_seconds);
-                    if gop_size > 0 {
-                        args.push("-g".to_string()); args.push(gop_size.to_string());
+        // Map video (one stream per rendition) followed by the shared audio from input 0
+        for map in video_maps {
+            args.push("-map".to_string()); args.push(map);
+        }
+        args.push("-map".to_string()); args.push("0:a".to_string());
 
-                        if group.video.codec == "libx264" || group.video.codec == "libx265" {
-                            args.push("-keyint_min".to_string()); args.push(gop_size.to_string());
-                            args.push("-sc_threshold".to_string()); args.push("0".to_string());
-                        }
+        // Progress output for stats parsing
+        args.push("-progress".to_string()); args.push("pipe:2".to_string());
+        args.push("-stats".to_string());
 
-                        args.push("-force_key_frames".to_string());
-                        args.push(format!("expr:gte(t,n_forced*{interval_seconds})"));
-                    }
+        args.push("-f".to_string());
+        args.push("mpegts".to_string());
+        args.push("pipe:1".to_string());
+        args
+    }
+
+    /// Resolve a target's muxer, options and destination, preparing local output directories
+    fn prepare_target_output(&self, group: &OutputGroup, target: &StreamTarget) -> Result<TargetOutput, String> {
+        if target.protocol == TargetProtocol::Record {
+            let profile_name = self.profile_name.lock()
+                .ok()
+                .and_then(|guard| guard.clone())
+                .unwrap_or_else(|| "default".to_string());
+            let plan = RecordingPlan::prepare(&self.recordings_dir, &profile_name, group, target)?;
+            return Ok(TargetOutput {
+                target: target.clone(),
+                muxer: "segment".to_string(),
+                options: plan.muxer_options.clone(),
+                destination: plan.output_pattern.clone(),
+                recording: Some(plan),
+            });
+        }
+
+        if target.protocol == TargetProtocol::Hls {
+            let plan = HlsPlan::prepare(&self.hls_dir, target)?;
+            return Ok(TargetOutput {
+                target: target.clone(),
+                muxer: plan.muxer.to_string(),
+                options: plan.muxer_options,
+                destination: plan.output_path,
+                recording: None,
+            });
+        }
+
+        let resolved_key = Self::resolve_stream_key(&target.stream_key);
+        let destination = match target.protocol {
+            TargetProtocol::Rtmp => {
+                let normalized_url = Self::normalize_rtmp_url(&target.url);
+                let normalized_url = self.platform_registry.normalize_url(&target.service, &normalized_url);
+                let full_url = self.platform_registry.build_url_with_key(&target.service, &normalized_url, &resolved_key);
+
+                // Add RTMP protocol options for connection resilience (matches OBS configuration)
+                if full_url.starts_with("rtmp://") || full_url.starts_with("rtmps://") {
+                    Self::add_rtmp_options(&full_url)
+                } else {
+                    full_url
                 }
             }
+            TargetProtocol::Srt => Self::build_srt_output_url(target, &resolved_key),
+            TargetProtocol::Rist => Self::build_rist_output_url(target, &resolved_key),
+            TargetProtocol::Whip => target.url.trim().to_string(),
+            TargetProtocol::Record | TargetProtocol::Hls => {
+                unreachable!("local targets are handled above")
+            }
+        };
+
+        let mut options = Vec::new();
+        // The WHIP muxer performs the SDP offer/answer itself and sends this as a bearer token
+        if target.protocol == TargetProtocol::Whip && !resolved_key.is_empty() {
+            options.push(("authorization".to_string(), resolved_key));
         }
 
-        if group.container.format == "flv" {
-            let force_flv_video_tag = use_stream_copy || group.video.codec.contains("264");
-            if force_flv_video_tag {
+        Ok(TargetOutput {
+            target: target.clone(),
+            muxer: target.protocol.muxer().unwrap_or(group.container.format.as_str()).to_string(),
+            options,
+            destination,
+            recording: None,
+        })
+    }
+
+    /// Output video stream carrying a target's rendition, if the group process encodes it
+    fn target_video_index(target: &StreamTarget, encoded_renditions: &[String]) -> Option<usize> {
+        match target.rendition_id.as_deref() {
+            None => Some(0),
+            Some(id) => encoded_renditions.iter().position(|r| r == id).map(|position| position + 1),
+        }
+    }
+
+    /// Build FFmpeg arguments for a target process reading its group's output from stdin
+    fn build_target_args(group: &OutputGroup, output: &TargetOutput, video_index: usize) -> Vec<String> {
+        let use_stream_copy = group.video.codec.eq_ignore_ascii_case("copy")
+            && group.audio.codec.eq_ignore_ascii_case("copy");
+
+        let mut args = vec![
+            "-nostdin".to_string(),
+            "-f".to_string(), "mpegts".to_string(),
+            "-i".to_string(), "pipe:0".to_string(),
+            "-map".to_string(), format!("0:v:{video_index}"),
+            "-map".to_string(), "0:a".to_string(),
+            "-c".to_string(), "copy".to_string(),
+        ];
+
+        if output.muxer == "flv" {
+            let video = group.video_for_target(&output.target);
+            if use_stream_copy || video.codec.contains("264") {
                 args.push("-tag:v".to_string());
                 args.push("7".to_string());
             }
 
-            let force_flv_audio_tag = use_stream_copy || group.audio.codec.contains("aac");
-            if force_flv_audio_tag {
+            // AAC arrives ADTS-framed from the MPEG-TS pipe
+            if use_stream_copy || group.audio.codec.contains("aac") {
                 args.push("-tag:a".to_string());
                 args.push("10".to_string());
-            }
-
-            if use_stream_copy {
                 args.push("-bsf:a".to_string());
                 args.push("aac_adtstoasc".to_string());
             }
         }
 
-        // Always map video and audio from input 0
-        args.push("-map".to_string()); args.push("0:v".to_string());
-        args.push("-map".to_string()); args.push("0:a".to_string());
-
-        // Progress output for stats parsing
+        // Progress output marks the target live once packets are being written
         args.push("-progress".to_string()); args.push("pipe:2".to_string());
-        args.push("-stats".to_string());
 
-        // Add output targets (skip disabled ones)
-        let disabled = self.disabled_targets.lock().unwrap_or_else(|e| {
-            log::warn!("Disabled targets mutex poisoned (build_args), recovering: {e}");
-            e.into_inner()
-        });
-        let mut target_outputs: Vec<String> = Vec::new();
-        for target in &group.stream_targets {
-            // Skip targets that have been disabled via toggle_target
-            if disabled.contains(&target.id) {
-                continue;
-            }
+        args.push("-f".to_string());
+        args.push(output.muxer.clone());
+        for (key, value) in &output.options {
+            args.push(format!("-{key}"));
+            args.push(value.clone());
+        }
+        args.push(output.destination.clone());
+        args
+    }
+}
 
-            let normalized_url = Self::normalize_rtmp_url(&target.url);
-            let normalized_url = self.platform_registry.normalize_url(&target.service, &normalized_url);
-            let resolved_key = Self::resolve_stream_key(&target.stream_key);
-            let full_url = self.platform_registry.build_url_with_key(&target.service, &normalized_url, &resolved_key);
+impl Default for FFmpegHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-            // Add RTMP protocol options for connection resilience (matches OBS configuration)
-            let full_url_with_options = if full_url.starts_with("rtmp://") || full_url.starts_with("rtmps://") {
-                Self::add_rtmp_options(&full_url)
-            } else {
-                full_url.clone()
-            };
-            target_outputs.push(full_url_with_options);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RistOutputOptions, SrtOutputOptions};
+
+    fn rendition(id: &str, width: u32, height: u32, fps: u32) -> Rendition {
+        Rendition {
+            id: id.to_string(),
+            name: id.to_string(),
+            video: VideoSettings {
+                codec: "libx264".to_string(),
+                width,
+                height,
+                fps,
+                bitrate: "3000k".to_string(),
+                ..VideoSettings::default()
+            },
         }
+    }
 
-        if target_outputs.is_empty() {
-            return args;
-        }
+    #[test]
+    fn test_rendition_filter_splits_single_decode() {
+        let primary = VideoSettings {
+            codec: "libx264".to_string(),
+            width: 1920,
+            height: 1080,
+            fps: 60,
+            ..VideoSettings::default()
+        };
+        let (filter, maps) =
+            FFmpegHandler::build_rendition_filter(&primary, &[rendition("720p", 1280, 720, 30)]);
+        assert_eq!(
+            filter,
+            "[0:v]split=2[split0][split1];[split0]scale=1920:1080,fps=60[v0];[split1]scale=1280:720,fps=30[v1]"
+        );
+        assert_eq!(maps, vec!["[v0]", "[v1]"]);
+    }
+
+    #[test]
+    fn test_rendition_filter_keeps_passthrough_main_video() {
+        let (filter, maps) = FFmpegHandler::build_rendition_filter(
+            &VideoSettings::default(),
+            &[rendition("480p", 854, 480, 30)],
+        );
+        assert_eq!(filter, "[0:v]scale=854:480,fps=30[v1]");
+        assert_eq!(maps, vec!["0:v", "[v1]"]);
+    }
+
+    #[test]
+    fn test_bitrate_meter_counts_video_pids() {
+        let meter = BitrateMeter::new(2);
+        let mut datagram = Vec::new();
+        for pid in [0x100u16, 0x101, 0x101, 0x102, 0x1000] {
+            let mut packet = vec![0u8; TS_PACKET_SIZE];
+            packet[0] = 0x47;
+            packet[1] = (pid >> 8) as u8;
+            packet[2] = pid as u8;
+            datagram.extend(packet);
+        }
+        meter.record(&datagram);
+
+        assert_eq!(meter.total.load(Ordering::Relaxed), 5 * 188);
+        assert_eq!(meter.video_streams[0].load(Ordering::Relaxed), 188);
+        assert_eq!(meter.video_streams[1].load(Ordering::Relaxed), 2 * 188);
+    }
+
+    fn target(id: &str, rendition_id: Option<&str>) -> StreamTarget {
+        let mut target: StreamTarget = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "url": "rtmp://live.example.com/app",
+            "streamKey": "secret-key",
+        }))
+        .unwrap();
+        target.rendition_id = rendition_id.map(String::from);
+        target
+    }
+
+    #[test]
+    fn test_target_video_index_follows_encoded_renditions() {
+        let encoded = vec!["720p".to_string(), "480p".to_string()];
+        assert_eq!(FFmpegHandler::target_video_index(&target("a", None), &encoded), Some(0));
+        assert_eq!(FFmpegHandler::target_video_index(&target("b", Some("480p")), &encoded), Some(2));
+        // A rendition the running process does not encode needs a group restart
+        assert_eq!(FFmpegHandler::target_video_index(&target("c", Some("1080p")), &encoded), None);
+    }
 
-        let meter_output = self.meter_output_url_for_group(&group.id);
+    #[test]
+    fn test_target_args_copy_selected_rendition_into_flv() {
+        let group = OutputGroup::default();
+        let output = TargetOutput {
+            target: target("a", None),
+            muxer: "flv".to_string(),
+            options: Vec::new(),
+            destination: "rtmp://live.example.com/app/secret-key".to_string(),
+            recording: None,
+        };
+        let args = FFmpegHandler::build_target_args(&group, &output, 2).join(" ");
+        assert!(args.starts_with("-nostdin -f mpegts -i pipe:0 -map 0:v:2 -map 0:a -c copy"));
+        assert!(args.contains("-bsf:a aac_adtstoasc"));
+        assert!(args.ends_with("-f flv rtmp://live.example.com/app/secret-key"));
+    }
 
-        // Always use onfail=ignore for RTMP outputs to prevent one failed connection
-        // from killing the stats meter and potentially other outputs
-        let mut tee_outputs: Vec<String> = Vec::new();
-        tee_outputs.extend(
-            target_outputs
-                .iter()
-                .map(|output| format!("[f={}:onfail=ignore]{output}", group.container.format))
+    #[test]
+    fn test_srt_url_converts_latency_and_encodes_passphrase() {
+        let mut srt = target("a", None);
+        srt.url = "srt://ingest.example.com:9000/?latency=50&pbkeylen=16".to_string();
+        srt.srt = Some(SrtOutputOptions { latency_ms: Some(200), stream_id: "live/a b".to_string() });
+        assert_eq!(
+            FFmpegHandler::build_srt_output_url(&srt, "p&ss=word"),
+            "srt://ingest.example.com:9000?pbkeylen=16&mode=caller&latency=200000&passphrase=p%26ss%3Dword&streamid=live%2Fa%20b"
         );
-        tee_outputs.push(format!("[f=mpegts:onfail=ignore]{meter_output}"));
 
-        args.push("-f".to_string());
-        args.push("tee".to_string());
-        args.push(tee_outputs.join("|"));
+        srt.srt = None;
+        assert_eq!(
+            FFmpegHandler::build_srt_output_url(&srt, ""),
+            "srt://ingest.example.com:9000?latency=50&pbkeylen=16&mode=caller"
+        );
+    }
 
-        args
+    #[test]
+    fn test_rist_url_defaults_encryption_only_with_secret() {
+        let mut rist = target("a", None);
+        rist.url = "rist://ingest.example.com:8000".to_string();
+        rist.rist = Some(RistOutputOptions { buffer_ms: Some(1000), encryption: None, profile: Some("main".to_string()) });
+        assert_eq!(
+            FFmpegHandler::build_rist_output_url(&rist, "s/cret"),
+            "rist://ingest.example.com:8000?buffer_size=1000&rist_profile=main&secret=s%2Fcret&encryption=128"
+        );
+        assert_eq!(
+            FFmpegHandler::build_rist_output_url(&rist, ""),
+            "rist://ingest.example.com:8000?buffer_size=1000&rist_profile=main"
+        );
+
+        rist.rist = Some(RistOutputOptions { encryption: Some(256), ..RistOutputOptions::default() });
+        assert_eq!(
+            FFmpegHandler::build_rist_output_url(&rist, "key"),
+            "rist://ingest.example.com:8000?secret=key&encryption=256"
+        );
     }
-}
 
-impl Default for FFmpegHandler {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_append_query_params_overrides_duplicate_keys() {
+        assert_eq!(
+            FFmpegHandler::append_query_params(" srt://host:9000/?mode=listener&&latency=1 ", vec!["mode=caller".to_string()]),
+            "srt://host:9000?latency=1&mode=caller"
+        );
+        assert_eq!(FFmpegHandler::append_query_params("srt://host:9000/", Vec::new()), "srt://host:9000");
+    }
+
+    #[test]
+    fn test_logged_target_args_hide_whip_token() {
+        let handler = FFmpegHandler::new();
+        let group = OutputGroup::default();
+        let output = TargetOutput {
+            target: target("a", None),
+            muxer: "whip".to_string(),
+            options: vec![("authorization".to_string(), "bearer-secret".to_string())],
+            destination: "https://whip.example.com/endpoint".to_string(),
+            recording: None,
+        };
+        let args = FFmpegHandler::build_target_args(&group, &output, 0);
+        assert!(args.iter().any(|arg| arg == "bearer-secret"));
+        let logged = handler.sanitize_ffmpeg_args(&args, &group);
+        assert!(!logged.iter().any(|arg| arg.contains("bearer-secret")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unsubscribed_target_exits_without_being_killed() {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let fanout = OutputFanout::new();
+        spawn_stdin_writer(fanout.subscribe("a"), child.stdin.take().unwrap(), "a".to_string());
+        fanout.send(Arc::from(&[0x47u8; TS_PACKET_SIZE][..]), "test");
+
+        let started = Instant::now();
+        fanout.unsubscribe("a");
+        FFmpegHandler::stop_child(&mut child, started + FFmpegHandler::TARGET_STOP_TIMEOUT);
+        // `cat` exits 0 on end of input; a kill would leave it without an exit code
+        assert_eq!(child.wait().unwrap().code(), Some(0));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_target_reconnects_are_cancelled_with_their_group() {
+        let handler = FFmpegHandler::new();
+        let pending = || tokio::spawn(std::future::pending::<()>());
+        let group = pending();
+        let target = pending();
+        let other = pending();
+        handler.register_reconnect_task("g", group.abort_handle());
+        handler.register_reconnect_task("g/t1", target.abort_handle());
+        handler.register_reconnect_task("h/t2", other.abort_handle());
+
+        handler.cancel_reconnect("g");
+        assert!(group.await.unwrap_err().is_cancelled());
+        assert!(target.await.unwrap_err().is_cancelled());
+        assert!(handler.has_pending_reconnects());
+
+        handler.cancel_target_reconnect("t2");
+        assert!(other.await.unwrap_err().is_cancelled());
+        assert!(!handler.has_pending_reconnects());
     }
 }
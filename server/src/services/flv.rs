@@ -0,0 +1,224 @@
+// FLV Service
+// Reads and writes FLV tags, the unit the ingest is switched and fanned out in
+
+use std::io::{ErrorKind, Read};
+
+/// Size of an FLV tag header
+const TAG_HEADER_SIZE: usize = 11;
+
+/// Largest tag body accepted (the header's 24-bit size field)
+const MAX_TAG_SIZE: usize = 0xFF_FFFF;
+
+/// FLV tag type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlvTagKind {
+    Audio,
+    Video,
+    Script,
+}
+
+impl FlvTagKind {
+    /// Tag type byte (also the RTMP message type of the same payload)
+    pub fn type_id(self) -> u8 {
+        match self {
+            Self::Audio => 8,
+            Self::Video => 9,
+            Self::Script => 18,
+        }
+    }
+
+    pub fn from_type_id(type_id: u8) -> Option<Self> {
+        match type_id {
+            8 => Some(Self::Audio),
+            9 => Some(Self::Video),
+            18 => Some(Self::Script),
+            _ => None,
+        }
+    }
+}
+
+/// One audio, video or script tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlvTag {
+    pub kind: FlvTagKind,
+    /// Decode timestamp in milliseconds
+    pub timestamp: u32,
+    pub data: Vec<u8>,
+}
+
+impl FlvTag {
+    pub fn new(kind: FlvTagKind, timestamp: u32, data: Vec<u8>) -> Self {
+        Self { kind, timestamp, data }
+    }
+
+    /// Whether this is a video key frame (sequence headers included)
+    pub fn is_keyframe(&self) -> bool {
+        self.kind == FlvTagKind::Video
+            && self.data.first().is_some_and(|b| (b >> 4) & 0x07 == 1)
+    }
+
+    /// Whether this is an AVC/HEVC/AAC decoder configuration record
+    ///
+    /// Both legacy codec ids and Enhanced RTMP (`IsExHeader`) video tags are recognised.
+    pub fn is_sequence_header(&self) -> bool {
+        let Some(&first) = self.data.first() else {
+            return false;
+        };
+        match self.kind {
+            FlvTagKind::Video if first & 0x80 != 0 => first & 0x0f == 0,
+            // AVC (7) and HEVC (12) carry a packet type byte
+            FlvTagKind::Video => matches!(first & 0x0f, 7 | 12) && self.data.get(1) == Some(&0),
+            // AAC (10)
+            FlvTagKind::Audio => first >> 4 == 10 && self.data.get(1) == Some(&0),
+            FlvTagKind::Script => false,
+        }
+    }
+
+    /// Whether this is an `onMetaData` script tag
+    pub fn is_metadata(&self) -> bool {
+        // AMF0 string marker, 16-bit length, then the name
+        self.kind == FlvTagKind::Script && self.data.get(3..13) == Some(b"onMetaData".as_slice())
+    }
+
+    /// Append the tag and its trailing previous-tag-size field
+    pub fn write_to(&self, out: &mut Vec<u8>) {
+        let size = self.data.len().min(MAX_TAG_SIZE) as u32;
+        out.push(self.kind.type_id());
+        out.extend_from_slice(&size.to_be_bytes()[1..]);
+        out.extend_from_slice(&self.timestamp.to_be_bytes()[1..]);
+        out.push((self.timestamp >> 24) as u8);
+        out.extend_from_slice(&[0, 0, 0]);
+        out.extend_from_slice(&self.data[..size as usize]);
+        out.extend_from_slice(&(size + TAG_HEADER_SIZE as u32).to_be_bytes());
+    }
+}
+
+/// FLV file header announcing audio and video, followed by PreviousTagSize0
+pub fn flv_header() -> Vec<u8> {
+    vec![b'F', b'L', b'V', 1, 0x05, 0, 0, 0, 9, 0, 0, 0, 0]
+}
+
+/// Reads tags from an FLV byte stream such as FFmpeg's `-f flv pipe:1`
+pub struct FlvReader<R> {
+    source: R,
+    header_read: bool,
+}
+
+impl<R: Read> FlvReader<R> {
+    pub fn new(source: R) -> Self {
+        Self { source, header_read: false }
+    }
+
+    /// Next tag, or None at the end of the stream
+    pub fn next_tag(&mut self) -> Result<Option<FlvTag>, String> {
+        if !self.header_read {
+            let mut header = [0u8; 9];
+            if !self.fill(&mut header)? {
+                return Ok(None);
+            }
+            if &header[..3] != b"FLV" {
+                return Err("Not an FLV stream".to_string());
+            }
+            let header_size = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) as usize;
+            // Rest of the header, then PreviousTagSize0
+            let mut rest = vec![0u8; header_size.saturating_sub(header.len()) + 4];
+            if !self.fill(&mut rest)? {
+                return Ok(None);
+            }
+            self.header_read = true;
+        }
+
+        loop {
+            let mut header = [0u8; TAG_HEADER_SIZE];
+            if !self.fill(&mut header)? {
+                return Ok(None);
+            }
+            let size = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+            let timestamp = u32::from_be_bytes([header[7], header[4], header[5], header[6]]);
+
+            // Tag body followed by its previous-tag-size field
+            let mut data = vec![0u8; size + 4];
+            if !self.fill(&mut data)? {
+                return Err("FLV stream ended mid-tag".to_string());
+            }
+            data.truncate(size);
+            // Skip tag types FLV readers are expected to ignore
+            if let Some(kind) = FlvTagKind::from_type_id(header[0] & 0x1f) {
+                return Ok(Some(FlvTag::new(kind, timestamp, data)));
+            }
+        }
+    }
+
+    /// Fill `buf` completely; false on a clean end of stream before the first byte
+    fn fill(&mut self, buf: &mut [u8]) -> Result<bool, String> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.source.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err("FLV stream ended mid-tag".to_string()),
+                Ok(len) => filled += len,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(format!("FLV read failed: {err}")),
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Read FLV tags from `source` until it ends
+pub fn read_flv_tags<R, F>(source: R, label: &str, mut on_tag: F)
+where
+    R: Read,
+    F: FnMut(FlvTag),
+{
+    let mut reader = FlvReader::new(source);
+    loop {
+        match reader.next_tag() {
+            Ok(Some(tag)) => on_tag(tag),
+            Ok(None) => break,
+            Err(err) => {
+                log::warn!("[FFmpeg:{label}] {err}");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_tags_round_trip() {
+        let tags = vec![
+            FlvTag::new(FlvTagKind::Video, 0, vec![0x17, 0, 0, 0, 0, 1, 2]),
+            FlvTag::new(FlvTagKind::Audio, 23, vec![0xaf, 1, 9]),
+            FlvTag::new(FlvTagKind::Video, 0x0100_0005, vec![0x27, 1, 0, 0, 0, 4]),
+        ];
+        let mut stream = flv_header();
+        for tag in &tags {
+            tag.write_to(&mut stream);
+        }
+
+        let mut read = Vec::new();
+        read_flv_tags(Cursor::new(stream), "test", |tag| read.push(tag));
+        assert_eq!(read, tags);
+    }
+
+    #[test]
+    fn test_classifies_tags() {
+        let avc_config = FlvTag::new(FlvTagKind::Video, 0, vec![0x17, 0, 0, 0, 0]);
+        assert!(avc_config.is_keyframe() && avc_config.is_sequence_header());
+        let inter = FlvTag::new(FlvTagKind::Video, 0, vec![0x27, 1, 0, 0, 0]);
+        assert!(!inter.is_keyframe() && !inter.is_sequence_header());
+        // Enhanced RTMP: key frame, SequenceStart, 'hvc1'
+        let hevc_config = FlvTag::new(FlvTagKind::Video, 0, vec![0x90, b'h', b'v', b'c', b'1']);
+        assert!(hevc_config.is_keyframe() && hevc_config.is_sequence_header());
+        assert!(FlvTag::new(FlvTagKind::Audio, 0, vec![0xaf, 0, 0x12, 0x10]).is_sequence_header());
+
+        let mut metadata = vec![2, 0, 10];
+        metadata.extend_from_slice(b"onMetaData");
+        assert!(FlvTag::new(FlvTagKind::Script, 0, metadata).is_metadata());
+    }
+}
@@ -0,0 +1,367 @@
+// Health Monitor Service
+// Rates output groups as good, degraded or critical from their recent stats
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::models::{
+    HealthLevel, HealthMetric, HealthReason, HealthSettings, OutputGroup, StreamHealth, StreamStats,
+};
+use crate::services::recording::parse_bitrate_kbps;
+
+/// Stats kept for the health window
+struct WindowSample {
+    at: i64,
+    frame: u64,
+    dropped_frames: u64,
+    speed: f64,
+    bitrate: f64,
+}
+
+#[derive(Default)]
+struct GroupHealth {
+    window: VecDeque<WindowSample>,
+    level: HealthLevel,
+    reasons: Vec<HealthReason>,
+    changed_at: i64,
+    /// When the group first rated better than `level` without getting worse since
+    better_since: Option<i64>,
+    /// When an alert was last sent for the group
+    alerted_at: Option<i64>,
+}
+
+/// Rates running groups against the active profile's thresholds
+///
+/// A group gets worse as soon as its window crosses a threshold, but only
+/// recovers once it has rated better for a whole window, so a borderline
+/// group does not flap between levels.
+#[derive(Default)]
+pub struct HealthMonitor {
+    settings: Mutex<HealthSettings>,
+    groups: Mutex<HashMap<String, GroupHealth>>,
+    /// Whether OBS was switched to the critical scene and not switched back yet
+    critical_scene_shown: AtomicBool,
+}
+
+impl HealthMonitor {
+    /// Minimum time between alerts for one group, so a group hovering at a threshold does not flood them
+    const ALERT_INTERVAL_MS: i64 = 2 * 60 * 1000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_settings(&self, settings: &HealthSettings) {
+        if let Ok(mut guard) = self.settings.lock() {
+            *guard = settings.clone();
+        }
+        if !settings.enabled {
+            if let Ok(mut groups) = self.groups.lock() {
+                groups.clear();
+            }
+        }
+    }
+
+    pub fn settings(&self) -> HealthSettings {
+        self.settings.lock().map(|settings| settings.clone()).unwrap_or_default()
+    }
+
+    /// Add a stats update; returns the new rating when the group's level changes
+    ///
+    /// `expected_kbps` is the group's configured output bitrate (`None` skips the bitrate check).
+    pub fn observe(&self, stats: &StreamStats, expected_kbps: Option<f64>) -> Option<StreamHealth> {
+        self.observe_at(stats, expected_kbps, chrono::Local::now().timestamp_millis())
+    }
+
+    fn observe_at(&self, stats: &StreamStats, expected_kbps: Option<f64>, now: i64) -> Option<StreamHealth> {
+        let settings = self.settings();
+        if !settings.enabled {
+            return None;
+        }
+        let window_ms = i64::from(settings.window_secs.max(1)) * 1000;
+
+        let mut groups = self.groups.lock().ok()?;
+        let health = groups.entry(stats.group_id.clone()).or_insert_with(|| GroupHealth {
+            changed_at: now,
+            ..GroupHealth::default()
+        });
+
+        // FFmpeg counts from zero again after a restart; the old run's numbers no longer apply
+        if health
+            .window
+            .back()
+            .is_some_and(|last| stats.frame < last.frame || stats.dropped_frames < last.dropped_frames)
+        {
+            health.window.clear();
+        }
+        health.window.push_back(WindowSample {
+            at: now,
+            frame: stats.frame,
+            dropped_frames: stats.dropped_frames,
+            speed: stats.speed,
+            bitrate: stats.bitrate,
+        });
+        while health.window.front().is_some_and(|first| now - first.at > window_ms) {
+            health.window.pop_front();
+        }
+
+        // Wait for half a window so start-up stats don't count
+        let span = now - health.window.front().map_or(now, |first| first.at);
+        if health.window.len() < 3 || span < window_ms / 2 {
+            return None;
+        }
+
+        let reasons = evaluate(&settings, &health.window, expected_kbps);
+        let level = reasons.iter().map(|reason| reason.level).max().unwrap_or_default();
+        let previous = health.level;
+        if level > previous {
+            health.better_since = None;
+        } else if level < previous {
+            let since = *health.better_since.get_or_insert(now);
+            if now - since < window_ms {
+                return None;
+            }
+            health.better_since = None;
+        } else {
+            health.better_since = None;
+            health.reasons = reasons;
+            return None;
+        }
+
+        health.level = level;
+        health.reasons = reasons;
+        health.changed_at = now;
+        Some(StreamHealth {
+            group_id: stats.group_id.clone(),
+            level,
+            previous,
+            reasons: health.reasons.clone(),
+            at: now,
+        })
+    }
+
+    /// Stop rating a group (it stopped or ended)
+    pub fn forget(&self, group_id: &str) {
+        if let Ok(mut groups) = self.groups.lock() {
+            groups.remove(group_id);
+        }
+    }
+
+    /// Current rating of every group being watched
+    pub fn current(&self) -> Vec<StreamHealth> {
+        let Ok(groups) = self.groups.lock() else {
+            return Vec::new();
+        };
+        let mut current: Vec<StreamHealth> = groups
+            .iter()
+            .map(|(group_id, health)| StreamHealth {
+                group_id: group_id.clone(),
+                level: health.level,
+                previous: health.level,
+                reasons: health.reasons.clone(),
+                at: health.changed_at,
+            })
+            .collect();
+        current.sort_by(|a, b| a.group_id.cmp(&b.group_id));
+        current
+    }
+
+    /// Whether an alert may be sent for a group now; if so, the next one waits `ALERT_INTERVAL_MS`
+    pub fn take_alert(&self, group_id: &str) -> bool {
+        self.take_alert_at(group_id, chrono::Local::now().timestamp_millis())
+    }
+
+    fn take_alert_at(&self, group_id: &str, now: i64) -> bool {
+        let Ok(mut groups) = self.groups.lock() else {
+            return false;
+        };
+        let Some(health) = groups.get_mut(group_id) else {
+            return false;
+        };
+        if health.alerted_at.is_some_and(|at| now - at < Self::ALERT_INTERVAL_MS) {
+            return false;
+        }
+        health.alerted_at = Some(now);
+        true
+    }
+
+    pub fn set_critical_scene_shown(&self, shown: bool) {
+        self.critical_scene_shown.store(shown, Ordering::SeqCst);
+    }
+
+    pub fn critical_scene_shown(&self) -> bool {
+        self.critical_scene_shown.load(Ordering::SeqCst)
+    }
+
+    /// Whether no watched group is degraded or critical
+    pub fn all_good(&self) -> bool {
+        self.groups
+            .lock()
+            .map(|groups| groups.values().all(|health| health.level == HealthLevel::Good))
+            .unwrap_or(true)
+    }
+}
+
+/// Output bitrate a group is configured for, or `None` when it copies the ingest video
+pub fn expected_bitrate_kbps(group: &OutputGroup) -> Option<f64> {
+    if group.video.codec.eq_ignore_ascii_case("copy") {
+        return None;
+    }
+    let audio = if group.audio.codec.eq_ignore_ascii_case("copy") {
+        0
+    } else {
+        parse_bitrate_kbps(&group.audio.bitrate)
+    };
+    let renditions: u64 = group
+        .renditions
+        .iter()
+        .map(|rendition| parse_bitrate_kbps(&rendition.video.bitrate))
+        .sum();
+    let total = parse_bitrate_kbps(&group.video.bitrate) + audio + renditions;
+    (total > 0).then_some(total as f64)
+}
+
+/// Level and threshold a value falls to, if it crosses one
+///
+/// Speed and bitrate are bad when low; dropped frames are bad when high.
+fn rate(value: f64, degraded: f64, critical: f64, high_is_bad: bool) -> Option<(HealthLevel, f64)> {
+    let crosses = |threshold: f64| if high_is_bad { value > threshold } else { value < threshold };
+    if crosses(critical) {
+        Some((HealthLevel::Critical, critical))
+    } else if crosses(degraded) {
+        Some((HealthLevel::Degraded, degraded))
+    } else {
+        None
+    }
+}
+
+/// Thresholds the window crosses, worst level per metric
+fn evaluate(settings: &HealthSettings, window: &VecDeque<WindowSample>, expected_kbps: Option<f64>) -> Vec<HealthReason> {
+    let mut reasons = Vec::new();
+    let mut push = |metric, value, rating: Option<(HealthLevel, f64)>, message: String| {
+        if let Some((level, threshold)) = rating {
+            reasons.push(HealthReason { metric, level, value, threshold, message });
+        }
+    };
+
+    // FFmpeg reports no speed until it has timing; those updates are skipped
+    let speeds: Vec<f64> = window.iter().map(|sample| sample.speed).filter(|speed| *speed > 0.0).collect();
+    if !speeds.is_empty() {
+        let speed = speeds.iter().sum::<f64>() / speeds.len() as f64;
+        let rating = rate(speed, settings.speed_degraded, settings.speed_critical, false);
+        push(HealthMetric::Speed, speed, rating, format!("Encoding at {speed:.2}x real-time"));
+    }
+
+    if let (Some(first), Some(last)) = (window.front(), window.back()) {
+        let dropped = last.dropped_frames - first.dropped_frames;
+        let total = (last.frame - first.frame) + dropped;
+        if total > 0 {
+            let percent = dropped as f64 * 100.0 / total as f64;
+            let rating = rate(percent, settings.dropped_degraded_percent, settings.dropped_critical_percent, true);
+            push(HealthMetric::DroppedFrames, percent, rating, format!("{percent:.1}% of frames dropped"));
+        }
+    }
+
+    if let Some(expected) = expected_kbps.filter(|kbps| *kbps > 0.0) {
+        let bitrate = window.iter().map(|sample| sample.bitrate).sum::<f64>() / window.len() as f64;
+        let percent = bitrate * 100.0 / expected;
+        let rating = rate(percent, settings.bitrate_degraded_percent, settings.bitrate_critical_percent, false);
+        push(
+            HealthMetric::Bitrate,
+            percent,
+            rating,
+            format!("Bitrate {bitrate:.0} kbps is {percent:.0}% of the configured {expected:.0} kbps"),
+        );
+    }
+
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(frame: u64, dropped_frames: u64, speed: f64, bitrate: f64) -> StreamStats {
+        StreamStats {
+            frame,
+            fps: 30.0,
+            bitrate,
+            speed,
+            dropped_frames,
+            ..StreamStats::new("main".to_string())
+        }
+    }
+
+    /// Feed one update per second, returning every level change
+    fn feed(monitor: &HealthMonitor, start: i64, updates: &[StreamStats]) -> Vec<StreamHealth> {
+        updates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, stats)| monitor.observe_at(stats, Some(6000.0), start + i as i64 * 1000))
+            .collect()
+    }
+
+    #[test]
+    fn test_rates_slow_encoding_and_recovers_after_a_window() {
+        let monitor = HealthMonitor::new();
+        let good: Vec<StreamStats> = (0..10).map(|i| stats(i * 30, 0, 1.0, 6000.0)).collect();
+        assert!(feed(&monitor, 0, &good).is_empty());
+
+        let slow: Vec<StreamStats> = (10..30).map(|i| stats(i * 30, 0, 0.7, 6000.0)).collect();
+        // The window average passes through degraded on its way down
+        let changes = feed(&monitor, 10_000, &slow);
+        let levels: Vec<HealthLevel> = changes.iter().map(|change| change.level).collect();
+        assert_eq!(levels, [HealthLevel::Degraded, HealthLevel::Critical]);
+        assert_eq!(changes[0].previous, HealthLevel::Good);
+        assert_eq!(changes[1].reasons[0].metric, HealthMetric::Speed);
+
+        // Good again, but only reported once it has held for the 10 s window
+        let recovered: Vec<StreamStats> = (30..60).map(|i| stats(i * 30, 0, 1.0, 6000.0)).collect();
+        let changes = feed(&monitor, 30_000, &recovered);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].level, HealthLevel::Good);
+        assert!(changes[0].at >= 30_000 + 10_000);
+        assert!(monitor.all_good());
+    }
+
+    #[test]
+    fn test_alerts_wait_between_changes() {
+        let monitor = HealthMonitor::new();
+        assert!(!monitor.take_alert_at("main", 0));
+
+        let good: Vec<StreamStats> = (0..10).map(|i| stats(i * 30, 0, 1.0, 6000.0)).collect();
+        feed(&monitor, 0, &good);
+        assert!(monitor.take_alert_at("main", 10_000));
+        assert!(!monitor.take_alert_at("main", 60_000));
+        assert!(monitor.take_alert_at("main", 10_000 + HealthMonitor::ALERT_INTERVAL_MS));
+    }
+
+    #[test]
+    fn test_rejects_critical_thresholds_looser_than_degraded() {
+        assert!(HealthSettings::default().validate().is_ok());
+        let speed = HealthSettings { speed_critical: 0.99, ..HealthSettings::default() };
+        assert!(speed.validate().is_err());
+        let dropped = HealthSettings { dropped_critical_percent: 0.5, ..HealthSettings::default() };
+        assert!(dropped.validate().is_err());
+        let bitrate = HealthSettings { bitrate_critical_percent: 80.0, ..HealthSettings::default() };
+        assert!(bitrate.validate().is_err());
+    }
+
+    #[test]
+    fn test_evaluates_dropped_frames_and_bitrate() {
+        let settings = HealthSettings::default();
+        let window: VecDeque<WindowSample> = (0..10)
+            .map(|i| WindowSample { at: i * 1000, frame: i as u64 * 30, dropped_frames: i as u64, speed: 1.0, bitrate: 2000.0 })
+            .collect();
+        let reasons = evaluate(&settings, &window, Some(6000.0));
+
+        let dropped = reasons.iter().find(|r| r.metric == HealthMetric::DroppedFrames).unwrap();
+        assert_eq!(dropped.level, HealthLevel::Degraded);
+        assert!((dropped.value - 9.0 * 100.0 / 279.0).abs() < 0.01);
+        assert_eq!(dropped.threshold, settings.dropped_degraded_percent);
+
+        let bitrate = reasons.iter().find(|r| r.metric == HealthMetric::Bitrate).unwrap();
+        assert_eq!(bitrate.level, HealthLevel::Critical);
+        assert!(reasons.iter().all(|r| r.metric != HealthMetric::Speed));
+    }
+}
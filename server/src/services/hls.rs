@@ -0,0 +1,223 @@
+// HLS Output Service
+// Local HLS/LL-HLS playlists written by an output group's target processes
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::models::{HlsOptions, StreamTarget};
+
+/// Playlist served for standard HLS output
+pub const HLS_PLAYLIST: &str = "index.m3u8";
+
+/// Master playlist written by the dash muxer in LL-HLS mode
+pub const LL_HLS_PLAYLIST: &str = "master.m3u8";
+
+/// Everything needed to publish one HLS target
+#[derive(Debug, Clone)]
+pub struct HlsPlan {
+    pub target_id: String,
+    pub directory: PathBuf,
+    /// Muxer name for the target process ("hls" or "dash" for LL-HLS)
+    pub muxer: &'static str,
+    /// Muxer options (unescaped)
+    pub muxer_options: Vec<(String, String)>,
+    /// Manifest path passed as the output filename
+    pub output_path: String,
+}
+
+impl HlsPlan {
+    /// Reset the target's directory and build the muxer options
+    ///
+    /// Leftovers from a previous run are removed so players never pick up a
+    /// stale playlist pointing at segments from an older session.
+    pub fn prepare(base_dir: &Path, target: &StreamTarget) -> Result<Self, String> {
+        if !is_safe_name(&target.id) {
+            return Err(format!("Invalid HLS target id: {}", target.id));
+        }
+
+        let options = target.hls.clone().unwrap_or_default();
+        let directory = base_dir.join(&target.id);
+        if directory.exists() {
+            fs::remove_dir_all(&directory)
+                .map_err(|e| format!("Failed to clear HLS directory: {e}"))?;
+        }
+        fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create HLS directory: {e}"))?;
+
+        let segment_seconds = options.segment_seconds.max(1);
+        let list_size = options.playlist_size.max(2);
+
+        let (muxer, muxer_options, manifest) = if options.low_latency {
+            // The dash muxer's LHLS mode writes fMP4 HLS playlists alongside the
+            // MPD and announces in-progress segments with EXT-X-PREFETCH
+            let options = vec![
+                ("seg_duration".to_string(), segment_seconds.to_string()),
+                ("frag_duration".to_string(), "0.2".to_string()),
+                ("window_size".to_string(), list_size.to_string()),
+                ("extra_window_size".to_string(), "2".to_string()),
+                ("streaming".to_string(), "1".to_string()),
+                ("ldash".to_string(), "1".to_string()),
+                ("lhls".to_string(), "1".to_string()),
+                ("hls_playlist".to_string(), "1".to_string()),
+                ("use_template".to_string(), "1".to_string()),
+                ("use_timeline".to_string(), "0".to_string()),
+                ("remove_at_exit".to_string(), "1".to_string()),
+            ];
+            ("dash", options, "manifest.mpd")
+        } else {
+            let segment_pattern = directory.join("segment_%05d.ts");
+            let options = vec![
+                ("hls_time".to_string(), segment_seconds.to_string()),
+                ("hls_list_size".to_string(), list_size.to_string()),
+                (
+                    "hls_flags".to_string(),
+                    "delete_segments+independent_segments+omit_endlist".to_string(),
+                ),
+                // Keep a couple of segments past the window for slow clients
+                ("hls_delete_threshold".to_string(), "2".to_string()),
+                (
+                    "hls_segment_filename".to_string(),
+                    segment_pattern.to_string_lossy().to_string(),
+                ),
+            ];
+            ("hls", options, HLS_PLAYLIST)
+        };
+
+        Ok(Self {
+            target_id: target.id.clone(),
+            output_path: directory.join(manifest).to_string_lossy().to_string(),
+            directory,
+            muxer,
+            muxer_options,
+        })
+    }
+}
+
+/// Playlist a player should load for the target
+pub fn hls_playlist_name(options: &HlsOptions) -> &'static str {
+    if options.low_latency {
+        LL_HLS_PLAYLIST
+    } else {
+        HLS_PLAYLIST
+    }
+}
+
+/// Whether a path component is safe to serve (no separators or traversal)
+pub fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Content type for files produced by the hls and dash muxers
+pub fn hls_content_type(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name).extension()?.to_str()?;
+    match extension {
+        "m3u8" => Some("application/vnd.apple.mpegurl"),
+        "ts" => Some("video/mp2t"),
+        "m4s" => Some("video/iso.segment"),
+        "mp4" => Some("video/mp4"),
+        "mpd" => Some("application/dash+xml"),
+        _ => None,
+    }
+}
+
+/// Resolve a requested HLS file inside the base directory
+pub fn resolve_hls_file(base_dir: &Path, target_id: &str, file_name: &str) -> Option<PathBuf> {
+    if !is_safe_name(target_id) || !is_safe_name(file_name) {
+        return None;
+    }
+    hls_content_type(file_name)?;
+
+    let path = base_dir.join(target_id).join(file_name);
+    path.is_file().then_some(path)
+}
+
+/// Delete HLS files that have not been written to within `max_age`
+///
+/// Live playlists are rewritten every segment, so only segments that fell out
+/// of the window and output left behind by stopped or crashed groups age out.
+/// Empty target directories are removed as well. Returns the number of files deleted.
+pub fn prune_hls_dir(base_dir: &Path, max_age: Duration) -> usize {
+    let Ok(targets) = fs::read_dir(base_dir) else {
+        return 0;
+    };
+
+    let now = SystemTime::now();
+    let is_stale = |entry: &fs::DirEntry| {
+        entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age)
+    };
+
+    let mut removed = 0;
+    for target_dir in targets.flatten() {
+        let path = target_dir.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let mut was_empty = true;
+        if let Ok(files) = fs::read_dir(&path) {
+            for file in files.flatten() {
+                was_empty = false;
+                if is_stale(&file) && fs::remove_file(file.path()).is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+
+        // A freshly prepared directory is empty until the first segment lands,
+        // so only remove directories that were already empty at the start of
+        // this pass and have been idle for the whole window.
+        if was_empty && is_stale(&target_dir) {
+            let _ = fs::remove_dir(&path);
+        }
+    }
+
+    if removed > 0 {
+        log::debug!("Pruned {removed} stale HLS files from {}", base_dir.display());
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_rejects_traversal() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("target1")).unwrap();
+        fs::write(temp.path().join("target1").join("index.m3u8"), "#EXTM3U").unwrap();
+
+        assert!(resolve_hls_file(temp.path(), "target1", "index.m3u8").is_some());
+        assert!(resolve_hls_file(temp.path(), "..", "index.m3u8").is_none());
+        assert!(resolve_hls_file(temp.path(), "target1", "../secret.m3u8").is_none());
+        assert!(resolve_hls_file(temp.path(), "target1", "notes.txt").is_none());
+    }
+
+    #[test]
+    fn test_prune_removes_stale_files_and_empty_dirs() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("target1");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("segment_00001.ts"), b"data").unwrap();
+
+        assert_eq!(prune_hls_dir(temp.path(), Duration::from_secs(60)), 0);
+        assert!(dir.exists());
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(prune_hls_dir(temp.path(), Duration::from_millis(1)), 1);
+        // The directory still held a segment at the start of this pass
+        assert!(dir.exists());
+
+        std::thread::sleep(Duration::from_millis(20));
+        prune_hls_dir(temp.path(), Duration::from_millis(1));
+        assert!(!dir.exists());
+    }
+}
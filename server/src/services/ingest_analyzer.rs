@@ -0,0 +1,659 @@
+// Ingest Analyzer Service
+// Measures codec, resolution, frame rate, GOP and bitrate of the incoming stream
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use crate::models::{IngestStats, IngestWarning, OutputGroup, TargetProtocol};
+use crate::services::{parse_ingest_metadata, FlvTag, FlvTagKind};
+
+/// Span of stream time the frame rate and bitrates are averaged over (ms)
+const WINDOW_MS: u32 = 5_000;
+
+/// Longest keyframe interval streaming platforms generally accept (2s is recommended)
+const MAX_KEYFRAME_INTERVAL_SECS: f64 = 4.0;
+
+/// H.264 profiles streaming platforms decode
+const PLATFORM_H264_PROFILES: &[&str] = &["Baseline", "Constrained Baseline", "Main", "High"];
+
+/// A media tag inside the measurement window
+struct WindowEntry {
+    timestamp: u32,
+    kind: FlvTagKind,
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct AnalyzerState {
+    stats: IngestStats,
+    /// Whether any tag arrived since the last reset
+    receiving: bool,
+    /// Resolution and frame rate were read from the SPS, not `onMetaData`
+    video_config_parsed: bool,
+    last_keyframe: Option<u32>,
+    window: VecDeque<WindowEntry>,
+}
+
+/// Watches the primary ingest's tags; reset for each new publisher
+#[derive(Default)]
+pub struct IngestAnalyzer {
+    state: Mutex<AnalyzerState>,
+}
+
+impl IngestAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the previous publisher
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = AnalyzerState::default();
+        }
+    }
+
+    /// Account for one tag from the primary ingest
+    pub fn observe(&self, tag: &FlvTag) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.receiving = true;
+
+        if tag.is_metadata() {
+            // Publisher-announced values fill in what the decoder config did not give
+            if let Some(metadata) = parse_ingest_metadata(&tag.data) {
+                if !state.video_config_parsed {
+                    state.stats.width = metadata.width.or(state.stats.width);
+                    state.stats.height = metadata.height.or(state.stats.height);
+                }
+                state.stats.fps = state.stats.fps.or(metadata.frame_rate);
+            }
+            return;
+        }
+
+        if tag.is_sequence_header() {
+            match tag.kind {
+                FlvTagKind::Video => apply_video_config(&mut state, &tag.data),
+                FlvTagKind::Audio => apply_audio_config(&mut state.stats, &tag.data),
+                FlvTagKind::Script => {}
+            }
+            return;
+        }
+
+        match tag.kind {
+            FlvTagKind::Video => {
+                if state.stats.video_codec.is_none() {
+                    state.stats.video_codec = video_codec(&tag.data);
+                }
+                if tag.is_keyframe() {
+                    if let Some(previous) = state.last_keyframe {
+                        if tag.timestamp > previous {
+                            state.stats.keyframe_interval_secs =
+                                Some(f64::from(tag.timestamp - previous) / 1000.0);
+                        }
+                    }
+                    state.last_keyframe = Some(tag.timestamp);
+                }
+            }
+            FlvTagKind::Audio => {
+                if state.stats.audio_codec.is_none() {
+                    apply_audio_header(&mut state.stats, &tag.data);
+                }
+            }
+            FlvTagKind::Script => return,
+        }
+
+        // A timestamp going back means a new stream; start measuring again
+        if state.window.back().is_some_and(|last| tag.timestamp < last.timestamp) {
+            state.window.clear();
+            state.last_keyframe = None;
+        }
+        state.window.push_back(WindowEntry {
+            timestamp: tag.timestamp,
+            kind: tag.kind,
+            bytes: tag.data.len(),
+        });
+        while state
+            .window
+            .front()
+            .is_some_and(|first| tag.timestamp - first.timestamp > WINDOW_MS)
+        {
+            state.window.pop_front();
+        }
+        measure_window(&mut state);
+    }
+
+    /// Current figures, or None before the first tag
+    pub fn snapshot(&self) -> Option<IngestStats> {
+        let state = self.state.lock().ok()?;
+        state.receiving.then(|| state.stats.clone())
+    }
+}
+
+/// Frame rate and bitrates over the current window
+fn measure_window(state: &mut AnalyzerState) {
+    let window = &state.window;
+    let (Some(first), Some(last)) = (window.front(), window.back()) else {
+        return;
+    };
+    let span_ms = last.timestamp - first.timestamp;
+    // Wait for a second of media so the first figures are not wild
+    if span_ms < 1_000 {
+        return;
+    }
+    let seconds = f64::from(span_ms) / 1000.0;
+
+    // The first tag of each kind opens the span, so only what follows it is counted
+    let (mut video_frames, mut video_bytes, mut audio_tags, mut audio_bytes) = (0usize, 0, 0usize, 0);
+    for entry in window {
+        match entry.kind {
+            FlvTagKind::Video => {
+                if video_frames > 0 {
+                    video_bytes += entry.bytes;
+                }
+                video_frames += 1;
+            }
+            FlvTagKind::Audio => {
+                if audio_tags > 0 {
+                    audio_bytes += entry.bytes;
+                }
+                audio_tags += 1;
+            }
+            FlvTagKind::Script => {}
+        }
+    }
+
+    let kbps = |bytes: usize| (bytes as f64 * 8.0 / 1000.0 / seconds * 10.0).round() / 10.0;
+    if video_frames > 1 {
+        let fps = (video_frames - 1) as f64 / seconds;
+        state.stats.fps = Some((fps * 100.0).round() / 100.0);
+        state.stats.video_bitrate_kbps = Some(kbps(video_bytes));
+    }
+    if audio_tags > 1 {
+        state.stats.audio_bitrate_kbps = Some(kbps(audio_bytes));
+    }
+}
+
+/// Codec of a video tag, from its legacy codec id or Enhanced RTMP FourCC
+fn video_codec(data: &[u8]) -> Option<String> {
+    let first = *data.first()?;
+    let name = if first & 0x80 != 0 {
+        match data.get(1..5)? {
+            b"avc1" => "h264",
+            b"hvc1" => "hevc",
+            b"av01" => "av1",
+            b"vp09" => "vp9",
+            other => return Some(String::from_utf8_lossy(other).into_owned()),
+        }
+    } else {
+        match first & 0x0f {
+            2 => "flv1",
+            4 | 5 => "vp6",
+            7 => "h264",
+            12 => "hevc",
+            id => return Some(format!("codec {id}")),
+        }
+    };
+    Some(name.to_string())
+}
+
+/// Read profile, level and resolution from a video sequence header
+fn apply_video_config(state: &mut AnalyzerState, data: &[u8]) {
+    let Some(codec) = video_codec(data) else {
+        return;
+    };
+    // Legacy tags: flags, packet type, composition time; Enhanced RTMP: flags, FourCC
+    let record = data.get(5..).unwrap_or_default();
+
+    let stats = &mut state.stats;
+    stats.video_profile = None;
+    stats.video_level = None;
+    match codec.as_str() {
+        "h264" => {
+            if let Some(sps) = parse_avc_config(record) {
+                stats.video_profile = Some(sps.profile.to_string());
+                stats.video_level = Some(format!("{:.1}", f64::from(sps.level_idc) / 10.0));
+                if let Some((width, height)) = sps.resolution {
+                    stats.width = Some(width);
+                    stats.height = Some(height);
+                    state.video_config_parsed = true;
+                }
+            }
+        }
+        "hevc" if record.len() > 12 => {
+            stats.video_profile = Some(
+                match record[1] & 0x1f {
+                    1 => "Main",
+                    2 => "Main 10",
+                    3 => "Main Still Picture",
+                    4 => "Range Extensions",
+                    _ => "Unknown",
+                }
+                .to_string(),
+            );
+            stats.video_level = Some(format!("{:.1}", f64::from(record[12]) / 30.0));
+        }
+        "av1" if record.len() > 1 => {
+            stats.video_profile = Some(
+                match record[1] >> 5 {
+                    0 => "Main",
+                    1 => "High",
+                    2 => "Professional",
+                    _ => "Unknown",
+                }
+                .to_string(),
+            );
+            // seq_level_idx: 2.0 is 0, four minor levels per major
+            let level = record[1] & 0x1f;
+            stats.video_level = Some(format!("{}.{}", 2 + level / 4, level % 4));
+        }
+        _ => {}
+    }
+    stats.video_codec = Some(codec);
+}
+
+/// Read codec, profile, sample rate and channels from an AAC sequence header
+fn apply_audio_config(stats: &mut IngestStats, data: &[u8]) {
+    apply_audio_header(stats, data);
+    // AudioSpecificConfig: object type (5 bits), frequency index (4), channel config (4)
+    let (Some(&a), Some(&b)) = (data.get(2), data.get(3)) else {
+        return;
+    };
+    let object_type = a >> 3;
+    let frequency_index = ((a & 0x07) << 1) | (b >> 7);
+    let channels = (b >> 3) & 0x0f;
+    stats.audio_profile = Some(
+        match object_type {
+            1 => "Main".to_string(),
+            2 => "LC".to_string(),
+            5 => "HE-AAC".to_string(),
+            29 => "HE-AACv2".to_string(),
+            other => format!("object type {other}"),
+        },
+    );
+    const SAMPLE_RATES: [u32; 13] = [
+        96_000, 88_200, 64_000, 48_000, 44_100, 32_000, 24_000, 22_050, 16_000, 12_000, 11_025,
+        8_000, 7_350,
+    ];
+    if let Some(rate) = SAMPLE_RATES.get(frequency_index as usize) {
+        stats.audio_sample_rate = Some(*rate);
+    }
+    if (1..=7).contains(&channels) {
+        stats.audio_channels = Some(if channels == 7 { 8 } else { u32::from(channels) });
+    }
+}
+
+/// Codec, sample rate and channels from an audio tag's first byte
+fn apply_audio_header(stats: &mut IngestStats, data: &[u8]) {
+    let Some(&first) = data.first() else {
+        return;
+    };
+    let format = first >> 4;
+    if format == 9 {
+        // Enhanced RTMP audio: FourCC follows the flags
+        stats.audio_codec = data.get(1..5).map(|fourcc| {
+            match fourcc {
+                b"mp4a" => "aac",
+                b".mp3" => "mp3",
+                b"Opus" => "opus",
+                b"fLaC" => "flac",
+                b"ac-3" => "ac3",
+                b"ec-3" => "eac3",
+                other => return String::from_utf8_lossy(other).into_owned(),
+            }
+            .to_string()
+        });
+        return;
+    }
+    stats.audio_codec = Some(
+        match format {
+            2 | 14 => "mp3".to_string(),
+            10 => "aac".to_string(),
+            11 => "speex".to_string(),
+            4..=6 => "nellymoser".to_string(),
+            other => format!("codec {other}"),
+        },
+    );
+    // AAC always signals 44.1kHz stereo here; its real values come from the config
+    if format != 10 {
+        stats.audio_sample_rate = Some([5_512, 11_025, 22_050, 44_100][usize::from((first >> 2) & 0x03)]);
+        stats.audio_channels = Some(if first & 0x01 == 1 { 2 } else { 1 });
+    }
+}
+
+/// Fields of an H.264 sequence parameter set
+struct AvcSps {
+    profile: &'static str,
+    level_idc: u8,
+    resolution: Option<(u32, u32)>,
+}
+
+/// Parse the first SPS of an AVCDecoderConfigurationRecord
+fn parse_avc_config(record: &[u8]) -> Option<AvcSps> {
+    // version, profile, compatibility, level, length size, SPS count, SPS length
+    if record.len() < 8 || record[0] != 1 {
+        return None;
+    }
+    let profile_idc = record[1];
+    let constraints = record[2];
+    let level_idc = record[3];
+    let sps_len = usize::from(u16::from_be_bytes([record[6], record[7]]));
+    let sps = record.get(8..8 + sps_len)?;
+
+    let profile = match profile_idc {
+        66 if constraints & 0x40 != 0 => "Constrained Baseline",
+        66 => "Baseline",
+        77 => "Main",
+        88 => "Extended",
+        100 => "High",
+        110 => "High 10",
+        122 => "High 4:2:2",
+        244 => "High 4:4:4 Predictive",
+        44 => "CAVLC 4:4:4 Intra",
+        _ => "Unknown",
+    };
+    // Skip the NAL header byte
+    let resolution = sps.get(1..).and_then(sps_resolution);
+    Some(AvcSps { profile, level_idc, resolution })
+}
+
+/// Picture size from an SPS payload (after the NAL header)
+fn sps_resolution(payload: &[u8]) -> Option<(u32, u32)> {
+    let mut bits = BitReader::new(payload);
+    let profile_idc = bits.read(8)?;
+    bits.read(16)?; // constraint flags, level
+    bits.read_ue()?; // seq_parameter_set_id
+
+    let mut chroma_format_idc = 1;
+    let mut separate_colour_plane = false;
+    if matches!(profile_idc, 100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135) {
+        chroma_format_idc = bits.read_ue()?;
+        if chroma_format_idc == 3 {
+            separate_colour_plane = bits.read(1)? == 1;
+        }
+        bits.read_ue()?; // bit_depth_luma_minus8
+        bits.read_ue()?; // bit_depth_chroma_minus8
+        bits.read(1)?; // qpprime_y_zero_transform_bypass_flag
+        if bits.read(1)? == 1 {
+            let lists = if chroma_format_idc == 3 { 12 } else { 8 };
+            for i in 0..lists {
+                if bits.read(1)? == 1 {
+                    skip_scaling_list(&mut bits, if i < 6 { 16 } else { 64 })?;
+                }
+            }
+        }
+    }
+
+    bits.read_ue()?; // log2_max_frame_num_minus4
+    match bits.read_ue()? {
+        0 => {
+            bits.read_ue()?; // log2_max_pic_order_cnt_lsb_minus4
+        }
+        1 => {
+            bits.read(1)?; // delta_pic_order_always_zero_flag
+            bits.read_se()?; // offset_for_non_ref_pic
+            bits.read_se()?; // offset_for_top_to_bottom_field
+            for _ in 0..bits.read_ue()? {
+                bits.read_se()?;
+            }
+        }
+        _ => {}
+    }
+    bits.read_ue()?; // max_num_ref_frames
+    bits.read(1)?; // gaps_in_frame_num_value_allowed_flag
+    // Field values come from the publisher; anything that overflows is a malformed SPS
+    let width_in_mbs = bits.read_ue()?.checked_add(1)?;
+    let height_in_map_units = bits.read_ue()?.checked_add(1)?;
+    let frame_mbs_only = bits.read(1)?;
+    if frame_mbs_only == 0 {
+        bits.read(1)?; // mb_adaptive_frame_field_flag
+    }
+    bits.read(1)?; // direct_8x8_inference_flag
+
+    let (mut crop_x, mut crop_y) = (0, 0);
+    if bits.read(1)? == 1 {
+        let (left, right, top, bottom) = (bits.read_ue()?, bits.read_ue()?, bits.read_ue()?, bits.read_ue()?);
+        let chroma_array_type = if separate_colour_plane { 0 } else { chroma_format_idc };
+        let (unit_x, unit_y) = match chroma_array_type {
+            0 | 3 => (1, 2 - frame_mbs_only),
+            2 => (2, 2 - frame_mbs_only),
+            _ => (2, 2 * (2 - frame_mbs_only)),
+        };
+        crop_x = left.checked_add(right)?.checked_mul(unit_x)?;
+        crop_y = top.checked_add(bottom)?.checked_mul(unit_y)?;
+    }
+
+    let width = width_in_mbs.checked_mul(16)?.checked_sub(crop_x)?;
+    let height = height_in_map_units
+        .checked_mul(16 * (2 - frame_mbs_only))?
+        .checked_sub(crop_y)?;
+    Some((width, height))
+}
+
+fn skip_scaling_list(bits: &mut BitReader, size: usize) -> Option<()> {
+    let (mut last, mut next) = (8i32, 8i32);
+    for _ in 0..size {
+        if next != 0 {
+            next = (i64::from(last) + i64::from(bits.read_se()?)).rem_euclid(256) as i32;
+        }
+        if next != 0 {
+            last = next;
+        }
+    }
+    Some(())
+}
+
+/// Reads bits and Exp-Golomb codes from an RBSP, skipping emulation prevention bytes
+struct BitReader {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl BitReader {
+    fn new(payload: &[u8]) -> Self {
+        let mut data = Vec::with_capacity(payload.len());
+        for &byte in payload {
+            if byte == 3 && data.ends_with(&[0, 0]) {
+                continue;
+            }
+            data.push(byte);
+        }
+        Self { data, position: 0 }
+    }
+
+    fn read(&mut self, count: u32) -> Option<u32> {
+        let mut value = 0u32;
+        for _ in 0..count {
+            let byte = self.data.get(self.position / 8)?;
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | u32::from(bit);
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    fn read_ue(&mut self) -> Option<u32> {
+        let mut zeros = 0;
+        while self.read(1)? == 0 {
+            zeros += 1;
+            if zeros > 31 {
+                return None;
+            }
+        }
+        Some((1u32 << zeros) - 1 + self.read(zeros)?)
+    }
+
+    fn read_se(&mut self) -> Option<i32> {
+        let value = self.read_ue()? as i64;
+        let signed = if value % 2 == 1 { (value + 1) / 2 } else { -(value / 2) };
+        Some(signed as i32)
+    }
+}
+
+/// Passthrough targets that cannot take the ingest as it arrives
+pub fn passthrough_warnings(stats: &IngestStats, groups: &[OutputGroup]) -> Vec<IngestWarning> {
+    let mut warnings = Vec::new();
+    for group in groups {
+        let video_copy = group.video.codec.eq_ignore_ascii_case("copy");
+        let audio_copy = group.audio.codec.eq_ignore_ascii_case("copy");
+        if !video_copy && !audio_copy {
+            continue;
+        }
+
+        for target in &group.stream_targets {
+            let mut problems = Vec::new();
+            let remote = !matches!(target.protocol, TargetProtocol::Record | TargetProtocol::Hls);
+
+            if video_copy && remote {
+                if let Some(interval) = stats.keyframe_interval_secs {
+                    if interval > MAX_KEYFRAME_INTERVAL_SECS {
+                        problems.push(format!(
+                            "Keyframe interval is {interval:.1}s; platforms expect {MAX_KEYFRAME_INTERVAL_SECS}s or less (2s recommended)"
+                        ));
+                    }
+                }
+            }
+
+            let video_codec = stats.video_codec.as_deref();
+            let audio_codec = stats.audio_codec.as_deref();
+            match target.protocol {
+                TargetProtocol::Rtmp if video_copy => {
+                    if let Some(codec) = video_codec.filter(|codec| *codec != "h264") {
+                        problems.push(format!(
+                            "Video is {codec}; most RTMP ingests accept only H.264 (HEVC and AV1 need Enhanced RTMP)"
+                        ));
+                    }
+                    if let Some(profile) = stats.video_profile.as_deref() {
+                        if video_codec == Some("h264") && !PLATFORM_H264_PROFILES.contains(&profile) {
+                            problems.push(format!(
+                                "H.264 {profile} profile is not accepted by streaming platforms; use High or Main"
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if audio_copy && target.protocol == TargetProtocol::Rtmp {
+                if let Some(codec) = audio_codec.filter(|codec| !matches!(*codec, "aac" | "mp3")) {
+                    problems.push(format!("Audio is {codec}; RTMP ingests expect AAC"));
+                }
+            }
+
+            warnings.extend(problems.into_iter().map(|message| IngestWarning {
+                group_id: group.id.clone(),
+                target_id: target.id.clone(),
+                message,
+            }));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StreamTarget;
+
+    /// 1920x1080 (1088 cropped) High profile level 4.2 SPS in a config record
+    fn avc_sequence_header() -> FlvTag {
+        let sps = [0x67, 0x64, 0x00, 0x2a, 0xac, 0xd9, 0x00, 0x78, 0x02, 0x27, 0xe5, 0x40];
+        let mut data = vec![0x17, 0, 0, 0, 0, 1, 0x64, 0x00, 0x2a, 0xff, 0xe1];
+        data.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        data.extend_from_slice(&sps);
+        FlvTag::new(FlvTagKind::Video, 0, data)
+    }
+
+    #[test]
+    fn test_measures_h264_aac_ingest() {
+        let analyzer = IngestAnalyzer::new();
+        assert!(analyzer.snapshot().is_none());
+
+        analyzer.observe(&avc_sequence_header());
+        // AAC LC, 48kHz, stereo
+        analyzer.observe(&FlvTag::new(FlvTagKind::Audio, 0, vec![0xaf, 0, 0x11, 0x90]));
+        // 30fps with a key frame every 6s, 5000 bytes per frame
+        for frame in 0..=360u32 {
+            let flags = if frame % 180 == 0 { 0x17 } else { 0x27 };
+            let mut data = vec![flags, 1, 0, 0, 0];
+            data.resize(5_000, 0);
+            analyzer.observe(&FlvTag::new(FlvTagKind::Video, frame * 100 / 3, data));
+        }
+
+        let stats = analyzer.snapshot().unwrap();
+        assert_eq!(stats.video_codec.as_deref(), Some("h264"));
+        assert_eq!(stats.video_profile.as_deref(), Some("High"));
+        assert_eq!(stats.video_level.as_deref(), Some("4.2"));
+        assert_eq!((stats.width, stats.height), (Some(1920), Some(1080)));
+        assert_eq!(stats.keyframe_interval_secs, Some(6.0));
+        assert!((stats.fps.unwrap() - 30.0).abs() < 0.1);
+        assert!((stats.video_bitrate_kbps.unwrap() - 1200.0).abs() < 5.0);
+        assert_eq!(stats.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(stats.audio_profile.as_deref(), Some("LC"));
+        assert_eq!((stats.audio_sample_rate, stats.audio_channels), (Some(48_000), Some(2)));
+
+        analyzer.reset();
+        assert!(analyzer.snapshot().is_none());
+    }
+
+    #[test]
+    fn test_rejects_sps_with_overflowing_fields() {
+        // Baseline profile, then every Exp-Golomb code at its maximum length:
+        // 31 zero bits, a one and 31 one bits (0xFFFF_FFFE)
+        let mut bits = format!("01000010{}", "0".repeat(16));
+        let max_ue = format!("{}1{}", "0".repeat(31), "1".repeat(31));
+        for _ in 0..5 {
+            bits += &max_ue; // sps id, log2_max_frame_num, poc type (>1), max refs
+        }
+        bits += "0"; // gaps_in_frame_num_value_allowed_flag
+        bits += &max_ue; // pic_width_in_mbs_minus1
+        bits += &max_ue; // pic_height_in_map_units_minus1
+        bits += "1"; // frame_mbs_only_flag
+        bits += "1"; // direct_8x8_inference_flag
+        bits += "1"; // frame_cropping_flag
+        for _ in 0..4 {
+            bits += &max_ue;
+        }
+        while bits.len() % 8 != 0 {
+            bits.push('0');
+        }
+        let payload: Vec<u8> = bits
+            .as_bytes()
+            .chunks(8)
+            .map(|byte| u8::from_str_radix(std::str::from_utf8(byte).unwrap(), 2).unwrap())
+            .collect();
+        assert_eq!(sps_resolution(&payload), None);
+    }
+
+    #[test]
+    fn test_warns_only_for_passthrough_targets() {
+        let stats = IngestStats {
+            video_codec: Some("hevc".to_string()),
+            keyframe_interval_secs: Some(8.0),
+            audio_codec: Some("opus".to_string()),
+            ..IngestStats::default()
+        };
+        let target = |id: &str, protocol: &str| -> StreamTarget {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "protocol": protocol,
+                "url": "rtmp://live.example.com/app",
+                "streamKey": "secret-key",
+            }))
+            .unwrap()
+        };
+        let twitch = target("twitch", "rtmp");
+        let mut passthrough = OutputGroup::new();
+        passthrough.id = "passthrough".to_string();
+        passthrough.stream_targets = vec![target("recording", "record"), twitch.clone()];
+
+        let mut encoded = OutputGroup::new();
+        encoded.id = "encoded".to_string();
+        encoded.video.codec = "libx264".to_string();
+        encoded.audio.codec = "aac".to_string();
+        encoded.stream_targets = vec![twitch];
+
+        let warnings = passthrough_warnings(&stats, &[passthrough, encoded]);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().all(|w| w.group_id == "passthrough" && w.target_id == "twitch"));
+        assert!(warnings[0].message.contains("Keyframe interval is 8.0s"));
+    }
+}
@@ -0,0 +1,180 @@
+// Ingest Probe Service
+// Measures latency to platform ingest servers to pick the nearest one
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+use crate::models::{IngestServer, IngestServerProbe};
+use crate::services::rtmp::{client_hello, HANDSHAKE_SIZE};
+
+/// Time allowed for each connect and for the handshake reply
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Connects per server; the fastest is kept to smooth out jitter
+const CONNECT_ATTEMPTS: usize = 3;
+
+/// Measure every server at once, returning them fastest first
+pub async fn probe_ingest_servers(servers: &[IngestServer]) -> Vec<IngestServerProbe> {
+    let mut probes = JoinSet::new();
+    for server in servers.iter().cloned() {
+        probes.spawn(async move {
+            let mut probe = IngestServerProbe {
+                name: server.name,
+                url: server.url,
+                latency_ms: None,
+                handshake_ms: None,
+                error: None,
+            };
+            match probe_ingest_server(&probe.url).await {
+                Ok((latency, handshake)) => {
+                    probe.latency_ms = Some(latency);
+                    probe.handshake_ms = handshake;
+                }
+                Err(e) => probe.error = Some(e),
+            }
+            probe
+        });
+    }
+
+    let mut results = Vec::with_capacity(servers.len());
+    while let Some(result) = probes.join_next().await {
+        if let Ok(probe) = result {
+            results.push(probe);
+        }
+    }
+    results.sort_by_key(|probe| (probe.error.is_some(), probe.latency_ms.unwrap_or(u32::MAX)));
+    results
+}
+
+/// Fastest server that answered
+pub fn fastest_server(probes: &[IngestServerProbe]) -> Option<IngestServer> {
+    probes
+        .iter()
+        .filter(|probe| probe.error.is_none())
+        .min_by_key(|probe| probe.latency_ms.unwrap_or(u32::MAX))
+        .map(|probe| IngestServer {
+            name: probe.name.clone(),
+            url: probe.url.clone(),
+        })
+}
+
+/// Best TCP connect time to an ingest server, plus its RTMP handshake time for plain RTMP
+///
+/// RTMPS servers only get the connect time, which is the round trip the choice depends on.
+pub async fn probe_ingest_server(url: &str) -> Result<(u32, Option<u32>), String> {
+    let (host, port, tls) = ingest_address(url)?;
+    let addr = timeout(PROBE_TIMEOUT, tokio::net::lookup_host((host.as_str(), port)))
+        .await
+        .map_err(|_| format!("Timed out resolving {host}"))?
+        .map_err(|e| format!("Cannot resolve {host}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("No address for {host}"))?;
+
+    let mut best: Option<Duration> = None;
+    let mut stream = None;
+    for _ in 0..CONNECT_ATTEMPTS {
+        let (elapsed, connected) = timed_connect(addr).await?;
+        best = Some(best.map_or(elapsed, |best| best.min(elapsed)));
+        stream = Some(connected);
+    }
+    let latency = best.map_or(0, as_ms);
+
+    if tls {
+        return Ok((latency, None));
+    }
+    let mut stream = stream.ok_or_else(|| "Not connected".to_string())?;
+    let handshake = timeout(PROBE_TIMEOUT, rtmp_hello(&mut stream))
+        .await
+        .map_err(|_| "No RTMP handshake reply".to_string())?
+        .map_err(|e| format!("RTMP handshake failed: {e}"))?;
+    Ok((latency, Some(as_ms(handshake))))
+}
+
+async fn timed_connect(addr: SocketAddr) -> Result<(Duration, TcpStream), String> {
+    let started = Instant::now();
+    let stream = timeout(PROBE_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .map_err(|_| "Connection timed out".to_string())?
+        .map_err(|e| format!("Cannot connect: {e}"))?;
+    Ok((started.elapsed(), stream))
+}
+
+/// Send C0+C1 and wait for S0+S1; the connection is dropped before anything is published
+async fn rtmp_hello(stream: &mut TcpStream) -> std::io::Result<Duration> {
+    let started = Instant::now();
+    stream.write_all(&client_hello()).await?;
+    let mut reply = vec![0u8; 1 + HANDSHAKE_SIZE];
+    stream.read_exact(&mut reply).await?;
+    Ok(started.elapsed())
+}
+
+/// Host, port and whether TLS is used, from an rtmp:// or rtmps:// URL
+fn ingest_address(url: &str) -> Result<(String, u16, bool), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid ingest URL: {e}"))?;
+    let (default_port, tls) = match parsed.scheme() {
+        "rtmp" => (1935, false),
+        "rtmps" => (443, true),
+        scheme => return Err(format!("Cannot probe {scheme}:// servers")),
+    };
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "Ingest URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    Ok((host, parsed.port().unwrap_or(default_port), tls))
+}
+
+fn as_ms(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rtmp::server_handshake;
+
+    #[test]
+    fn test_ingest_address() {
+        assert_eq!(
+            ingest_address("rtmp://live-fra.twitch.tv/app").unwrap(),
+            ("live-fra.twitch.tv".to_string(), 1935, false)
+        );
+        assert_eq!(
+            ingest_address("rtmps://a.rtmps.youtube.com:443/live2").unwrap(),
+            ("a.rtmps.youtube.com".to_string(), 443, true)
+        );
+        assert!(ingest_address("srt://example.com:9000").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_probes_and_ranks_servers() {
+        // Local RTMP server answering handshakes
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = server_handshake(&mut stream);
+            }
+        });
+
+        // A port nothing listens on
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+
+        let servers = vec![
+            IngestServer { name: "Down".to_string(), url: format!("rtmp://127.0.0.1:{closed_port}/app") },
+            IngestServer { name: "Local".to_string(), url: format!("rtmp://127.0.0.1:{port}/app") },
+        ];
+        let probes = probe_ingest_servers(&servers).await;
+        assert_eq!(probes[0].name, "Local");
+        assert!(probes[0].latency_ms.is_some());
+        assert!(probes[0].handshake_ms.is_some());
+        assert!(probes[1].error.is_some());
+        assert_eq!(fastest_server(&probes).unwrap().name, "Local");
+    }
+}
@@ -0,0 +1,525 @@
+// Ingest Switcher Service
+// Feeds output groups from the ingest, or from a backup slate while the publisher is away
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::models::{SlateSettings, SlateSource};
+use crate::services::{flv_header, FlvTag, FlvTagKind, OutputChunk};
+
+/// Chunks buffered per group before it starts dropping tags
+const QUEUE_CHUNKS: usize = 1024;
+
+/// Gap put between the last tag of one source and the first of the next (ms)
+const SWITCH_GAP_MS: i64 = 1;
+
+/// Source currently forwarded to the groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestSource {
+    Primary,
+    Slate,
+}
+
+/// Decoder setup of one source, replayed to groups that join or switch to it
+#[derive(Default)]
+struct SourceHeaders {
+    metadata: Option<FlvTag>,
+    video_config: Option<FlvTag>,
+    audio_config: Option<FlvTag>,
+}
+
+impl SourceHeaders {
+    /// Remember a header tag; false if the tag is media
+    fn remember(&mut self, tag: &FlvTag) -> bool {
+        let slot = if tag.is_metadata() {
+            &mut self.metadata
+        } else if tag.is_sequence_header() && tag.kind == FlvTagKind::Video {
+            &mut self.video_config
+        } else if tag.is_sequence_header() {
+            &mut self.audio_config
+        } else {
+            return false;
+        };
+        *slot = Some(tag.clone());
+        true
+    }
+
+    fn tags(&self) -> impl Iterator<Item = &FlvTag> {
+        [&self.metadata, &self.video_config, &self.audio_config]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// A group's input queue
+struct Subscriber {
+    sender: SyncSender<OutputChunk>,
+    /// Media is held back until the next key frame (after joining, switching or dropping)
+    waiting_for_keyframe: bool,
+    dropped: u64,
+}
+
+#[derive(Default)]
+struct SwitchState {
+    subscribers: HashMap<String, Subscriber>,
+    primary: SourceHeaders,
+    slate: SourceHeaders,
+    on_slate: bool,
+    /// When the ingest last delivered a tag
+    last_primary: Option<Instant>,
+    /// Added to the active source's timestamps so the output never jumps back
+    offset: i64,
+    /// Latest timestamp written to the groups
+    last_out: Option<u32>,
+    /// The next tag starts a new timeline (switch or new publisher)
+    rebase: bool,
+}
+
+impl SwitchState {
+    fn headers_mut(&mut self, source: IngestSource) -> &mut SourceHeaders {
+        match source {
+            IngestSource::Primary => &mut self.primary,
+            IngestSource::Slate => &mut self.slate,
+        }
+    }
+
+    fn active(&self) -> IngestSource {
+        if self.on_slate {
+            IngestSource::Slate
+        } else {
+            IngestSource::Primary
+        }
+    }
+
+    /// Start a new timeline at the next tag and hold media until a key frame
+    fn begin_timeline(&mut self) {
+        self.rebase = true;
+        for subscriber in self.subscribers.values_mut() {
+            subscriber.waiting_for_keyframe = true;
+        }
+    }
+
+    fn forward(&mut self, source: IngestSource, tag: FlvTag) {
+        let is_header = self.headers_mut(source).remember(&tag);
+        if source != self.active() {
+            return;
+        }
+
+        if self.rebase {
+            self.rebase = false;
+            let start = self.last_out.map_or(0, |last| i64::from(last) + SWITCH_GAP_MS);
+            self.offset = start - i64::from(tag.timestamp);
+            // Decoders need the new source's setup before its media
+            let headers: Vec<FlvTag> = self
+                .headers_mut(source)
+                .tags()
+                .map(|header| FlvTag::new(header.kind, tag.timestamp, header.data.clone()))
+                .collect();
+            for header in headers {
+                self.broadcast(&header);
+            }
+            if is_header {
+                return;
+            }
+        }
+        self.broadcast(&tag);
+    }
+
+    fn broadcast(&mut self, tag: &FlvTag) {
+        let timestamp = (i64::from(tag.timestamp) + self.offset).clamp(0, i64::from(u32::MAX)) as u32;
+        self.last_out = Some(self.last_out.map_or(timestamp, |last| last.max(timestamp)));
+        let mut bytes = Vec::with_capacity(tag.data.len() + 15);
+        FlvTag::new(tag.kind, timestamp, tag.data.clone()).write_to(&mut bytes);
+        let chunk: OutputChunk = Arc::from(bytes);
+
+        let is_media = !(tag.is_metadata() || tag.is_sequence_header());
+        let is_keyframe = tag.is_keyframe();
+        let has_video = self.headers_mut(self.active()).video_config.is_some();
+        self.subscribers.retain(|id, subscriber| {
+            if is_media && subscriber.waiting_for_keyframe {
+                if has_video && !is_keyframe {
+                    return true;
+                }
+                subscriber.waiting_for_keyframe = false;
+            }
+            match subscriber.sender.try_send(Arc::clone(&chunk)) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    // A dropped tag breaks decoding until the next key frame
+                    subscriber.dropped += 1;
+                    subscriber.waiting_for_keyframe = true;
+                    if subscriber.dropped == 1 || subscriber.dropped % QUEUE_CHUNKS as u64 == 0 {
+                        log::warn!(
+                            "[FFmpeg:ingest] {id} is falling behind, dropped {} tags",
+                            subscriber.dropped
+                        );
+                    }
+                    true
+                }
+                // The writer exited (group process gone)
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+}
+
+/// Forwards either the ingest or the slate to every running group as FLV
+///
+/// Groups join mid-stream without restarting anything: each new subscriber
+/// gets an FLV header and the active source's metadata and sequence headers,
+/// then media from the next key frame. On a switch between sources the new
+/// source's headers are replayed and its timestamps are shifted to continue
+/// where the old source stopped, so the groups see one continuous input.
+#[derive(Default)]
+pub struct IngestSwitcher {
+    state: Mutex<SwitchState>,
+}
+
+impl IngestSwitcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe a group's input
+    pub fn subscribe(&self, group_id: &str) -> Receiver<OutputChunk> {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CHUNKS);
+        let mut state = self.lock();
+
+        let timestamp = state.last_out.unwrap_or(0);
+        let mut start = flv_header();
+        let active = state.active();
+        for header in state.headers_mut(active).tags() {
+            FlvTag::new(header.kind, timestamp, header.data.clone()).write_to(&mut start);
+        }
+        let _ = sender.try_send(Arc::from(start));
+
+        state.subscribers.insert(
+            group_id.to_string(),
+            Subscriber {
+                sender,
+                waiting_for_keyframe: true,
+                dropped: 0,
+            },
+        );
+        receiver
+    }
+
+    /// End a group's input
+    pub fn unsubscribe(&self, group_id: &str) -> bool {
+        self.lock().subscribers.remove(group_id).is_some()
+    }
+
+    /// End every group's input (the ingest is gone and there is no slate)
+    pub fn close_all(&self) {
+        self.lock().subscribers.clear();
+    }
+
+    /// A publisher started; its timestamps and headers replace the previous one's
+    pub fn begin_primary(&self) {
+        let mut state = self.lock();
+        state.primary = SourceHeaders::default();
+        if !state.on_slate {
+            state.begin_timeline();
+        }
+    }
+
+    /// A slate process started
+    pub fn begin_slate(&self) {
+        let mut state = self.lock();
+        state.slate = SourceHeaders::default();
+        if state.on_slate {
+            state.begin_timeline();
+        }
+    }
+
+    /// Forward an ingest tag; the publisher returning takes over from the slate at once
+    pub fn feed_primary(&self, tag: FlvTag) {
+        let mut state = self.lock();
+        state.last_primary = Some(Instant::now());
+        if state.on_slate {
+            state.on_slate = false;
+            state.begin_timeline();
+        }
+        state.forward(IngestSource::Primary, tag);
+    }
+
+    /// Forward a slate tag while the slate is on air
+    pub fn feed_slate(&self, tag: FlvTag) {
+        self.lock().forward(IngestSource::Slate, tag);
+    }
+
+    /// Put the slate on air
+    pub fn switch_to_slate(&self) {
+        let mut state = self.lock();
+        if !state.on_slate {
+            state.on_slate = true;
+            state.begin_timeline();
+        }
+    }
+
+    /// Take the slate off air without waiting for the publisher
+    pub fn switch_to_primary(&self) {
+        let mut state = self.lock();
+        if state.on_slate {
+            state.on_slate = false;
+            state.begin_timeline();
+        }
+    }
+
+    /// Forget the previous session's ingest once every group has stopped
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.last_primary = None;
+        state.on_slate = false;
+        state.slate = SourceHeaders::default();
+        state.last_out = None;
+        state.begin_timeline();
+    }
+
+    /// Source currently forwarded
+    pub fn source(&self) -> IngestSource {
+        self.lock().active()
+    }
+
+    /// Whether the ingest has been silent for longer than `delay`
+    ///
+    /// An ingest that never connected is not missing: the slate covers drops,
+    /// it does not go live on its own before the publisher has started.
+    pub fn primary_missing(&self, delay: Duration) -> bool {
+        self.lock()
+            .last_primary
+            .is_some_and(|last| last.elapsed() >= delay)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SwitchState> {
+        self.state.lock().unwrap_or_else(|e| {
+            log::warn!("Ingest switcher mutex poisoned, recovering: {e}");
+            e.into_inner()
+        })
+    }
+}
+
+/// Build FFmpeg arguments for a slate process writing real-time FLV to stdout
+///
+/// H.264 video and stereo 48 kHz AAC audio, like a typical publisher.
+/// Without `drawtext` (FFmpeg built without libfreetype) a card shows no message.
+pub fn build_slate_args(settings: &SlateSettings, drawtext: bool) -> Vec<String> {
+    let size = format!("{}x{}", settings.width, settings.height);
+    let fps = settings.fps.to_string();
+    let mut args: Vec<String> = vec!["-nostdin".into(), "-re".into()];
+
+    match settings.source {
+        SlateSource::Card => {
+            args.extend([
+                "-f".into(),
+                "lavfi".into(),
+                "-i".into(),
+                format!("color=c=0x101820:s={size}:r={fps}"),
+            ]);
+        }
+        SlateSource::Image => {
+            args.extend([
+                "-loop".into(),
+                "1".into(),
+                "-framerate".into(),
+                fps.clone(),
+                "-i".into(),
+                settings.path.clone(),
+            ]);
+        }
+        SlateSource::Video => {
+            args.extend([
+                "-stream_loop".into(),
+                "-1".into(),
+                "-i".into(),
+                settings.path.clone(),
+            ]);
+        }
+    }
+
+    args.extend([
+        "-re".into(),
+        "-f".into(),
+        "lavfi".into(),
+        "-i".into(),
+        "anullsrc=channel_layout=stereo:sample_rate=48000".into(),
+    ]);
+
+    // Letterbox files into the slate size; cards carry the message instead
+    let filter = match settings.source {
+        SlateSource::Card if drawtext && !settings.message.trim().is_empty() => format!(
+            "drawtext=text='{}':fontcolor=white:fontsize=h/12:x=(w-text_w)/2:y=(h-text_h)/2",
+            escape_drawtext(settings.message.trim())
+        ),
+        SlateSource::Card => "null".to_string(),
+        SlateSource::Image | SlateSource::Video => format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,fps={fps}",
+            w = settings.width,
+            h = settings.height
+        ),
+    };
+
+    let gop = (settings.fps * 2).to_string();
+    args.extend([
+        "-filter:v".into(),
+        format!("{filter},format=yuv420p"),
+        "-map".into(),
+        "0:v".into(),
+        "-map".into(),
+        "1:a".into(),
+        "-c:v".into(),
+        "libx264".into(),
+        "-preset".into(),
+        "veryfast".into(),
+        "-tune".into(),
+        "stillimage".into(),
+        "-b:v".into(),
+        "1500k".into(),
+        "-g".into(),
+        gop,
+        "-c:a".into(),
+        "aac".into(),
+        "-b:a".into(),
+        "128k".into(),
+        "-f".into(),
+        "flv".into(),
+        "pipe:1".into(),
+    ]);
+    args
+}
+
+/// Check that a slate file exists before spawning FFmpeg for it
+pub fn check_slate_source(settings: &SlateSettings) -> Result<(), String> {
+    settings.validate()?;
+    if settings.source != SlateSource::Card && !Path::new(&settings.path).is_file() {
+        return Err(format!("Backup slate file not found: {}", settings.path));
+    }
+    Ok(())
+}
+
+/// Whether `ffmpeg -filters` output lists the filter `name`
+pub fn lists_filter(filters: &str, name: &str) -> bool {
+    filters
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(name))
+}
+
+/// Escape text for a drawtext option inside a filter graph
+fn escape_drawtext(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            // A quote ends the quoted value, so close, escape and reopen it
+            '\'' => escaped.push_str("'\\\\\\''"),
+            '\\' | ':' | '%' | ',' | ';' | '[' | ']' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::FlvReader;
+
+    fn video(timestamp: u32, keyframe: bool) -> FlvTag {
+        let frame_type = if keyframe { 0x17 } else { 0x27 };
+        FlvTag::new(FlvTagKind::Video, timestamp, vec![frame_type, 1, 0, 0, 0])
+    }
+
+    fn video_config() -> FlvTag {
+        FlvTag::new(FlvTagKind::Video, 0, vec![0x17, 0, 0, 0, 0])
+    }
+
+    /// Tags queued for a group so far
+    fn received(group: &Receiver<OutputChunk>, stream: &mut Vec<u8>) -> Vec<FlvTag> {
+        for chunk in group.try_iter() {
+            stream.extend_from_slice(&chunk);
+        }
+        let mut reader = FlvReader::new(stream.as_slice());
+        std::iter::from_fn(|| reader.next_tag().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_switches_to_slate_and_back() {
+        let switcher = IngestSwitcher::new();
+        let group = switcher.subscribe("group1");
+        assert!(!switcher.primary_missing(Duration::ZERO));
+
+        switcher.begin_primary();
+        switcher.feed_primary(video_config());
+        switcher.feed_primary(video(1000, true));
+        switcher.feed_primary(video(1040, false));
+        assert!(switcher.primary_missing(Duration::ZERO));
+        assert!(!switcher.primary_missing(Duration::from_secs(60)));
+
+        // Slate output is only cached until the slate is put on air
+        switcher.feed_slate(video_config());
+        switcher.feed_slate(video(0, true));
+        switcher.switch_to_slate();
+        assert_eq!(switcher.source(), IngestSource::Slate);
+        // Held back until the slate's next key frame
+        switcher.feed_slate(video(40, false));
+        switcher.feed_slate(video(80, true));
+
+        // The publisher coming back wins immediately
+        switcher.feed_primary(video(5000, true));
+        switcher.feed_slate(video(120, true));
+        assert_eq!(switcher.source(), IngestSource::Primary);
+
+        let tags = received(&group, &mut Vec::new());
+        let timestamps: Vec<u32> = tags.iter().map(|tag| tag.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 1000, 1040, 1041, 1081, 1082, 1082]);
+        let headers: Vec<bool> = tags.iter().map(FlvTag::is_sequence_header).collect();
+        assert_eq!(headers, vec![true, false, false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_late_group_starts_at_key_frame() {
+        let switcher = IngestSwitcher::new();
+        switcher.begin_primary();
+        switcher.feed_primary(video_config());
+        switcher.feed_primary(video(0, true));
+        switcher.feed_primary(video(40, false));
+
+        let late = switcher.subscribe("late");
+        switcher.feed_primary(video(80, false));
+        switcher.feed_primary(video(120, true));
+
+        let tags = received(&late, &mut Vec::new());
+        assert_eq!(tags, vec![
+            FlvTag::new(FlvTagKind::Video, 40, video_config().data),
+            video(120, true),
+        ]);
+    }
+
+    #[test]
+    fn test_card_slate_args() {
+        let settings = SlateSettings {
+            enabled: true,
+            message: "Back soon: 5 min".to_string(),
+            ..SlateSettings::default()
+        };
+        let args = build_slate_args(&settings, true).join(" ");
+        assert!(args.contains("-f lavfi -i color=c=0x101820:s=1280x720:r=30"));
+        assert!(args.contains("drawtext=text='Back soon\\: 5 min'"));
+        assert!(args.ends_with("-f flv pipe:1"));
+
+        let plain = build_slate_args(&settings, false).join(" ");
+        assert!(!plain.contains("drawtext"));
+        assert!(plain.contains("-filter:v null,format=yuv420p"));
+    }
+
+    #[test]
+    fn test_lists_filter() {
+        let filters = "Filters:\n  T.. = Timeline support\n ---\n T.C drawbox            V->V       Draw a colored box on the input video.\n T.C drawtext           V->V       Draw text on top of video frames using libfreetype library.\n";
+        assert!(lists_filter(filters, "drawtext"));
+        assert!(!lists_filter(&filters.replace("drawtext", "drawgrid"), "drawtext"));
+    }
+}
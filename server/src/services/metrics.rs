@@ -0,0 +1,269 @@
+// Metrics Service
+// Prometheus text exposition of streaming, chat and process metrics
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use serde_json::Value;
+use crate::models::{ChatConnectionStatus, ChatPlatformStatus, FFmpegProcessStats, StreamStats};
+use crate::services::{ObsConnectionStatus, ObsStreamStatus};
+
+/// Content type of the exposition format written by [`MetricsCollector::render`]
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Metric name, help text and how to read its value
+type MetricDef<T, V> = (&'static str, &'static str, fn(&T) -> V);
+
+/// Reconnect and error counts of one group since the server started
+#[derive(Debug, Default, Clone, Copy)]
+struct GroupCounters {
+    errors: u64,
+    reconnect_attempts: u64,
+    reconnects: u64,
+    gave_up: u64,
+}
+
+/// State gathered from the services at scrape time
+pub struct MetricsSnapshot {
+    /// Groups with a running FFmpeg process; stats gauges are only written for these
+    pub active_group_ids: Vec<String>,
+    pub chat: Vec<ChatPlatformStatus>,
+    pub obs_connection: ObsConnectionStatus,
+    pub obs_stream: ObsStreamStatus,
+    pub relay_running: bool,
+    pub publisher_connected: bool,
+    pub slate_on_air: bool,
+    pub processes: Vec<FFmpegProcessStats>,
+}
+
+/// Keeps the latest stats and event counters for `/metrics`
+#[derive(Default)]
+pub struct MetricsCollector {
+    stats: Mutex<HashMap<String, StreamStats>>,
+    counters: Mutex<HashMap<String, GroupCounters>>,
+}
+
+impl MetricsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed an emitted event into the collector
+    pub fn observe(&self, event: &str, payload: &Value) {
+        if event == "stream_stats" {
+            if let (Ok(stats), Ok(mut latest)) = (serde_json::from_value::<StreamStats>(payload.clone()), self.stats.lock()) {
+                latest.insert(stats.group_id.clone(), stats);
+            }
+            return;
+        }
+
+        let Some(group_id) = payload["groupId"].as_str() else {
+            return;
+        };
+        let Ok(mut counters) = self.counters.lock() else {
+            return;
+        };
+        let counter = counters.entry(group_id.to_string()).or_default();
+        match event {
+            "stream_error" => counter.errors += 1,
+            "stream_reconnecting" => counter.reconnect_attempts += 1,
+            "stream_reconnected" => counter.reconnects += 1,
+            "stream_gave_up" => counter.gave_up += 1,
+            _ => {}
+        }
+    }
+
+    /// Write every metric in the Prometheus text format
+    pub fn render(&self, snapshot: &MetricsSnapshot) -> String {
+        let mut out = MetricsWriter::default();
+
+        out.family("spiritstream_active_groups", "gauge", "Output groups with a running FFmpeg process");
+        out.sample("spiritstream_active_groups", &[], snapshot.active_group_ids.len() as f64);
+
+        let stats: Vec<StreamStats> = self
+            .stats
+            .lock()
+            .map(|latest| {
+                snapshot
+                    .active_group_ids
+                    .iter()
+                    .filter_map(|group_id| latest.get(group_id).cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let gauges: [MetricDef<StreamStats, f64>; 8] = [
+            ("spiritstream_stream_fps", "Output frames per second", |s| s.fps),
+            ("spiritstream_stream_bitrate_kbps", "Output bitrate in kbps", |s| s.bitrate),
+            ("spiritstream_stream_speed", "Encoding speed (1.0 = real-time)", |s| s.speed),
+            ("spiritstream_stream_frames", "Frames encoded by the current FFmpeg run", |s| s.frame as f64),
+            ("spiritstream_stream_dropped_frames", "Frames dropped by the current FFmpeg run", |s| s.dropped_frames as f64),
+            ("spiritstream_stream_dup_frames", "Frames duplicated by the current FFmpeg run", |s| s.dup_frames as f64),
+            ("spiritstream_stream_output_bytes", "Bytes written by the current FFmpeg run", |s| s.size as f64),
+            ("spiritstream_stream_time_seconds", "Media time encoded by the current FFmpeg run", |s| s.time),
+        ];
+        for (name, help, value) in gauges {
+            out.family(name, "gauge", help);
+            for group in &stats {
+                out.sample(name, &[("group_id", &group.group_id)], value(group));
+            }
+        }
+
+        let counters: Vec<(String, GroupCounters)> = self
+            .counters
+            .lock()
+            .map(|counters| {
+                let mut counters: Vec<_> = counters.iter().map(|(id, c)| (id.clone(), *c)).collect();
+                counters.sort_by(|a, b| a.0.cmp(&b.0));
+                counters
+            })
+            .unwrap_or_default();
+        let totals: [MetricDef<GroupCounters, u64>; 4] = [
+            ("spiritstream_stream_errors_total", "Stream errors reported", |c| c.errors),
+            ("spiritstream_stream_reconnect_attempts_total", "Automatic reconnect attempts", |c| c.reconnect_attempts),
+            ("spiritstream_stream_reconnects_total", "Successful automatic reconnects", |c| c.reconnects),
+            ("spiritstream_stream_gave_up_total", "Times reconnecting was given up", |c| c.gave_up),
+        ];
+        for (name, help, value) in totals {
+            out.family(name, "counter", help);
+            for (group_id, counter) in &counters {
+                out.sample(name, &[("group_id", group_id)], value(counter) as f64);
+            }
+        }
+
+        out.family("spiritstream_chat_messages_total", "counter", "Chat messages received since the platform connected");
+        for status in &snapshot.chat {
+            out.sample("spiritstream_chat_messages_total", &[("platform", status.platform.as_str())], status.message_count as f64);
+        }
+        out.family("spiritstream_chat_connected", "gauge", "Whether the chat platform is connected");
+        for status in &snapshot.chat {
+            let connected = status.status == ChatConnectionStatus::Connected;
+            out.sample("spiritstream_chat_connected", &[("platform", status.platform.as_str())], flag(connected));
+        }
+
+        out.family("spiritstream_obs_connected", "gauge", "Whether the OBS WebSocket is connected");
+        out.sample("spiritstream_obs_connected", &[], flag(snapshot.obs_connection == ObsConnectionStatus::Connected));
+        out.family("spiritstream_obs_streaming", "gauge", "Whether OBS reports its stream as active");
+        out.sample("spiritstream_obs_streaming", &[], flag(snapshot.obs_stream == ObsStreamStatus::Active));
+
+        out.family("spiritstream_relay_running", "gauge", "Whether the ingest listener is up");
+        out.sample("spiritstream_relay_running", &[], flag(snapshot.relay_running));
+        out.family("spiritstream_ingest_publisher_connected", "gauge", "Whether a publisher is live on the built-in RTMP server");
+        out.sample("spiritstream_ingest_publisher_connected", &[], flag(snapshot.publisher_connected));
+        out.family("spiritstream_slate_on_air", "gauge", "Whether groups are showing the backup slate");
+        out.sample("spiritstream_slate_on_air", &[], flag(snapshot.slate_on_air));
+
+        let processes: Vec<(&FFmpegProcessStats, String)> =
+            snapshot.processes.iter().map(|stats| (stats, stats.process.pid.to_string())).collect();
+        let process_metrics: [MetricDef<FFmpegProcessStats, f64>; 3] = [
+            ("spiritstream_ffmpeg_cpu_seconds_total", "CPU time of an FFmpeg child process", |p| p.cpu_seconds),
+            ("spiritstream_ffmpeg_resident_memory_bytes", "Resident memory of an FFmpeg child process", |p| p.rss_bytes as f64),
+            ("spiritstream_ffmpeg_threads", "Threads of an FFmpeg child process", |p| f64::from(p.threads)),
+        ];
+        for (name, help, value) in process_metrics {
+            let kind = if name.ends_with("_total") { "counter" } else { "gauge" };
+            out.family(name, kind, help);
+            for (stats, pid) in &processes {
+                let labels = [("role", stats.process.role.as_str()), ("id", stats.process.id.as_str()), ("pid", pid.as_str())];
+                out.sample(name, &labels, value(stats));
+            }
+        }
+
+        out.finish()
+    }
+}
+
+fn flag(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+#[derive(Default)]
+struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Escape a label value (backslash, double quote and line feed)
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChatPlatform, FFmpegProcess, FFmpegProcessRole};
+    use serde_json::json;
+
+    fn snapshot() -> MetricsSnapshot {
+        MetricsSnapshot {
+            active_group_ids: vec!["main".to_string()],
+            chat: vec![ChatPlatformStatus {
+                platform: ChatPlatform::Twitch,
+                status: ChatConnectionStatus::Connected,
+                message_count: 42,
+                error: None,
+            }],
+            obs_connection: ObsConnectionStatus::Disconnected,
+            obs_stream: ObsStreamStatus::Inactive,
+            relay_running: true,
+            publisher_connected: true,
+            slate_on_air: false,
+            processes: vec![FFmpegProcessStats {
+                process: FFmpegProcess { role: FFmpegProcessRole::Group, id: "main".to_string(), pid: 4242 },
+                cpu_percent: 80.0,
+                cpu_seconds: 12.5,
+                rss_bytes: 1024,
+                threads: 12,
+                nice: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_renders_stats_counters_and_processes() {
+        let collector = MetricsCollector::new();
+        for group_id in ["main", "stopped"] {
+            collector.observe("stream_stats", &json!({
+                "groupId": group_id, "frame": 300, "fps": 30.0, "bitrate": 6000.5, "speed": 1.0,
+                "size": 1000, "time": 10.0, "droppedFrames": 2, "dupFrames": 0
+            }));
+        }
+        collector.observe("stream_reconnecting", &json!({ "groupId": "main", "attempt": 1 }));
+        collector.observe("stream_reconnected", &json!({ "groupId": "main", "attempt": 1 }));
+
+        let text = collector.render(&snapshot());
+        assert!(text.contains("# TYPE spiritstream_stream_bitrate_kbps gauge\n"));
+        assert!(text.contains("spiritstream_stream_bitrate_kbps{group_id=\"main\"} 6000.5\n"));
+        assert!(!text.contains("group_id=\"stopped\""));
+        assert!(text.contains("spiritstream_stream_reconnects_total{group_id=\"main\"} 1\n"));
+        assert!(text.contains("spiritstream_chat_messages_total{platform=\"twitch\"} 42\n"));
+        assert!(text.contains("spiritstream_obs_connected 0\n"));
+        assert!(text.contains("spiritstream_ffmpeg_cpu_seconds_total{role=\"group\",id=\"main\",pid=\"4242\"} 12.5\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -17,6 +17,27 @@ mod path_validator;
 mod obs_websocket;
 mod discord_webhook;
 mod oauth;
+mod whip_standin;
+mod recording;
+mod hls;
+mod target_status;
+mod reconnect_supervisor;
+mod output_fanout;
+mod flv;
+pub mod rtmp;
+mod native_ingest;
+mod ingest_switcher;
+mod ingest_analyzer;
+mod cron;
+mod scheduler;
+mod preflight;
+mod ingest_probe;
+mod session_history;
+mod process_stats;
+mod process_priority;
+mod metrics;
+mod health_monitor;
+mod ffmpeg_errors;
 
 pub use profile_manager::*;
 pub use ffmpeg_handler::*;
@@ -33,3 +54,23 @@ pub use path_validator::*;
 pub use obs_websocket::*;
 pub use discord_webhook::*;
 pub use oauth::*;
+pub use whip_standin::*;
+pub use recording::*;
+pub use hls::*;
+pub use target_status::*;
+pub use reconnect_supervisor::*;
+pub use output_fanout::*;
+pub use flv::*;
+pub use native_ingest::*;
+pub use ingest_switcher::*;
+pub use ingest_analyzer::*;
+pub use cron::*;
+pub use scheduler::*;
+pub use preflight::*;
+pub use ingest_probe::*;
+pub use session_history::*;
+pub use process_stats::*;
+pub use process_priority::*;
+pub use metrics::*;
+pub use health_monitor::*;
+pub use ffmpeg_errors::*;
@@ -0,0 +1,321 @@
+// Native Ingest Service
+// Connects the built-in RTMP server's publisher to the ingest switcher
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use crate::models::{IngestMetadata, IngestStatus, IpNetwork, RtmpInput, SlateSettings};
+use crate::services::rtmp::amf::{self, Amf0Value};
+use crate::services::rtmp::{PublishHandler, PublishRequest};
+use crate::services::{emit_event, EventSink, FlvTag, IngestAnalyzer, IngestSwitcher};
+
+/// Who may publish to the ingest (from the active profile's input settings)
+#[derive(Debug, Clone, Default)]
+pub struct IngestAccess {
+    /// Required stream key (empty = any key)
+    key: String,
+    /// Allowed source networks (empty = any address)
+    networks: Vec<IpNetwork>,
+}
+
+impl IngestAccess {
+    /// Build from a decrypted input
+    pub fn from_input(input: &RtmpInput) -> Result<Self, String> {
+        let networks = input
+            .allowed_networks
+            .iter()
+            .map(|network| IpNetwork::parse(network))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            key: input.ingest_key.clone(),
+            networks,
+        })
+    }
+
+    /// Check the connecting address against the allowlist
+    pub fn check_peer(&self, peer: SocketAddr) -> Result<(), String> {
+        if self.networks.is_empty() || self.networks.iter().any(|network| network.contains(peer.ip())) {
+            Ok(())
+        } else {
+            Err(format!("{} is not in the allowed networks", peer.ip()))
+        }
+    }
+
+    /// Check the stream name a publisher sent against the ingest key
+    pub fn check_key(&self, stream_key: &str) -> Result<(), String> {
+        // Some publishers append query parameters to the stream name
+        let stream_key = stream_key.split('?').next().unwrap_or_default();
+        if self.key.is_empty() || constant_time_eq(stream_key.as_bytes(), self.key.as_bytes()) {
+            Ok(())
+        } else {
+            Err("Invalid stream key".to_string())
+        }
+    }
+}
+
+/// Compare secrets without leaking the matching prefix length through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Feeds one publisher at a time into the switcher and tracks who is live
+pub struct NativeIngest {
+    switcher: Arc<IngestSwitcher>,
+    slate: Arc<Mutex<SlateSettings>>,
+    access: Arc<Mutex<IngestAccess>>,
+    status: Arc<Mutex<Option<IngestStatus>>>,
+    analyzer: Arc<IngestAnalyzer>,
+    event_sink: Arc<dyn EventSink>,
+}
+
+impl NativeIngest {
+    pub fn new(
+        switcher: Arc<IngestSwitcher>,
+        slate: Arc<Mutex<SlateSettings>>,
+        access: Arc<Mutex<IngestAccess>>,
+        status: Arc<Mutex<Option<IngestStatus>>>,
+        analyzer: Arc<IngestAnalyzer>,
+        event_sink: Arc<dyn EventSink>,
+    ) -> Self {
+        Self { switcher, slate, access, status, analyzer, event_sink }
+    }
+
+    fn access(&self) -> IngestAccess {
+        self.access.lock().map(|access| access.clone()).unwrap_or_default()
+    }
+
+    /// Log and emit `ingest_rejected` for a refused publisher
+    fn reject(&self, peer: SocketAddr, reason: String) -> Result<(), String> {
+        log::warn!("Rejected ingest publisher {peer}: {reason}");
+        emit_event(
+            self.event_sink.as_ref(),
+            "ingest_rejected",
+            &serde_json::json!({
+                "clientAddr": peer.to_string(),
+                "reason": reason,
+            }),
+        );
+        Err(reason)
+    }
+
+    fn lock_status(&self) -> std::sync::MutexGuard<'_, Option<IngestStatus>> {
+        self.status.lock().unwrap_or_else(|e| {
+            log::warn!("Ingest status mutex poisoned, recovering: {e}");
+            e.into_inner()
+        })
+    }
+}
+
+impl PublishHandler for NativeIngest {
+    fn on_connect(&self, peer: SocketAddr) -> Result<(), String> {
+        match self.access().check_peer(peer) {
+            Ok(()) => Ok(()),
+            Err(reason) => self.reject(peer, reason),
+        }
+    }
+
+    fn on_publish(&self, request: &PublishRequest) -> Result<(), String> {
+        if let Err(reason) = self.access().check_key(&request.stream_key) {
+            return self.reject(request.peer, reason);
+        }
+
+        let status = {
+            let mut current = self.lock_status();
+            if let Some(live) = current.as_ref() {
+                return Err(format!("Another publisher ({}) is already live", live.client_addr));
+            }
+            let status = IngestStatus {
+                client_addr: request.peer.to_string(),
+                app: request.app.clone(),
+                connected_at: chrono::Utc::now().timestamp_millis(),
+                metadata: IngestMetadata::default(),
+            };
+            *current = Some(status.clone());
+            status
+        };
+
+        self.analyzer.reset();
+        self.switcher.begin_primary();
+        emit_event(self.event_sink.as_ref(), "ingest_connected", &status);
+        Ok(())
+    }
+
+    fn on_tag(&self, tag: FlvTag) {
+        if tag.is_metadata() {
+            if let Some(metadata) = parse_ingest_metadata(&tag.data) {
+                let status = self.lock_status().as_mut().map(|status| {
+                    status.metadata = metadata;
+                    status.clone()
+                });
+                if let Some(status) = status {
+                    emit_event(self.event_sink.as_ref(), "ingest_connected", &status);
+                }
+            }
+        }
+        self.analyzer.observe(&tag);
+        self.switcher.feed_primary(tag);
+    }
+
+    fn on_unpublish(&self, request: &PublishRequest) {
+        self.lock_status().take();
+        self.analyzer.reset();
+        emit_event(
+            self.event_sink.as_ref(),
+            "ingest_disconnected",
+            &serde_json::json!({ "clientAddr": request.peer.to_string() }),
+        );
+
+        // Without a slate the groups end with the publisher, as they always have
+        let slate_enabled = self.slate.lock().map(|s| s.enabled).unwrap_or(false);
+        if !slate_enabled {
+            self.switcher.close_all();
+        }
+    }
+}
+
+/// Read stream properties from an `onMetaData` script tag body
+pub fn parse_ingest_metadata(data: &[u8]) -> Option<IngestMetadata> {
+    let values = amf::decode_all(data).ok()?;
+    if values.first().and_then(Amf0Value::as_str) != Some("onMetaData") {
+        return None;
+    }
+    let properties = values.get(1)?;
+    let number = |key: &str| properties.get(key).and_then(Amf0Value::as_number);
+    let whole = |key: &str| number(key).filter(|n| *n > 0.0).map(|n| n.round() as u32);
+
+    Some(IngestMetadata {
+        width: whole("width"),
+        height: whole("height"),
+        frame_rate: number("framerate").or_else(|| number("videoframerate")),
+        video_codec: properties.get("videocodecid").and_then(|id| codec_name(id, VIDEO_CODECS)),
+        video_bitrate_kbps: number("videodatarate"),
+        audio_codec: properties.get("audiocodecid").and_then(|id| codec_name(id, AUDIO_CODECS)),
+        audio_bitrate_kbps: number("audiodatarate"),
+        audio_sample_rate: whole("audiosamplerate"),
+        audio_channels: whole("audiochannels").or_else(|| {
+            match properties.get("stereo") {
+                Some(Amf0Value::Boolean(stereo)) => Some(if *stereo { 2 } else { 1 }),
+                _ => None,
+            }
+        }),
+        encoder: properties.get("encoder").and_then(Amf0Value::as_str).map(str::to_string),
+    })
+}
+
+/// FLV codec ids and Enhanced RTMP FourCCs with their names
+const VIDEO_CODECS: &[(f64, &str, &str)] = &[
+    (7.0, "avc1", "h264"),
+    (12.0, "hvc1", "hevc"),
+    (13.0, "av01", "av1"),
+    (14.0, "vp09", "vp9"),
+];
+const AUDIO_CODECS: &[(f64, &str, &str)] = &[
+    (10.0, "mp4a", "aac"),
+    (2.0, ".mp3", "mp3"),
+    (13.0, "Opus", "opus"),
+];
+
+/// Codec name for a numeric id or FourCC, falling back to the raw value
+fn codec_name(id: &Amf0Value, table: &[(f64, &str, &str)]) -> Option<String> {
+    let known = table.iter().find(|(number, fourcc, _)| match id {
+        Amf0Value::Number(value) => value == number,
+        Amf0Value::String(value) => value == fourcc,
+        _ => false,
+    });
+    match (known, id) {
+        (Some((_, _, name)), _) => Some(name.to_string()),
+        (None, Amf0Value::Number(value)) => Some(format!("codec {value}")),
+        (None, Amf0Value::String(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::NoopEventSink;
+
+    fn ingest_with(access: IngestAccess) -> (NativeIngest, Arc<IngestSwitcher>) {
+        let switcher = Arc::new(IngestSwitcher::new());
+        let ingest = NativeIngest::new(
+            Arc::clone(&switcher),
+            Arc::new(Mutex::new(SlateSettings::default())),
+            Arc::new(Mutex::new(access)),
+            Arc::new(Mutex::new(None)),
+            Arc::new(IngestAnalyzer::new()),
+            Arc::new(NoopEventSink),
+        );
+        (ingest, switcher)
+    }
+
+    fn ingest() -> (NativeIngest, Arc<IngestSwitcher>) {
+        ingest_with(IngestAccess::default())
+    }
+
+    fn request(peer: &str) -> PublishRequest {
+        PublishRequest {
+            peer: peer.parse().unwrap(),
+            app: "live".to_string(),
+            stream_key: "key".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_checks_allowlist_and_key() {
+        let input = RtmpInput {
+            ingest_key: "s3cret".to_string(),
+            allowed_networks: vec!["192.168.1.0/24".to_string(), "fd00::/8".to_string()],
+            ..RtmpInput::default()
+        };
+        let (ingest, _switcher) = ingest_with(IngestAccess::from_input(&input).unwrap());
+
+        assert!(ingest.on_connect("192.168.1.20:50000".parse().unwrap()).is_ok());
+        assert!(ingest.on_connect("[::ffff:192.168.1.20]:50000".parse().unwrap()).is_ok());
+        assert!(ingest.on_connect("[fd12::1]:50000".parse().unwrap()).is_ok());
+        assert!(ingest.on_connect("192.168.2.20:50000".parse().unwrap()).is_err());
+
+        assert!(ingest.on_publish(&request("192.168.1.20:50000")).is_err());
+        let mut keyed = request("192.168.1.20:50000");
+        keyed.stream_key = "s3cret?bandwidth=auto".to_string();
+        assert!(ingest.on_publish(&keyed).is_ok());
+    }
+
+    #[test]
+    fn test_one_publisher_at_a_time() {
+        let (ingest, switcher) = ingest();
+        let group = switcher.subscribe("group1");
+        let first = request("192.168.1.20:50000");
+        ingest.on_publish(&first).unwrap();
+        assert!(ingest.on_publish(&request("192.168.1.21:50001")).is_err());
+
+        // Without a slate the groups' input ends with the publisher
+        ingest.on_unpublish(&first);
+        assert!(ingest.status.lock().unwrap().is_none());
+        assert_eq!(group.iter().count(), 1);
+        ingest.on_publish(&request("192.168.1.21:50001")).unwrap();
+    }
+
+    #[test]
+    fn test_parses_obs_metadata() {
+        let data = amf::encode_all(&[
+            Amf0Value::string("onMetaData"),
+            Amf0Value::EcmaArray(vec![
+                ("width".to_string(), Amf0Value::Number(1920.0)),
+                ("height".to_string(), Amf0Value::Number(1080.0)),
+                ("framerate".to_string(), Amf0Value::Number(60.0)),
+                ("videocodecid".to_string(), Amf0Value::Number(7.0)),
+                ("videodatarate".to_string(), Amf0Value::Number(6000.0)),
+                ("audiocodecid".to_string(), Amf0Value::string("mp4a")),
+                ("stereo".to_string(), Amf0Value::Boolean(true)),
+                ("encoder".to_string(), Amf0Value::string("obs-output module")),
+            ]),
+        ]);
+        let metadata = parse_ingest_metadata(&data).unwrap();
+        assert_eq!((metadata.width, metadata.height), (Some(1920), Some(1080)));
+        assert_eq!(metadata.frame_rate, Some(60.0));
+        assert_eq!(metadata.video_codec.as_deref(), Some("h264"));
+        assert_eq!(metadata.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(metadata.audio_channels, Some(2));
+        assert_eq!(metadata.encoder.as_deref(), Some("obs-output module"));
+        assert!(parse_ingest_metadata(&amf::encode_all(&[Amf0Value::string("onCuePoint")])).is_none());
+    }
+}
@@ -299,6 +299,20 @@ impl ObsWebSocketHandler {
         }
     }
 
+    /// Switch OBS's program output to a scene
+    pub async fn set_current_scene(&self, scene: &str) -> Result<(), String> {
+        let client = self.client.read().await;
+        if let Some(ref client) = *client {
+            client.scenes().set_current_program_scene(scene)
+                .await
+                .map_err(|e| format!("Failed to switch OBS scene: {e}"))?;
+            log::info!("Switched OBS scene to '{scene}'");
+            Ok(())
+        } else {
+            Err("Not connected to OBS".to_string())
+        }
+    }
+
     /// Check if connected to OBS
     pub async fn is_connected(&self) -> bool {
         let state = self.state.read().await;
@@ -0,0 +1,212 @@
+// Output Fanout Service
+// Splits an MPEG-TS stream across the FFmpeg processes that consume it
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::process::ChildStdin;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// MPEG-TS packet size; chunks are cut on packet boundaries
+pub const TS_PACKET_SIZE: usize = 188;
+
+/// Chunks buffered per subscriber before it starts dropping data
+const QUEUE_CHUNKS: usize = 256;
+
+/// Bytes read from the group process per pass
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// One batch of whole MPEG-TS packets, shared by every subscriber
+pub type OutputChunk = Arc<[u8]>;
+
+/// Subscriber queue with a count of chunks it had to drop
+struct Subscriber {
+    sender: SyncSender<OutputChunk>,
+    dropped: u64,
+}
+
+/// Copies an MPEG-TS stream to every subscribed process
+///
+/// A slow or stalled subscriber only loses its own data: queues are bounded and
+/// never block the reader, so the producer and the other subscribers keep going.
+#[derive(Default)]
+pub struct OutputFanout {
+    subscribers: Mutex<HashMap<String, Subscriber>>,
+}
+
+impl OutputFanout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe a consumer (target or group), replacing any previous subscription it had
+    pub fn subscribe(&self, id: &str) -> Receiver<OutputChunk> {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CHUNKS);
+        self.lock().insert(id.to_string(), Subscriber { sender, dropped: 0 });
+        receiver
+    }
+
+    /// Drop a subscription; its writer sees the end of the stream
+    pub fn unsubscribe(&self, id: &str) -> bool {
+        self.lock().remove(id).is_some()
+    }
+
+    /// Number of consumers currently fed
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Read `source` until it ends, handing each packet-aligned chunk to
+    /// `on_packets` and then to every subscriber
+    ///
+    /// Subscriptions are dropped when the source ends so consumers see end
+    /// of input and finish their files.
+    pub fn pump<R, F>(&self, source: R, label: &str, mut on_packets: F)
+    where
+        R: Read,
+        F: FnMut(&[u8]),
+    {
+        read_packets(source, label, |chunk| {
+            on_packets(&chunk);
+            self.send(chunk, label);
+        });
+        self.close_all();
+    }
+
+    /// Queue a chunk for every subscriber without blocking
+    pub fn send(&self, chunk: OutputChunk, label: &str) {
+        self.lock().retain(|id, subscriber| {
+            match subscriber.sender.try_send(Arc::clone(&chunk)) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    subscriber.dropped += 1;
+                    if subscriber.dropped == 1 || subscriber.dropped % QUEUE_CHUNKS as u64 == 0 {
+                        log::warn!(
+                            "[FFmpeg:{label}] {id} is falling behind, dropped {} chunks",
+                            subscriber.dropped
+                        );
+                    }
+                    true
+                }
+                // The writer exited (consumer process gone)
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+
+    /// End every subscription
+    pub fn close_all(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Subscriber>> {
+        self.subscribers.lock().unwrap_or_else(|e| {
+            log::warn!("Output fanout mutex poisoned, recovering: {e}");
+            e.into_inner()
+        })
+    }
+}
+
+/// Read MPEG-TS from `source` until it ends, yielding chunks cut on packet boundaries
+pub fn read_packets<R, F>(mut source: R, label: &str, mut on_chunk: F)
+where
+    R: Read,
+    F: FnMut(OutputChunk),
+{
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut pending: Vec<u8> = Vec::with_capacity(READ_BUFFER_SIZE + TS_PACKET_SIZE);
+
+    loop {
+        let len = match source.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => {
+                log::warn!("[FFmpeg:{label}] Output read failed: {err}");
+                break;
+            }
+        };
+
+        pending.extend_from_slice(&buffer[..len]);
+        let aligned = pending.len() - pending.len() % TS_PACKET_SIZE;
+        if aligned == 0 {
+            continue;
+        }
+
+        let chunk: OutputChunk = Arc::from(&pending[..aligned]);
+        pending.drain(..aligned);
+        on_chunk(chunk);
+    }
+}
+
+/// Copy a subscription into a process's stdin until either side goes away
+///
+/// Closing stdin when the subscription ends lets FFmpeg write trailers and exit.
+pub fn spawn_stdin_writer(chunks: Receiver<OutputChunk>, mut stdin: ChildStdin, label: String) {
+    thread::spawn(move || {
+        for chunk in chunks {
+            if let Err(err) = stdin.write_all(&chunk) {
+                log::debug!("[FFmpeg:{label}] Input closed: {err}");
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Reader that returns at most `step` bytes per call
+    struct Trickle {
+        data: Cursor<Vec<u8>>,
+        step: usize,
+    }
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.step);
+            self.data.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn test_pump_aligns_chunks_to_packets() {
+        let fanout = OutputFanout::new();
+        let receiver = fanout.subscribe("target1");
+        let source = Trickle {
+            data: Cursor::new(vec![0x47; TS_PACKET_SIZE * 3 + 100]),
+            step: 100,
+        };
+
+        let mut metered = 0;
+        fanout.pump(source, "group1", |packets| metered += packets.len());
+
+        let received: Vec<OutputChunk> = receiver.iter().collect();
+        assert!(received.iter().all(|chunk| chunk.len() % TS_PACKET_SIZE == 0));
+        assert_eq!(received.iter().map(|chunk| chunk.len()).sum::<usize>(), TS_PACKET_SIZE * 3);
+        assert_eq!(metered, TS_PACKET_SIZE * 3);
+        assert_eq!(fanout.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_full_queue_drops_instead_of_blocking() {
+        let fanout = OutputFanout::new();
+        let stalled = fanout.subscribe("stalled");
+        let gone = fanout.subscribe("gone");
+        drop(gone);
+
+        let source = Trickle {
+            data: Cursor::new(vec![0x47; TS_PACKET_SIZE * (QUEUE_CHUNKS + 10)]),
+            step: TS_PACKET_SIZE,
+        };
+        let mut metered = 0;
+        fanout.pump(source, "group1", |_| metered += 1);
+
+        // The meter saw every chunk even though the stalled target kept only a full queue
+        assert_eq!(metered, QUEUE_CHUNKS + 10);
+        assert_eq!(stalled.try_iter().count(), QUEUE_CHUNKS);
+    }
+}
@@ -0,0 +1,146 @@
+// Preflight Service
+// Local go-live checks that need no network or app state
+
+use std::net::{TcpListener, UdpSocket};
+use std::path::Path;
+use crate::models::{Encoders, OutputGroup, PreflightCheck, Profile, RtmpInput};
+use crate::services::Encryption;
+
+/// Check every encoder the profile's groups and renditions use against the available ones
+pub fn check_encoders(groups: &[OutputGroup], available: &Encoders) -> Vec<PreflightCheck> {
+    let mut required: Vec<(&str, bool)> = Vec::new();
+    for group in groups {
+        let videos = std::iter::once(&group.video.codec)
+            .chain(group.renditions.iter().map(|rendition| &rendition.video.codec));
+        for codec in videos {
+            required.push((codec.as_str(), true));
+        }
+        required.push((group.audio.codec.as_str(), false));
+    }
+
+    let mut missing = Vec::new();
+    let mut used = Vec::new();
+    for (codec, video) in required {
+        if codec.eq_ignore_ascii_case("copy") || used.contains(&codec) {
+            continue;
+        }
+        used.push(codec);
+        let list = if video { &available.video } else { &available.audio };
+        if !list.iter().any(|name| name == codec) {
+            missing.push(codec);
+        }
+    }
+
+    if used.is_empty() {
+        return vec![PreflightCheck::pass("encoders", "Encoders", "All groups pass the ingest through")];
+    }
+    if missing.is_empty() {
+        return vec![PreflightCheck::pass("encoders", "Encoders", format!("Available: {}", used.join(", ")))];
+    }
+    missing
+        .into_iter()
+        .map(|codec| {
+            PreflightCheck::fail(
+                "encoders",
+                format!("Encoder: {codec}"),
+                format!("'{codec}' is not available from FFmpeg on this machine's hardware"),
+            )
+        })
+        .collect()
+}
+
+/// Check that the ingest port can be bound (unless the running ingest already holds it)
+pub fn check_listen_port(input: &RtmpInput, held_by_ingest: bool) -> PreflightCheck {
+    let address = format!("{}:{}", input.bind_address, input.port);
+    let label = format!("Ingest port {address}");
+    if held_by_ingest {
+        return PreflightCheck::pass("ingest_port", label, "In use by the running ingest");
+    }
+
+    // SRT listens on UDP, RTMP on TCP
+    let bound = if input.is_srt() {
+        UdpSocket::bind(&address).map(drop)
+    } else {
+        TcpListener::bind(&address).map(drop)
+    };
+    match bound {
+        Ok(()) => PreflightCheck::pass("ingest_port", label, "Free"),
+        Err(e) => PreflightCheck::fail("ingest_port", label, format!("Cannot listen: {e}")),
+    }
+}
+
+/// Decrypt each target's stream key on its own, clearing the ones that fail
+///
+/// Used when the profile did not load with all keys decrypted, to name the targets at fault.
+pub fn decrypt_target_keys(profile: &mut Profile, app_data_dir: &Path) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    for group in &mut profile.output_groups {
+        for target in &mut group.stream_targets {
+            if !Encryption::is_stream_key_encrypted(&target.stream_key) {
+                continue;
+            }
+            match Encryption::decrypt_stream_key(&target.stream_key, app_data_dir) {
+                Ok(key) => target.stream_key = key,
+                Err(e) => {
+                    target.stream_key.clear();
+                    checks.push(
+                        PreflightCheck::fail("stream_key", format!("Stream key: {}", target.name), e)
+                            .for_target(&group.id, &target.id),
+                    );
+                }
+            }
+        }
+    }
+    checks
+}
+
+/// Resolve a `${ENV_VAR}` stream key, failing when the variable is not set
+pub fn resolve_env_key(key: &str) -> Result<String, String> {
+    if key.starts_with("${") && key.ends_with('}') && key.len() > 3 {
+        return std::env::var(&key[2..key.len() - 1])
+            .map_err(|_| "Stream key refers to an environment variable that is not set".to_string());
+    }
+    Ok(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PreflightStatus;
+
+    #[test]
+    fn test_reports_missing_encoders() {
+        let available = Encoders {
+            video: vec!["libx264".to_string()],
+            audio: vec!["aac".to_string()],
+        };
+        let passthrough = OutputGroup::new();
+        assert_eq!(check_encoders(std::slice::from_ref(&passthrough), &available)[0].status, PreflightStatus::Pass);
+
+        let mut nvenc = OutputGroup::new();
+        nvenc.video.codec = "h264_nvenc".to_string();
+        nvenc.audio.codec = "aac".to_string();
+        let mut x264 = nvenc.clone();
+        x264.video.codec = "libx264".to_string();
+
+        let checks = check_encoders(&[passthrough, x264.clone(), nvenc], &available);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, PreflightStatus::Fail);
+        assert!(checks[0].label.contains("h264_nvenc"));
+        assert_eq!(check_encoders(&[x264], &available)[0].status, PreflightStatus::Pass);
+    }
+
+    #[test]
+    fn test_detects_busy_ingest_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let input = RtmpInput {
+            bind_address: "127.0.0.1".to_string(),
+            port: listener.local_addr().unwrap().port(),
+            ..RtmpInput::default()
+        };
+        assert_eq!(check_listen_port(&input, false).status, PreflightStatus::Fail);
+        assert_eq!(check_listen_port(&input, true).status, PreflightStatus::Pass);
+        drop(listener);
+        assert_eq!(check_listen_port(&input, false).status, PreflightStatus::Pass);
+    }
+}
@@ -0,0 +1,105 @@
+// Process Priority
+// Niceness and I/O priority for FFmpeg child processes
+
+use std::process::Command;
+use crate::models::{IoPriorityClass, ProcessPriority};
+use crate::services::read_process_usage;
+
+/// `ioprio_set` target type for a single process (linux/ioprio.h)
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+/// Bits the class is shifted by in an I/O priority value
+const IOPRIO_CLASS_SHIFT: i32 = 13;
+
+/// Value for `ioprio_set`, or `None` when no I/O priority is configured
+#[cfg_attr(not(unix), allow(dead_code))]
+fn io_priority_value(priority: &ProcessPriority) -> Option<i32> {
+    let class = match (priority.io_class, priority.io_level) {
+        (None, None) => return None,
+        (Some(class), _) => class,
+        (None, Some(_)) => IoPriorityClass::BestEffort,
+    };
+    let (class, level) = match class {
+        IoPriorityClass::Realtime => (1, priority.io_level.unwrap_or(4)),
+        IoPriorityClass::BestEffort => (2, priority.io_level.unwrap_or(4)),
+        // The idle class has no levels
+        IoPriorityClass::Idle => (3, 0),
+    };
+    Some((class << IOPRIO_CLASS_SHIFT) | i32::from(level.min(7)))
+}
+
+/// Have the spawned process start with `priority`
+///
+/// Set in the child before exec, so every thread FFmpeg creates inherits it.
+/// A value the server may not set (e.g. negative niceness without privileges)
+/// is skipped rather than failing the spawn; [`check_priority`] reports it.
+#[cfg(unix)]
+pub fn apply_priority(cmd: &mut Command, priority: &ProcessPriority) {
+    use std::os::unix::process::CommandExt;
+
+    let nice = priority.nice;
+    // ioprio_set is Linux-only; other Unix systems only get the niceness
+    let io_priority = if cfg!(target_os = "linux") { io_priority_value(priority) } else { None };
+    if nice.is_none() && io_priority.is_none() {
+        return;
+    }
+
+    // SAFETY: the closure only makes async-signal-safe system calls and does not allocate
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(nice) = nice {
+                libc::setpriority(libc::PRIO_PROCESS as _, 0, nice);
+            }
+            #[cfg(target_os = "linux")]
+            if let Some(value) = io_priority {
+                libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, value);
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub fn apply_priority(_cmd: &mut Command, _priority: &ProcessPriority) {}
+
+/// Log when a spawned process did not get the niceness it was configured with
+pub fn check_priority(pid: u32, label: &str, priority: &ProcessPriority) {
+    let (Some(wanted), Some(usage)) = (priority.nice, read_process_usage(pid)) else {
+        return;
+    };
+    if usage.nice != wanted {
+        log::warn!(
+            "FFmpeg {label} runs at niceness {} instead of {wanted} (raising priority needs CAP_SYS_NICE)",
+            usage.nice
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_priority_value() {
+        let priority = |io_class, io_level| ProcessPriority { nice: None, io_class, io_level };
+        assert_eq!(io_priority_value(&priority(None, None)), None);
+        assert_eq!(io_priority_value(&priority(None, Some(7))), Some((2 << 13) | 7));
+        assert_eq!(io_priority_value(&priority(Some(IoPriorityClass::Idle), Some(3))), Some(3 << 13));
+        assert_eq!(io_priority_value(&priority(Some(IoPriorityClass::Realtime), None)), Some((1 << 13) | 4));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_spawned_process_gets_niceness() {
+        let priority = ProcessPriority { nice: Some(19), io_class: Some(IoPriorityClass::Idle), io_level: None };
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        apply_priority(&mut cmd, &priority);
+        let mut child = cmd.spawn().unwrap();
+        let nice = read_process_usage(child.id()).map(|usage| usage.nice);
+        let _ = child.kill();
+        let _ = child.wait();
+        assert_eq!(nice, Some(19));
+    }
+}
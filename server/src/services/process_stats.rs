@@ -0,0 +1,153 @@
+// Process Stats
+// CPU, memory and thread usage of child processes, read from /proc on Linux
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use crate::models::{FFmpegProcess, FFmpegProcessStats};
+
+/// Resource usage of one process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcessUsage {
+    /// User plus system CPU time since the process started
+    pub cpu_seconds: f64,
+    /// Seconds since the process started
+    pub uptime_seconds: f64,
+    /// Resident set size
+    pub rss_bytes: u64,
+    pub threads: u32,
+    pub nice: i32,
+}
+
+/// Fields of `/proc/<pid>/stat` used here, in clock ticks where timed
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, PartialEq)]
+struct ProcStat {
+    cpu_ticks: u64,
+    nice: i32,
+    threads: u32,
+    start_ticks: u64,
+}
+
+/// Read a process's usage, or `None` if it is gone or the platform has no /proc
+#[cfg(target_os = "linux")]
+pub fn read_process_usage(pid: u32) -> Option<ProcessUsage> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let statm = std::fs::read_to_string(format!("/proc/{pid}/statm")).ok()?;
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    // SAFETY: sysconf only reads configuration values
+    let (ticks_per_sec, page_size) = unsafe { (libc::sysconf(libc::_SC_CLK_TCK), libc::sysconf(libc::_SC_PAGESIZE)) };
+    if ticks_per_sec <= 0 || page_size <= 0 {
+        return None;
+    }
+    let ticks_per_sec = ticks_per_sec as f64;
+
+    let stat = parse_proc_stat(&stat)?;
+    let rss_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let system_uptime: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    Some(ProcessUsage {
+        cpu_seconds: stat.cpu_ticks as f64 / ticks_per_sec,
+        uptime_seconds: (system_uptime - stat.start_ticks as f64 / ticks_per_sec).max(0.0),
+        rss_bytes: rss_pages * page_size as u64,
+        threads: stat.threads,
+        nice: stat.nice,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_usage(_pid: u32) -> Option<ProcessUsage> {
+    None
+}
+
+/// Parse the contents of `/proc/<pid>/stat`
+///
+/// The command name in field 2 may contain spaces and parentheses, so fields
+/// are counted from the last `)`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<ProcStat> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    // rest starts at field 3 (state), so field N is at index N - 3
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3).copied();
+    let utime: u64 = field(14)?.parse().ok()?;
+    let stime: u64 = field(15)?.parse().ok()?;
+    Some(ProcStat {
+        cpu_ticks: utime + stime,
+        nice: field(19)?.parse().ok()?,
+        threads: field(20)?.parse().ok()?,
+        start_ticks: field(22)?.parse().ok()?,
+    })
+}
+
+/// Turns CPU time readings into CPU% between calls
+#[derive(Default)]
+pub struct ProcessSampler {
+    /// CPU seconds at the previous sample of each pid
+    previous: Mutex<HashMap<u32, (f64, Instant)>>,
+}
+
+impl ProcessSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Usage of each process; those that exited meanwhile are left out
+    ///
+    /// CPU% covers the time since the previous call (since the process started
+    /// on the first call) and is per core, so a busy encoder can exceed 100.
+    pub fn sample(&self, processes: Vec<FFmpegProcess>) -> Vec<FFmpegProcessStats> {
+        let now = Instant::now();
+        let Ok(mut previous) = self.previous.lock() else {
+            return Vec::new();
+        };
+
+        let stats: Vec<FFmpegProcessStats> = processes
+            .into_iter()
+            .filter_map(|process| {
+                let usage = read_process_usage(process.pid)?;
+                let cpu_percent = match previous.get(&process.pid) {
+                    Some((cpu_seconds, at)) if now > *at => {
+                        (usage.cpu_seconds - cpu_seconds).max(0.0) * 100.0 / (now - *at).as_secs_f64()
+                    }
+                    _ if usage.uptime_seconds > 0.0 => usage.cpu_seconds * 100.0 / usage.uptime_seconds,
+                    _ => 0.0,
+                };
+                previous.insert(process.pid, (usage.cpu_seconds, now));
+                Some(FFmpegProcessStats {
+                    process,
+                    cpu_percent,
+                    cpu_seconds: usage.cpu_seconds,
+                    rss_bytes: usage.rss_bytes,
+                    threads: usage.threads,
+                    nice: usage.nice,
+                })
+            })
+            .collect();
+
+        previous.retain(|pid, _| stats.iter().any(|stat| stat.process.pid == *pid));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "4242 (ffmpeg (x) 1) S 1 4242 4242 0 -1 4194560 5812 0 0 0 1500 250 0 0 20 5 7 0 9000 1 2";
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(ProcStat { cpu_ticks: 1750, nice: 5, threads: 7, start_ticks: 9000 })
+        );
+        assert_eq!(parse_proc_stat("4242 (ffmpeg) S 1"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_reads_own_usage() {
+        let usage = read_process_usage(std::process::id()).unwrap();
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.threads >= 1);
+    }
+}
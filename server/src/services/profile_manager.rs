@@ -284,11 +284,11 @@ impl ProfileManager {
     pub async fn validate_input_conflict(
         &self,
         _profile_id: &str,
-        _input: &RtmpInput,
+        input: &RtmpInput,
     ) -> Result<(), String> {
         // Since only one profile can be active at a time,
         // multiple profiles can use the same port without conflict
-        Ok(())
+        input.validate()
     }
 
     /// Encrypt all stream keys in a profile
@@ -301,6 +301,17 @@ impl ProfileManager {
                 }
             }
         }
+
+        // SRT ingest passphrase is a stream credential as well
+        let passphrase = &profile.input.srt.passphrase;
+        if !passphrase.is_empty() && !Encryption::is_stream_key_encrypted(passphrase) {
+            profile.input.srt.passphrase = Encryption::encrypt_stream_key(passphrase, &self.app_data_dir)?;
+        }
+
+        let ingest_key = &profile.input.ingest_key;
+        if !ingest_key.is_empty() && !Encryption::is_stream_key_encrypted(ingest_key) {
+            profile.input.ingest_key = Encryption::encrypt_stream_key(ingest_key, &self.app_data_dir)?;
+        }
         Ok(())
     }
 
@@ -314,6 +325,15 @@ impl ProfileManager {
                 }
             }
         }
+
+        if Encryption::is_stream_key_encrypted(&profile.input.srt.passphrase) {
+            profile.input.srt.passphrase =
+                Encryption::decrypt_stream_key(&profile.input.srt.passphrase, &self.app_data_dir)?;
+        }
+        if Encryption::is_stream_key_encrypted(&profile.input.ingest_key) {
+            profile.input.ingest_key =
+                Encryption::decrypt_stream_key(&profile.input.ingest_key, &self.app_data_dir)?;
+        }
         Ok(())
     }
 
@@ -443,6 +463,7 @@ impl ProfileManager {
 
         // Validate profile name to prevent path traversal attacks
         validate_profile_name(&profile.name)?;
+        profile.settings.validate()?;
 
         // Clone the profile so we can modify it
         let mut profile_to_save = profile.clone();
@@ -0,0 +1,362 @@
+// Reconnect Supervisor Service
+// Restarts output groups, or single targets, whose FFmpeg process exits unexpectedly
+
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use rand::Rng;
+use tokio::sync::{mpsc, oneshot};
+use crate::models::{FFmpegErrorKind, ReconnectSettings};
+use crate::services::{emit_event, EventSink, FFmpegHandler};
+
+/// A process that stayed up this long is considered stable; its attempt count resets
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Reconnection configuration
+#[derive(Debug, Clone)]
+pub struct ReconnectionConfig {
+    pub enabled: bool,
+    pub max_retries: u32,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub jitter_percent: u8,
+}
+
+impl Default for ReconnectionConfig {
+    fn default() -> Self {
+        Self::from(&ReconnectSettings::default())
+    }
+}
+
+impl From<&ReconnectSettings> for ReconnectionConfig {
+    fn from(settings: &ReconnectSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            max_retries: settings.max_retries,
+            initial_delay_secs: settings.initial_delay_secs,
+            max_delay_secs: settings.max_delay_secs.max(settings.initial_delay_secs),
+            jitter_percent: settings.jitter_percent.min(100),
+        }
+    }
+}
+
+/// Tracks reconnection attempts for a group
+#[derive(Debug, Clone)]
+pub struct ReconnectionState {
+    pub attempt: u32,
+    pub last_attempt: Option<Instant>,
+}
+
+impl ReconnectionState {
+    pub fn new() -> Self {
+        Self {
+            attempt: 0,
+            last_attempt: None,
+        }
+    }
+
+    pub fn increment(&mut self) {
+        self.attempt += 1;
+        self.last_attempt = Some(Instant::now());
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+        self.last_attempt = None;
+    }
+
+    pub fn should_retry(&self, config: &ReconnectionConfig) -> bool {
+        self.attempt < config.max_retries
+    }
+
+    pub fn next_delay(&self, config: &ReconnectionConfig) -> Duration {
+        // Exponential backoff: initial * 2^attempt, capped at max_delay
+        let delay_secs = config.initial_delay_secs.saturating_mul(1 << self.attempt.min(6));
+        Duration::from_secs(delay_secs.min(config.max_delay_secs))
+    }
+
+    /// Backoff delay spread by the configured jitter so groups sharing an
+    /// ingest outage do not all hit the platforms at the same moment
+    pub fn next_delay_with_jitter(&self, config: &ReconnectionConfig) -> Duration {
+        let base = self.next_delay(config);
+        if config.jitter_percent == 0 || base.is_zero() {
+            return base;
+        }
+        let spread = f64::from(config.jitter_percent) / 100.0;
+        let factor = 1.0 + rand::thread_rng().gen_range(-spread..=spread);
+        // Profiles saved before delays were bounded may hold values too large to scale
+        Duration::try_from_secs_f64(base.as_secs_f64() * factor.max(0.0)).unwrap_or(base)
+    }
+}
+
+impl Default for ReconnectionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unexpected FFmpeg exit reported by a group's stats reader or one of its target readers
+#[derive(Debug, Clone)]
+pub struct GroupExit {
+    pub group_id: String,
+    /// Target whose output process exited (None = the group's encoder process)
+    pub target_id: Option<String>,
+    /// Error message shown to the user
+    pub error: String,
+    /// What the failure was classified as
+    pub kind: FFmpegErrorKind,
+    /// How long the process ran before exiting
+    pub uptime: Duration,
+    /// Attempts made so far for this outage
+    pub reconnection_state: ReconnectionState,
+}
+
+impl GroupExit {
+    /// `group` or `group/target`, used in logs and as the reconnect task key
+    pub fn label(&self) -> String {
+        match &self.target_id {
+            Some(target_id) => format!("{}/{target_id}", self.group_id),
+            None => self.group_id.clone(),
+        }
+    }
+}
+
+/// Receive group exits and schedule reconnect attempts until the handler is dropped
+///
+/// Each attempt runs as its own task, registered with the handler so that
+/// stopping the group aborts a pending backoff. A failed target is restarted
+/// on its own, with its own backoff, while the group keeps running.
+pub async fn run_reconnect_supervisor(
+    handler: Weak<FFmpegHandler>,
+    mut exits: mpsc::UnboundedReceiver<GroupExit>,
+    exit_tx: mpsc::UnboundedSender<GroupExit>,
+    event_sink: Arc<dyn EventSink>,
+) {
+    while let Some(exit) = exits.recv().await {
+        let Some(handler_ref) = handler.upgrade() else {
+            break;
+        };
+        schedule_attempt(&handler_ref, &handler, exit, &exit_tx, &event_sink);
+    }
+}
+
+fn schedule_attempt(
+    handler_ref: &FFmpegHandler,
+    handler: &Weak<FFmpegHandler>,
+    exit: GroupExit,
+    exit_tx: &mpsc::UnboundedSender<GroupExit>,
+    event_sink: &Arc<dyn EventSink>,
+) {
+    let group_id = exit.group_id.clone();
+    let target_id = exit.target_id.clone();
+    let label = exit.label();
+    let config = handler_ref.reconnection_config();
+    if !config.enabled || !handler_ref.is_group_active(&group_id) {
+        return;
+    }
+
+    let mut state = exit.reconnection_state;
+    if exit.uptime >= STABLE_UPTIME {
+        state.reset();
+    }
+
+    // Rejected credentials or bad settings fail the same way on every attempt;
+    // the stream_error event (or the target's failed status) already told clients
+    if !exit.kind.retryable() {
+        log::error!("[FFmpeg:{label}] Not reconnecting after {} error: {}", exit.kind.code(), exit.error);
+        return;
+    }
+
+    if !state.should_retry(&config) {
+        log::error!(
+            "[FFmpeg:{label}] Giving up after {} reconnection attempts: {}",
+            state.attempt,
+            exit.error
+        );
+        match &target_id {
+            Some(target_id) => handler_ref.mark_target_gave_up(
+                target_id,
+                format!("Gave up after {} attempts: {}", state.attempt, exit.error),
+                event_sink.as_ref(),
+            ),
+            None => emit_event(
+                event_sink.as_ref(),
+                "stream_gave_up",
+                &serde_json::json!({
+                    "groupId": group_id,
+                    "attempts": state.attempt,
+                    "error": exit.error,
+                    "code": exit.kind,
+                }),
+            ),
+        }
+        return;
+    }
+
+    let delay = state.next_delay_with_jitter(&config);
+    log::info!(
+        "[FFmpeg:{label}] Reconnecting (attempt {}/{}) in {:.1}s",
+        state.attempt + 1,
+        config.max_retries,
+        delay.as_secs_f64()
+    );
+    match &target_id {
+        // Group events describe the whole group, which is still live
+        Some(target_id) => handler_ref.mark_target_retrying(target_id, event_sink.as_ref()),
+        None => {
+            emit_event(
+                event_sink.as_ref(),
+                "stream_reconnecting",
+                &serde_json::json!({
+                    "groupId": group_id,
+                    "attempt": state.attempt + 1,
+                    "maxAttempts": config.max_retries,
+                    "delaySecs": delay.as_secs(),
+                }),
+            );
+            handler_ref.mark_group_retrying(&group_id, event_sink.as_ref());
+        }
+    }
+
+    let handler = handler.clone();
+    let exit_tx = exit_tx.clone();
+    let event_sink = Arc::clone(event_sink);
+    let task_label = label.clone();
+    let task_group_id = group_id.clone();
+    let (start_tx, start_rx) = oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
+        let label = task_label;
+        let group_id = task_group_id;
+        // Wait until the abort handle is registered, so a stop can always find it
+        if start_rx.await.is_err() {
+            return;
+        }
+        tokio::time::sleep(delay).await;
+        state.increment();
+
+        let Some(handler) = handler.upgrade() else {
+            return;
+        };
+        // Past this point the attempt can no longer be aborted; a concurrent
+        // stop is caught by the active-group check inside the restart
+        handler.finish_reconnect_task(&label);
+
+        let restart_handler = Arc::clone(&handler);
+        let restart_group_id = group_id.clone();
+        let restart_target_id = target_id.clone();
+        let restart_state = state.clone();
+        let restart_sink = Arc::clone(&event_sink);
+        let result = tokio::task::spawn_blocking(move || match restart_target_id {
+            Some(target_id) => {
+                restart_handler.restart_failed_target(&restart_group_id, &target_id, restart_state, restart_sink)
+            }
+            None => restart_handler
+                .restart_crashed_group(&restart_group_id, restart_state, restart_sink)
+                .map(Some),
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("Task join error: {e}")));
+
+        match result {
+            Ok(Some(pid)) => {
+                log::info!("[FFmpeg:{label}] Stream reconnected (PID: {pid})");
+                if target_id.is_none() {
+                    emit_event(
+                        event_sink.as_ref(),
+                        "stream_reconnected",
+                        &serde_json::json!({
+                            "groupId": group_id,
+                            "pid": pid,
+                            "attempt": state.attempt,
+                        }),
+                    );
+                }
+            }
+            // The target was removed, or its group is down and brings it back on restart
+            Ok(None) => log::info!("[FFmpeg:{label}] Output no longer needs reconnecting"),
+            Err(error) => {
+                log::warn!("[FFmpeg:{label}] Reconnection attempt {} failed: {error}", state.attempt);
+                // Treat a failed start like another exit so the next attempt is scheduled
+                let _ = exit_tx.send(GroupExit {
+                    group_id,
+                    target_id,
+                    error,
+                    kind: FFmpegErrorKind::Unknown,
+                    uptime: Duration::ZERO,
+                    reconnection_state: state,
+                });
+            }
+        }
+    });
+
+    handler_ref.register_reconnect_task(&label, task.abort_handle());
+    // A stop that ran before registration had nothing to abort
+    if !handler_ref.is_group_active(&group_id) {
+        handler_ref.cancel_reconnect(&group_id);
+        return;
+    }
+    let _ = start_tx.send(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered_within_bounds() {
+        let config = ReconnectionConfig::from(&ReconnectSettings {
+            enabled: true,
+            max_retries: 10,
+            initial_delay_secs: 5,
+            max_delay_secs: 60,
+            jitter_percent: 20,
+        });
+        let mut state = ReconnectionState::new();
+        assert_eq!(state.next_delay(&config), Duration::from_secs(5));
+        for _ in 0..8 {
+            state.increment();
+        }
+        assert_eq!(state.next_delay(&config), Duration::from_secs(60));
+
+        for _ in 0..100 {
+            let delay = state.next_delay_with_jitter(&config);
+            assert!(delay >= Duration::from_secs(48) && delay <= Duration::from_secs(72));
+        }
+    }
+
+    #[test]
+    fn test_backoff_saturates_on_huge_delays() {
+        let config = ReconnectionConfig::from(&ReconnectSettings {
+            enabled: true,
+            max_retries: 10,
+            initial_delay_secs: u64::MAX / 2,
+            max_delay_secs: u64::MAX,
+            jitter_percent: 100,
+        });
+        let mut state = ReconnectionState::new();
+        for _ in 0..6 {
+            state.increment();
+        }
+        assert_eq!(state.next_delay(&config), Duration::from_secs(u64::MAX));
+        state.next_delay_with_jitter(&config);
+
+        assert!(ReconnectSettings { initial_delay_secs: 10, max_delay_secs: 5, ..ReconnectSettings::default() }
+            .validate()
+            .is_err());
+        assert!(ReconnectSettings { max_delay_secs: 86_400, ..ReconnectSettings::default() }.validate().is_err());
+        assert!(ReconnectSettings { jitter_percent: 255, ..ReconnectSettings::default() }.validate().is_err());
+        assert!(ReconnectSettings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_should_retry_respects_profile_limit() {
+        let config = ReconnectionConfig::from(&ReconnectSettings {
+            max_retries: 2,
+            ..ReconnectSettings::default()
+        });
+        let mut state = ReconnectionState::new();
+        assert!(state.should_retry(&config));
+        state.increment();
+        state.increment();
+        assert!(!state.should_retry(&config));
+    }
+}
@@ -0,0 +1,448 @@
+// Recording Service
+// Segmented local recordings written by an output group's target processes
+
+use serde::Serialize;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::models::{OutputGroup, RecordOptions, StreamTarget};
+use crate::services::{emit_event, EventSink};
+
+/// Bitrate assumed for passthrough groups when estimating size-based segments
+const PASSTHROUGH_BITRATE_KBPS: u64 = 6_000;
+
+/// Segment length used when the recording is not split (30 days)
+const UNSPLIT_SEGMENT_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// How often the watcher re-checks free disk space
+const FREE_SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time allowed for FFmpeg to finalize the last file after the group stops
+const FINALIZE_GRACE: Duration = Duration::from_secs(5);
+
+/// Payload of the `recording_file_finished` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingFileEvent {
+    pub group_id: String,
+    pub target_id: String,
+    pub path: String,
+    /// Duration of the file in seconds
+    pub duration_secs: f64,
+    pub size_bytes: u64,
+}
+
+/// Payload of the `recording_low_disk_space` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingDiskSpaceEvent {
+    pub group_id: String,
+    pub target_id: String,
+    pub available_mb: u64,
+    pub min_free_space_mb: u64,
+}
+
+/// Everything needed to record one target: segment muxer options and output pattern
+#[derive(Debug, Clone)]
+pub struct RecordingPlan {
+    pub target_id: String,
+    pub directory: PathBuf,
+    /// CSV list the segment muxer appends to as each file is closed
+    pub segment_list: PathBuf,
+    /// strftime pattern passed as the output filename
+    pub output_pattern: String,
+    /// Segment muxer options (unescaped)
+    pub muxer_options: Vec<(String, String)>,
+    pub min_free_space_mb: u64,
+}
+
+impl RecordingPlan {
+    /// Create the recording directory, check free space and build the segment options
+    pub fn prepare(
+        base_dir: &Path,
+        profile_name: &str,
+        group: &OutputGroup,
+        target: &StreamTarget,
+    ) -> Result<Self, String> {
+        let options = target.record.clone().unwrap_or_default();
+        let directory = base_dir.join(sanitize_component(profile_name));
+        fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create recordings directory: {e}"))?;
+
+        if let Some(available) = available_space_bytes(&directory) {
+            let available_mb = available / (1024 * 1024);
+            if available_mb < options.min_free_space_mb {
+                return Err(format!(
+                    "Not enough disk space to record '{}': {available_mb} MB free, {} MB required",
+                    target.name, options.min_free_space_mb
+                ));
+            }
+        } else {
+            log::warn!("Could not determine free disk space for {}", directory.display());
+        }
+
+        let filename = recording_filename(&options.filename_template, profile_name, &group.name, &target.name);
+        let output_pattern = directory
+            .join(format!("{filename}.{}", options.format.extension()))
+            .to_string_lossy()
+            .to_string();
+
+        let session = chrono::Local::now().format("%Y%m%d%H%M%S");
+        let segment_list = directory.join(format!(".segments-{}-{session}.csv", target.id));
+
+        let mut muxer_options = vec![
+            ("segment_format".to_string(), options.format.muxer().to_string()),
+            ("segment_time".to_string(), segment_seconds(group, &options).to_string()),
+            ("reset_timestamps".to_string(), "1".to_string()),
+            ("strftime".to_string(), "1".to_string()),
+            ("segment_list".to_string(), segment_list.to_string_lossy().to_string()),
+            ("segment_list_type".to_string(), "csv".to_string()),
+        ];
+        if options.format == crate::models::RecordingFormat::Mp4 {
+            // Fragmented MP4 stays playable if FFmpeg is killed mid-file
+            muxer_options.push((
+                "segment_format_options".to_string(),
+                "movflags=+frag_keyframe+empty_moov+default_base_moof".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            target_id: target.id.clone(),
+            directory,
+            segment_list,
+            output_pattern,
+            muxer_options,
+            min_free_space_mb: options.min_free_space_mb,
+        })
+    }
+
+    /// Tail the segment list and emit an event for every finished file
+    ///
+    /// The watcher exits once `is_running` turns false and FFmpeg has had time
+    /// to write the trailer of the last file.
+    pub fn spawn_watcher<F>(&self, group_id: &str, event_sink: Arc<dyn EventSink>, is_running: F)
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        let plan = self.clone();
+        let group_id = group_id.to_string();
+
+        thread::spawn(move || {
+            let mut offset = 0u64;
+            let mut pending = String::new();
+            let mut last_space_check: Option<Instant> = None;
+            let mut low_space_reported = false;
+            let mut stopped_at: Option<Instant> = None;
+
+            loop {
+                plan.read_finished_files(&group_id, &mut offset, &mut pending, event_sink.as_ref());
+
+                if last_space_check.map_or(true, |t| t.elapsed() >= FREE_SPACE_CHECK_INTERVAL) {
+                    last_space_check = Some(Instant::now());
+                    let available_mb = available_space_bytes(&plan.directory)
+                        .map(|bytes| bytes / (1024 * 1024));
+                    match available_mb {
+                        Some(available_mb) if available_mb < plan.min_free_space_mb && !low_space_reported => {
+                            log::warn!(
+                                "[Recording:{group_id}] Low disk space: {available_mb} MB free"
+                            );
+                            emit_event(
+                                event_sink.as_ref(),
+                                "recording_low_disk_space",
+                                &RecordingDiskSpaceEvent {
+                                    group_id: group_id.clone(),
+                                    target_id: plan.target_id.clone(),
+                                    available_mb,
+                                    min_free_space_mb: plan.min_free_space_mb,
+                                },
+                            );
+                            low_space_reported = true;
+                        }
+                        Some(available_mb) if available_mb < plan.min_free_space_mb => {}
+                        Some(_) => low_space_reported = false,
+                        None => {}
+                    }
+                }
+
+                if !is_running() {
+                    let stopped = *stopped_at.get_or_insert_with(Instant::now);
+                    if stopped.elapsed() >= FINALIZE_GRACE {
+                        plan.read_finished_files(&group_id, &mut offset, &mut pending, event_sink.as_ref());
+                        break;
+                    }
+                }
+
+                thread::sleep(Duration::from_millis(500));
+            }
+
+            let _ = fs::remove_file(&plan.segment_list);
+        });
+    }
+
+    /// Read newly appended CSV lines (`filename,start,end`) from the segment list
+    fn read_finished_files(
+        &self,
+        group_id: &str,
+        offset: &mut u64,
+        pending: &mut String,
+        event_sink: &dyn EventSink,
+    ) {
+        let Ok(mut file) = fs::File::open(&self.segment_list) else {
+            return;
+        };
+        if file.seek(SeekFrom::Start(*offset)).is_err() {
+            return;
+        }
+        let mut appended = String::new();
+        let Ok(read) = file.read_to_string(&mut appended) else {
+            return;
+        };
+        *offset += read as u64;
+        pending.push_str(&appended);
+
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            let Some((filename, start, end)) = parse_segment_list_line(line.trim_end()) else {
+                continue;
+            };
+
+            let path = self.directory.join(&filename);
+            let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            log::info!("[Recording:{group_id}] Finished {}", path.display());
+            emit_event(
+                event_sink,
+                "recording_file_finished",
+                &RecordingFileEvent {
+                    group_id: group_id.to_string(),
+                    target_id: self.target_id.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    duration_secs: (end - start).max(0.0),
+                    size_bytes,
+                },
+            );
+        }
+    }
+}
+
+/// Segment length in seconds from the duration and/or size limits
+///
+/// FFmpeg's segment muxer can only split on time, so size limits are converted
+/// using the group's configured bitrate (approximate for passthrough groups).
+fn segment_seconds(group: &OutputGroup, options: &RecordOptions) -> u64 {
+    let by_duration = options.segment_seconds.map(u64::from);
+    let by_size = options.segment_size_mb.map(|size_mb| {
+        let kbps = if group.video.codec.eq_ignore_ascii_case("copy") {
+            PASSTHROUGH_BITRATE_KBPS
+        } else {
+            parse_bitrate_kbps(&group.video.bitrate) + parse_bitrate_kbps(&group.audio.bitrate)
+        };
+        let bits = u64::from(size_mb) * 1024 * 1024 * 8;
+        (bits / (kbps.max(1) * 1000)).max(1)
+    });
+
+    match (by_duration, by_size) {
+        (Some(duration), Some(size)) => duration.min(size),
+        (Some(duration), None) => duration,
+        (None, Some(size)) => size,
+        (None, None) => UNSPLIT_SEGMENT_SECS,
+    }
+}
+
+/// Parse an FFmpeg bitrate string ("6000k", "8M", "160000") into kbps
+pub(crate) fn parse_bitrate_kbps(bitrate: &str) -> u64 {
+    let trimmed = bitrate.trim();
+    let (digits, multiplier) = match trimmed.chars().last() {
+        Some('k') | Some('K') => (&trimmed[..trimmed.len() - 1], 1.0),
+        Some('m') | Some('M') => (&trimmed[..trimmed.len() - 1], 1000.0),
+        _ => (trimmed, 0.001),
+    };
+    digits
+        .parse::<f64>()
+        .map(|value| (value * multiplier) as u64)
+        .unwrap_or(0)
+}
+
+/// Parse one `filename,start,end` CSV entry (filenames may be quoted)
+fn parse_segment_list_line(line: &str) -> Option<(String, f64, f64)> {
+    let (filename, rest) = if let Some(quoted) = line.strip_prefix('"') {
+        let mut filename = String::new();
+        let mut chars = quoted.char_indices();
+        let mut rest_start = None;
+        while let Some((i, c)) = chars.next() {
+            if c == '"' {
+                if quoted[i + 1..].starts_with('"') {
+                    filename.push('"');
+                    chars.next();
+                } else {
+                    rest_start = Some(i + 1);
+                    break;
+                }
+            } else {
+                filename.push(c);
+            }
+        }
+        (filename, quoted[rest_start?..].strip_prefix(',')?)
+    } else {
+        let (filename, rest) = line.split_once(',')?;
+        (filename.to_string(), rest)
+    };
+
+    let (start, end) = rest.split_once(',')?;
+    Some((filename, start.parse().ok()?, end.parse().ok()?))
+}
+
+/// Replace characters that are unsafe in filenames or strftime patterns
+fn sanitize_component(value: &str) -> String {
+    let sanitized = sanitize_text(value.trim());
+    let sanitized = sanitized.trim_matches('.').to_string();
+    if sanitized.is_empty() {
+        "recording".to_string()
+    } else {
+        sanitized
+    }
+}
+
+/// strftime pattern for a recording's file name (without extension)
+///
+/// Everything except `{timestamp}` is cleaned like a path component (`%`
+/// included), so the result stays inside the recordings folder and carries no
+/// strftime codes of its own.
+fn recording_filename(template: &str, profile: &str, group: &str, target: &str) -> String {
+    let filename = template
+        .split("{timestamp}")
+        .map(|part| {
+            let part = part
+                .replace("{profile}", &sanitize_component(profile))
+                .replace("{group}", &sanitize_component(group))
+                .replace("{target}", &sanitize_component(target));
+            sanitize_text(&part)
+        })
+        .collect::<Vec<_>>()
+        .join("%Y%m%d-%H%M%S");
+    filename.trim_start_matches('.').to_string()
+}
+
+/// Replace characters that are unsafe in file names, keeping the length
+fn sanitize_text(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// Free space available to the current user on the filesystem containing `path`
+#[cfg(unix)]
+pub fn available_space_bytes(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs only writes into the provided struct, which is zero-initialized
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    #[allow(clippy::useless_conversion)] // field widths differ between platforms
+    let available = u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize));
+    Some(available)
+}
+
+/// Free space available to the current user on the volume containing `path`
+#[cfg(windows)]
+pub fn available_space_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    // SAFETY: the output pointer refers to a live, zero-initialized ULARGE_INTEGER
+    unsafe {
+        let mut free: winapi::um::winnt::ULARGE_INTEGER = std::mem::zeroed();
+        if winapi::um::fileapi::GetDiskFreeSpaceExW(
+            wide_path.as_ptr(),
+            &mut free,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        ) == 0
+        {
+            return None;
+        }
+        Some(*free.QuadPart())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_segment_list_line() {
+        assert_eq!(
+            parse_segment_list_line("show_20250101-120000.mkv,0.000000,600.032000"),
+            Some(("show_20250101-120000.mkv".to_string(), 0.0, 600.032))
+        );
+        assert_eq!(
+            parse_segment_list_line("\"a,\"\"b\"\".mp4\",1.5,3.5"),
+            Some(("a,\"b\".mp4".to_string(), 1.5, 3.5))
+        );
+        assert_eq!(parse_segment_list_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_size_based_segment_estimate() {
+        let mut group = OutputGroup::new();
+        group.video.codec = "libx264".to_string();
+        group.video.bitrate = "6000k".to_string();
+        group.audio.bitrate = "160k".to_string();
+        let options = RecordOptions {
+            segment_size_mb: Some(1024),
+            ..RecordOptions::default()
+        };
+        // 1 GiB at 6.16 Mbps is roughly 23 minutes
+        assert_eq!(segment_seconds(&group, &options), 1394);
+
+        let options = RecordOptions {
+            segment_seconds: Some(600),
+            segment_size_mb: Some(1024),
+            ..RecordOptions::default()
+        };
+        assert_eq!(segment_seconds(&group, &options), 600);
+    }
+
+    #[test]
+    fn test_sanitize_component() {
+        assert_eq!(sanitize_component("My Show: Part 1/2"), "My_Show__Part_1_2");
+        assert_eq!(sanitize_component("50%"), "50_");
+        assert_eq!(sanitize_component(".."), "recording");
+    }
+
+    #[test]
+    fn test_recording_filename_stays_a_file_name() {
+        assert_eq!(
+            recording_filename("{profile}_{group}_{timestamp}", "Main", "100% Live", "YT"),
+            "Main_100__Live_%Y%m%d-%H%M%S"
+        );
+        assert_eq!(
+            recording_filename("../../../tmp/x-{timestamp}-%s", "p", "g", "t"),
+            "_.._.._tmp_x-%Y%m%d-%H%M%S-_s"
+        );
+
+        let target: StreamTarget = serde_json::from_value(serde_json::json!({
+            "id": "rec",
+            "name": "Recording",
+            "protocol": "record",
+            "url": "",
+            "streamKey": "",
+            "record": { "filenameTemplate": "../../../tmp/x-{timestamp}" },
+        }))
+        .unwrap();
+        assert!(target.validate().unwrap_err().contains("not a path"));
+        for template in ["/tmp/x-{timestamp}", "C:x-{timestamp}", "..{timestamp}", "a\\b-{timestamp}"] {
+            let mut target = target.clone();
+            target.record.as_mut().unwrap().filename_template = template.to_string();
+            assert!(target.validate().is_err(), "{template}");
+        }
+    }
+}
@@ -0,0 +1,289 @@
+// AMF0
+// Encodes and decodes the values carried by RTMP command and data messages
+
+/// An AMF0 value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Amf0Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    /// Anonymous object, properties in wire order
+    Object(Vec<(String, Amf0Value)>),
+    Null,
+    Undefined,
+    /// Associative array (used by `onMetaData`)
+    EcmaArray(Vec<(String, Amf0Value)>),
+    StrictArray(Vec<Amf0Value>),
+    /// Milliseconds since the Unix epoch
+    Date(f64),
+}
+
+impl Amf0Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Property of an object or associative array
+    pub fn get(&self, key: &str) -> Option<&Amf0Value> {
+        match self {
+            Self::Object(properties) | Self::EcmaArray(properties) => properties
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Build an object from `(name, value)` pairs
+    pub fn object<const N: usize>(properties: [(&str, Amf0Value); N]) -> Self {
+        Self::Object(
+            properties
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn string(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+const NUMBER: u8 = 0x00;
+const BOOLEAN: u8 = 0x01;
+const STRING: u8 = 0x02;
+const OBJECT: u8 = 0x03;
+const NULL: u8 = 0x05;
+const UNDEFINED: u8 = 0x06;
+const ECMA_ARRAY: u8 = 0x08;
+const OBJECT_END: u8 = 0x09;
+const STRICT_ARRAY: u8 = 0x0a;
+const DATE: u8 = 0x0b;
+const LONG_STRING: u8 = 0x0c;
+
+/// Nesting depth accepted from the wire
+const MAX_DEPTH: usize = 16;
+
+/// Decode every value in a message body
+pub fn decode_all(data: &[u8]) -> Result<Vec<Amf0Value>, String> {
+    let mut decoder = Decoder { data, pos: 0 };
+    let mut values = Vec::new();
+    while decoder.pos < data.len() {
+        values.push(decoder.value(0)?);
+    }
+    Ok(values)
+}
+
+/// Encode values back to back, as in a command message body
+pub fn encode_all(values: &[Amf0Value]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for value in values {
+        encode(value, &mut out);
+    }
+    out
+}
+
+/// Append one encoded value
+pub fn encode(value: &Amf0Value, out: &mut Vec<u8>) {
+    match value {
+        Amf0Value::Number(number) => {
+            out.push(NUMBER);
+            out.extend_from_slice(&number.to_be_bytes());
+        }
+        Amf0Value::Boolean(flag) => {
+            out.push(BOOLEAN);
+            out.push(u8::from(*flag));
+        }
+        Amf0Value::String(text) => {
+            if text.len() > usize::from(u16::MAX) {
+                out.push(LONG_STRING);
+                out.extend_from_slice(&(text.len() as u32).to_be_bytes());
+                out.extend_from_slice(text.as_bytes());
+            } else {
+                out.push(STRING);
+                encode_utf8(text, out);
+            }
+        }
+        Amf0Value::Object(properties) => {
+            out.push(OBJECT);
+            encode_properties(properties, out);
+        }
+        Amf0Value::Null => out.push(NULL),
+        Amf0Value::Undefined => out.push(UNDEFINED),
+        Amf0Value::EcmaArray(properties) => {
+            out.push(ECMA_ARRAY);
+            out.extend_from_slice(&(properties.len() as u32).to_be_bytes());
+            encode_properties(properties, out);
+        }
+        Amf0Value::StrictArray(items) => {
+            out.push(STRICT_ARRAY);
+            out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+            for item in items {
+                encode(item, out);
+            }
+        }
+        Amf0Value::Date(ms) => {
+            out.push(DATE);
+            out.extend_from_slice(&ms.to_be_bytes());
+            out.extend_from_slice(&0i16.to_be_bytes());
+        }
+    }
+}
+
+fn encode_utf8(text: &str, out: &mut Vec<u8>) {
+    let bytes = &text.as_bytes()[..text.len().min(usize::from(u16::MAX))];
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn encode_properties(properties: &[(String, Amf0Value)], out: &mut Vec<u8>) {
+    for (name, value) in properties {
+        encode_utf8(name, out);
+        encode(value, out);
+    }
+    out.extend_from_slice(&[0, 0, OBJECT_END]);
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len());
+        let Some(end) = end else {
+            return Err("AMF0 value is truncated".to_string());
+        };
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f64(&mut self) -> Result<f64, String> {
+        let bytes = self.take(8)?;
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(bytes);
+        Ok(f64::from_be_bytes(raw))
+    }
+
+    fn utf8(&mut self, len: usize) -> Result<String, String> {
+        let bytes = self.take(len)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Amf0Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("AMF0 value is nested too deeply".to_string());
+        }
+        let marker = self.u8()?;
+        Ok(match marker {
+            NUMBER => Amf0Value::Number(self.f64()?),
+            BOOLEAN => Amf0Value::Boolean(self.u8()? != 0),
+            STRING => {
+                let len = usize::from(self.u16()?);
+                Amf0Value::String(self.utf8(len)?)
+            }
+            LONG_STRING => {
+                let len = self.u32()? as usize;
+                Amf0Value::String(self.utf8(len)?)
+            }
+            OBJECT => Amf0Value::Object(self.properties(depth)?),
+            NULL => Amf0Value::Null,
+            UNDEFINED => Amf0Value::Undefined,
+            ECMA_ARRAY => {
+                // The count is advisory; the end marker terminates the array
+                self.u32()?;
+                Amf0Value::EcmaArray(self.properties(depth)?)
+            }
+            STRICT_ARRAY => {
+                let count = self.u32()? as usize;
+                let mut items = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    items.push(self.value(depth + 1)?);
+                }
+                Amf0Value::StrictArray(items)
+            }
+            DATE => {
+                let ms = self.f64()?;
+                self.u16()?;
+                Amf0Value::Date(ms)
+            }
+            other => return Err(format!("Unsupported AMF0 type 0x{other:02x}")),
+        })
+    }
+
+    fn properties(&mut self, depth: usize) -> Result<Vec<(String, Amf0Value)>, String> {
+        let mut properties = Vec::new();
+        loop {
+            let len = usize::from(self.u16()?);
+            if len == 0 {
+                if self.data.get(self.pos) == Some(&OBJECT_END) {
+                    self.pos += 1;
+                    return Ok(properties);
+                }
+                // Empty property name
+                properties.push((String::new(), self.value(depth + 1)?));
+                continue;
+            }
+            let name = self.utf8(len)?;
+            properties.push((name, self.value(depth + 1)?));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_command_round_trip() {
+        let values = vec![
+            Amf0Value::string("connect"),
+            Amf0Value::Number(1.0),
+            Amf0Value::object([
+                ("app", Amf0Value::string("live")),
+                ("tcUrl", Amf0Value::string("rtmp://localhost:1935/live")),
+                ("fpad", Amf0Value::Boolean(false)),
+            ]),
+            Amf0Value::Null,
+        ];
+        let decoded = decode_all(&encode_all(&values)).unwrap();
+        assert_eq!(decoded, values);
+        assert_eq!(decoded[2].get("app").and_then(Amf0Value::as_str), Some("live"));
+    }
+
+    #[test]
+    fn test_rejects_truncated_values() {
+        let mut encoded = encode_all(&[Amf0Value::EcmaArray(vec![
+            ("width".to_string(), Amf0Value::Number(1920.0)),
+        ])]);
+        encoded.truncate(encoded.len() - 2);
+        assert!(decode_all(&encoded).is_err());
+        assert!(decode_all(&[0x11]).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+// RTMP Chunk Stream
+// Splits messages into chunks and reassembles them on the receiving side
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+/// Chunk size both sides start with
+pub const DEFAULT_CHUNK_SIZE: usize = 128;
+
+/// Largest chunk size a peer may set (the protocol's 31-bit limit is far above anything sane)
+pub const MAX_CHUNK_SIZE: usize = 1 << 24;
+
+/// Timestamp value announcing a 32-bit extended timestamp field
+const EXTENDED_TIMESTAMP: u32 = 0xFF_FFFF;
+
+/// Message type ids
+pub const MSG_SET_CHUNK_SIZE: u8 = 1;
+pub const MSG_ABORT: u8 = 2;
+pub const MSG_ACKNOWLEDGEMENT: u8 = 3;
+pub const MSG_USER_CONTROL: u8 = 4;
+pub const MSG_WINDOW_ACK_SIZE: u8 = 5;
+pub const MSG_SET_PEER_BANDWIDTH: u8 = 6;
+pub const MSG_AUDIO: u8 = 8;
+pub const MSG_VIDEO: u8 = 9;
+pub const MSG_DATA_AMF0: u8 = 18;
+pub const MSG_COMMAND_AMF0: u8 = 20;
+
+/// Chunk stream ids used for messages we send
+pub const CSID_CONTROL: u32 = 2;
+pub const CSID_COMMAND: u32 = 3;
+
+/// A complete RTMP message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtmpMessage {
+    pub type_id: u8,
+    pub stream_id: u32,
+    pub timestamp: u32,
+    pub payload: Vec<u8>,
+}
+
+impl RtmpMessage {
+    pub fn new(type_id: u8, stream_id: u32, timestamp: u32, payload: Vec<u8>) -> Self {
+        Self { type_id, stream_id, timestamp, payload }
+    }
+
+    /// Protocol control message carrying one 32-bit value
+    pub fn control(type_id: u8, value: u32) -> Self {
+        Self::new(type_id, 0, 0, value.to_be_bytes().to_vec())
+    }
+}
+
+/// Header state of one incoming chunk stream
+#[derive(Default)]
+struct ChunkStreamState {
+    timestamp: u32,
+    delta: u32,
+    length: usize,
+    type_id: u8,
+    stream_id: u32,
+    /// The last header used an extended timestamp, so type 3 chunks repeat it
+    extended: bool,
+    payload: Vec<u8>,
+}
+
+/// Reassembles messages from the incoming chunk stream
+pub struct ChunkReader {
+    chunk_size: usize,
+    streams: HashMap<u32, ChunkStreamState>,
+    bytes_read: u64,
+}
+
+impl Default for ChunkReader {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            streams: HashMap::new(),
+            bytes_read: 0,
+        }
+    }
+}
+
+impl ChunkReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the peer's Set Chunk Size
+    pub fn set_chunk_size(&mut self, size: usize) {
+        self.chunk_size = size.clamp(1, MAX_CHUNK_SIZE);
+    }
+
+    /// Drop a partly received message (Abort message)
+    pub fn abort(&mut self, csid: u32) {
+        if let Some(state) = self.streams.get_mut(&csid) {
+            state.payload.clear();
+        }
+    }
+
+    /// Bytes consumed so far, for acknowledgements
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Read chunks until a message is complete
+    pub fn read_message<R: Read>(&mut self, source: &mut R) -> io::Result<RtmpMessage> {
+        loop {
+            if let Some(message) = self.read_chunk(source)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    fn read_chunk<R: Read>(&mut self, source: &mut R) -> io::Result<Option<RtmpMessage>> {
+        let first = self.read_u8(source)?;
+        let fmt = first >> 6;
+        let csid = match first & 0x3f {
+            0 => 64 + u32::from(self.read_u8(source)?),
+            1 => {
+                let low = self.read_u8(source)?;
+                let high = self.read_u8(source)?;
+                64 + u32::from(low) + (u32::from(high) << 8)
+            }
+            csid => u32::from(csid),
+        };
+
+        if fmt != 0 && !self.streams.contains_key(&csid) {
+            return Err(invalid(format!("Chunk stream {csid} continues before its first header")));
+        }
+
+        let mut header = [0u8; 11];
+        let header_len = match fmt {
+            0 => 11,
+            1 => 7,
+            2 => 3,
+            _ => 0,
+        };
+        self.read_exact(source, &mut header[..header_len])?;
+
+        let state = self.streams.entry(csid).or_default();
+        let starts_message = state.payload.is_empty();
+        let mut field = 0;
+        if fmt <= 2 {
+            field = u32::from_be_bytes([0, header[0], header[1], header[2]]);
+            state.extended = field == EXTENDED_TIMESTAMP;
+        }
+        if fmt <= 1 {
+            state.length = u32::from_be_bytes([0, header[3], header[4], header[5]]) as usize;
+            state.type_id = header[6];
+        }
+        if fmt == 0 {
+            state.stream_id = u32::from_le_bytes([header[7], header[8], header[9], header[10]]);
+        }
+        let extended = state.extended;
+
+        if extended {
+            let mut bytes = [0u8; 4];
+            self.read_exact(source, &mut bytes)?;
+            // On type 3 chunks the field only repeats the message's timestamp
+            if fmt <= 2 {
+                field = u32::from_be_bytes(bytes);
+            }
+        }
+
+        let chunk_size = self.chunk_size;
+        let state = self.streams.get_mut(&csid).expect("chunk stream state exists");
+        if starts_message {
+            match fmt {
+                0 => state.timestamp = field,
+                1 | 2 => {
+                    state.delta = field;
+                    state.timestamp = state.timestamp.wrapping_add(field);
+                }
+                _ => state.timestamp = state.timestamp.wrapping_add(state.delta),
+            }
+        }
+
+        let remaining = state.length.saturating_sub(state.payload.len());
+        let len = remaining.min(chunk_size);
+        let start = state.payload.len();
+        state.payload.resize(start + len, 0);
+        let mut payload = std::mem::take(&mut state.payload);
+        let read = self.read_exact(source, &mut payload[start..]);
+        let state = self.streams.get_mut(&csid).expect("chunk stream state exists");
+        state.payload = payload;
+        read?;
+
+        if state.payload.len() < state.length {
+            return Ok(None);
+        }
+        Ok(Some(RtmpMessage {
+            type_id: state.type_id,
+            stream_id: state.stream_id,
+            timestamp: state.timestamp,
+            payload: std::mem::take(&mut state.payload),
+        }))
+    }
+
+    fn read_u8<R: Read>(&mut self, source: &mut R) -> io::Result<u8> {
+        let mut byte = [0u8; 1];
+        self.read_exact(source, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_exact<R: Read>(&mut self, source: &mut R, buf: &mut [u8]) -> io::Result<()> {
+        source.read_exact(buf)?;
+        self.bytes_read += buf.len() as u64;
+        Ok(())
+    }
+}
+
+/// Splits outgoing messages into chunks
+pub struct ChunkWriter {
+    chunk_size: usize,
+}
+
+impl Default for ChunkWriter {
+    fn default() -> Self {
+        Self { chunk_size: DEFAULT_CHUNK_SIZE }
+    }
+}
+
+impl ChunkWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a new outgoing chunk size (after sending Set Chunk Size)
+    pub fn set_chunk_size(&mut self, size: usize) {
+        self.chunk_size = size.clamp(1, MAX_CHUNK_SIZE);
+    }
+
+    /// Write a message: a full header, then type 3 continuation chunks
+    pub fn write_message<W: Write>(&self, sink: &mut W, csid: u32, message: &RtmpMessage) -> io::Result<()> {
+        let extended = message.timestamp >= EXTENDED_TIMESTAMP;
+        let timestamp_field = message.timestamp.min(EXTENDED_TIMESTAMP);
+        let length = message.payload.len() as u32;
+
+        let mut out = Vec::with_capacity(message.payload.len() + 16);
+        write_basic_header(&mut out, 0, csid);
+        out.extend_from_slice(&timestamp_field.to_be_bytes()[1..]);
+        out.extend_from_slice(&length.to_be_bytes()[1..]);
+        out.push(message.type_id);
+        out.extend_from_slice(&message.stream_id.to_le_bytes());
+        if extended {
+            out.extend_from_slice(&message.timestamp.to_be_bytes());
+        }
+
+        let mut chunks = message.payload.chunks(self.chunk_size);
+        if let Some(first) = chunks.next() {
+            out.extend_from_slice(first);
+        }
+        for chunk in chunks {
+            write_basic_header(&mut out, 3, csid);
+            if extended {
+                out.extend_from_slice(&message.timestamp.to_be_bytes());
+            }
+            out.extend_from_slice(chunk);
+        }
+        sink.write_all(&out)
+    }
+}
+
+fn write_basic_header(out: &mut Vec<u8>, fmt: u8, csid: u32) {
+    match csid {
+        2..=63 => out.push((fmt << 6) | csid as u8),
+        64..=319 => {
+            out.push(fmt << 6);
+            out.push((csid - 64) as u8);
+        }
+        _ => {
+            let id = csid.saturating_sub(64).min(0xFFFF);
+            out.push((fmt << 6) | 1);
+            out.push((id & 0xff) as u8);
+            out.push((id >> 8) as u8);
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_round_trips_across_chunk_sizes() {
+        let video = RtmpMessage::new(MSG_VIDEO, 1, 40, (0..1000).map(|i| i as u8).collect());
+        let late = RtmpMessage::new(MSG_AUDIO, 1, 0x0100_0000, vec![0xaf; 300]);
+
+        let mut writer = ChunkWriter::new();
+        let mut wire = Vec::new();
+        writer.write_message(&mut wire, 6, &video).unwrap();
+        writer.set_chunk_size(4096);
+        writer.write_message(&mut wire, 320, &late).unwrap();
+
+        let mut reader = ChunkReader::new();
+        let mut source = Cursor::new(wire.clone());
+        assert_eq!(reader.read_message(&mut source).unwrap(), video);
+        reader.set_chunk_size(4096);
+        assert_eq!(reader.read_message(&mut source).unwrap(), late);
+        assert_eq!(reader.bytes_read(), wire.len() as u64);
+    }
+
+    #[test]
+    fn test_applies_timestamp_deltas() {
+        // Type 0 header at 1000, then a type 2 chunk with a 33 ms delta and a
+        // type 3 chunk reusing it
+        let mut wire = vec![0x04, 0x00, 0x03, 0xe8, 0x00, 0x00, 0x02, MSG_VIDEO, 1, 0, 0, 0, 0x27, 1];
+        wire.extend_from_slice(&[0x84, 0x00, 0x00, 0x21, 0x27, 2]);
+        wire.extend_from_slice(&[0xc4, 0x27, 3]);
+
+        let mut reader = ChunkReader::new();
+        let mut source = Cursor::new(wire);
+        let timestamps: Vec<u32> = (0..3)
+            .map(|_| reader.read_message(&mut source).unwrap().timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![1000, 1033, 1066]);
+        assert!(reader.read_message(&mut source).is_err());
+    }
+}
@@ -0,0 +1,517 @@
+// RTMP Publish Probe
+// Checks that an RTMP(S) destination accepts a stream key without going live
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use super::amf::{self, Amf0Value};
+use super::chunk::*;
+use super::handshake::client_handshake;
+
+/// Limit for each stage, including every socket read and write within it
+const STAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Chunk stream for commands on the publish stream (as FFmpeg and OBS use)
+const CSID_STREAM: u32 = 8;
+
+/// Transaction IDs of the probe's commands
+const TX_CONNECT: f64 = 1.0;
+const TX_RELEASE_STREAM: f64 = 2.0;
+const TX_FC_PUBLISH: f64 = 3.0;
+const TX_CREATE_STREAM: f64 = 4.0;
+
+/// Step of the publish probe, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RtmpProbeStage {
+    Dns,
+    Tcp,
+    Tls,
+    Handshake,
+    Connect,
+    Key,
+    Publish,
+}
+
+/// Outcome of one probe stage
+#[derive(Debug, Clone, Serialize)]
+pub struct RtmpStageResult {
+    pub stage: RtmpProbeStage,
+    pub success: bool,
+    pub message: String,
+    /// Time the stage took in milliseconds
+    pub latency_ms: u64,
+}
+
+/// Probe an RTMP(S) destination without going live
+///
+/// Resolves the host, connects (with TLS for `rtmps://`), performs the handshake,
+/// sends `connect`, then announces the stream key with `releaseStream` and
+/// `FCPublish`, whose answers are the key check. Servers that only check keys
+/// on `publish` simply acknowledge them, so a passing key stage is not proof.
+///
+/// `publish` additionally sends `createStream` and `publish` and waits for
+/// `NetStream.Publish.Start`. No media is sent, but platforms whose persistent
+/// keys start a broadcast on publish (YouTube, Facebook) go live, and a session
+/// already live on the key may be disrupted, so only do this when the user asked.
+///
+/// Stages stop at the first failure, and each is limited to a few seconds.
+/// Returns an error only if the URL itself is invalid.
+pub async fn probe_rtmp_publish(url: &str, stream_key: &str, publish: bool) -> Result<Vec<RtmpStageResult>, String> {
+    let target = ProbeTarget::parse(url)?;
+    let mut stages = Stages::default();
+
+    let started = Instant::now();
+    let host = target.host.clone();
+    let lookup = tokio::time::timeout(STAGE_TIMEOUT, tokio::net::lookup_host((host.as_str(), target.port))).await;
+    let resolved = match lookup {
+        Ok(Ok(mut addrs)) => addrs.next().ok_or_else(|| format!("No address found for {}", target.host)),
+        Ok(Err(e)) => Err(format!("Cannot resolve {} - {e}", target.host)),
+        Err(_) => Err(format!("Resolving {} timed out", target.host)),
+    };
+    let resolved = resolved.map(|addr| (addr, format!("Resolved {} to {}", target.host, addr.ip())));
+    let Some(addr) = stages.record(RtmpProbeStage::Dns, started, resolved) else {
+        return Ok(stages.0);
+    };
+
+    let stream_key = stream_key.to_string();
+    let rest = tokio::task::spawn_blocking(move || {
+        let mut stages = Stages::default();
+        probe_connection(&mut stages, addr, &target, &stream_key, publish);
+        stages.0
+    })
+    .await
+    .map_err(|e| format!("RTMP probe failed: {e}"))?;
+    stages.0.extend(rest);
+    Ok(stages.0)
+}
+
+/// TCP, TLS, handshake, connect, key and (optionally) publish stages over one connection
+fn probe_connection(
+    stages: &mut Stages,
+    addr: SocketAddr,
+    target: &ProbeTarget,
+    stream_key: &str,
+    publish: bool,
+) -> Option<()> {
+    let tcp = stages.run(RtmpProbeStage::Tcp, || {
+        let stream = TcpStream::connect_timeout(&addr, STAGE_TIMEOUT).map_err(|e| describe_io(&e))?;
+        stream
+            .set_read_timeout(Some(STAGE_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(STAGE_TIMEOUT)))
+            .and_then(|_| stream.set_nodelay(true))
+            .map_err(|e| describe_io(&e))?;
+        Ok((stream, format!("Connected to {addr}")))
+    })?;
+
+    let io: Box<dyn Transport> = if target.tls {
+        stages.run(RtmpProbeStage::Tls, || {
+            let connector = native_tls::TlsConnector::new().map_err(|e| format!("TLS unavailable: {e}"))?;
+            let stream = connector
+                .connect(&target.host, tcp)
+                .map_err(|e| format!("TLS handshake failed: {e}"))?;
+            Ok((Box::new(stream) as Box<dyn Transport>, "TLS session established".to_string()))
+        })?
+    } else {
+        Box::new(tcp)
+    };
+
+    let mut session = ProbeSession::new(io);
+    stages.run(RtmpProbeStage::Handshake, || {
+        client_handshake(&mut session.io).map_err(|e| describe_io(&e))?;
+        Ok(((), "RTMP handshake completed".to_string()))
+    })?;
+    stages.run(RtmpProbeStage::Connect, || session.connect(target))?;
+    let mut accepted = stages.run(RtmpProbeStage::Key, || session.check_key(stream_key));
+    if publish && accepted.is_some() {
+        accepted = stages.run(RtmpProbeStage::Publish, || session.publish(stream_key));
+    }
+    session.close(stream_key);
+    accepted
+}
+
+/// Stage results collected in order
+#[derive(Default)]
+struct Stages(Vec<RtmpStageResult>);
+
+impl Stages {
+    fn run<T>(&mut self, stage: RtmpProbeStage, f: impl FnOnce() -> Result<(T, String), String>) -> Option<T> {
+        let started = Instant::now();
+        let result = f();
+        self.record(stage, started, result)
+    }
+
+    fn record<T>(&mut self, stage: RtmpProbeStage, started: Instant, result: Result<(T, String), String>) -> Option<T> {
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let (value, success, message) = match result {
+            Ok((value, message)) => (Some(value), true, message),
+            Err(message) => (None, false, message),
+        };
+        self.0.push(RtmpStageResult { stage, success, message, latency_ms });
+        value
+    }
+}
+
+/// Where to connect and what to send in `connect`
+struct ProbeTarget {
+    host: String,
+    port: u16,
+    tls: bool,
+    app: String,
+    tc_url: String,
+}
+
+impl ProbeTarget {
+    fn parse(url: &str) -> Result<Self, String> {
+        let url = url.trim().trim_end_matches('/');
+        let parsed = url::Url::parse(url).map_err(|e| format!("Invalid RTMP URL: {e}"))?;
+        let (default_port, tls) = match parsed.scheme() {
+            "rtmp" => (1935, false),
+            "rtmps" => (443, true),
+            _ => return Err("Invalid RTMP URL: must start with rtmp:// or rtmps://".to_string()),
+        };
+        let host = parsed
+            .host_str()
+            .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| "Empty host in RTMP URL".to_string())?
+            .to_string();
+
+        // Query parameters belong to the application, as FFmpeg sends them
+        let mut app = parsed.path().trim_matches('/').to_string();
+        if let Some(query) = parsed.query() {
+            app = format!("{app}?{query}");
+        }
+
+        Ok(Self {
+            host,
+            port: parsed.port().unwrap_or(default_port),
+            tls,
+            app,
+            tc_url: url.to_string(),
+        })
+    }
+}
+
+/// Plain TCP or TLS connection
+trait Transport: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Transport for T {}
+
+/// Command exchange with the server after the handshake
+struct ProbeSession {
+    io: Box<dyn Transport>,
+    reader: ChunkReader,
+    writer: ChunkWriter,
+    /// The key was announced with `FCPublish`
+    announced: bool,
+    stream_id: Option<u32>,
+}
+
+impl ProbeSession {
+    fn new(io: Box<dyn Transport>) -> Self {
+        Self {
+            io,
+            reader: ChunkReader::new(),
+            writer: ChunkWriter::new(),
+            announced: false,
+            stream_id: None,
+        }
+    }
+
+    fn connect(&mut self, target: &ProbeTarget) -> Result<((), String), String> {
+        self.send_command(CSID_COMMAND, 0, vec![
+            Amf0Value::string("connect"),
+            Amf0Value::Number(TX_CONNECT),
+            Amf0Value::object([
+                ("app", Amf0Value::string(&target.app)),
+                ("type", Amf0Value::string("nonprivate")),
+                ("flashVer", Amf0Value::string("FMLE/3.0 (compatible; SpiritStream)")),
+                ("tcUrl", Amf0Value::string(&target.tc_url)),
+            ]),
+        ])?;
+
+        let reply = self.wait_for(|values| is_reply(values, TX_CONNECT))?;
+        if reply[0].as_str() == Some("_error") {
+            return Err(format!("Connection rejected - {}", describe_status(reply.get(3))));
+        }
+        Ok(((), format!("Connected to application '{}'", target.app)))
+    }
+
+    /// Announce the key with `releaseStream`/`FCPublish`; neither starts a broadcast
+    fn check_key(&mut self, stream_key: &str) -> Result<((), String), String> {
+        for (name, transaction) in [("releaseStream", TX_RELEASE_STREAM), ("FCPublish", TX_FC_PUBLISH)] {
+            self.send_command(CSID_COMMAND, 0, vec![
+                Amf0Value::string(name),
+                Amf0Value::Number(transaction),
+                Amf0Value::Null,
+                Amf0Value::string(stream_key),
+            ])?;
+        }
+        self.announced = true;
+
+        let mut acknowledged = 0;
+        loop {
+            let reply = self.wait_for(|values| {
+                is_reply(values, TX_RELEASE_STREAM)
+                    || is_reply(values, TX_FC_PUBLISH)
+                    || values.first().and_then(Amf0Value::as_str) == Some("onFCPublish")
+            });
+            let reply = match reply {
+                Ok(reply) => reply,
+                // Many servers ignore both commands and only check the key on publish
+                Err(e) if e == no_answer() => {
+                    let message = if acknowledged > 0 {
+                        "Stream key acknowledged - the server checks it only when going live"
+                    } else {
+                        "Connected - the server checks stream keys only when going live"
+                    };
+                    return Ok(((), message.to_string()));
+                }
+                Err(e) => return Err(describe_key_error(e)),
+            };
+
+            match reply[0].as_str() {
+                Some("_error") => return Err(format!("Stream key rejected - {}", describe_status(reply.get(3)))),
+                Some("onFCPublish") => {
+                    let info = reply.get(3);
+                    return match info.and_then(|info| info.get("code")).and_then(Amf0Value::as_str) {
+                        Some("NetStream.Publish.Start") => Ok(((), "Stream key accepted - nothing was published".to_string())),
+                        _ => Err(format!("Stream key rejected - {}", describe_status(info))),
+                    };
+                }
+                _ => {
+                    acknowledged += 1;
+                    if acknowledged == 2 {
+                        return Ok(((), "Stream key acknowledged - some platforms check it only when going live".to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn publish(&mut self, stream_key: &str) -> Result<((), String), String> {
+        self.send_command(CSID_COMMAND, 0, vec![
+            Amf0Value::string("createStream"),
+            Amf0Value::Number(TX_CREATE_STREAM),
+            Amf0Value::Null,
+        ])?;
+
+        let reply = self.wait_for(|values| is_reply(values, TX_CREATE_STREAM))?;
+        let stream_id = match (reply[0].as_str(), reply.get(3).and_then(Amf0Value::as_number)) {
+            (Some("_result"), Some(id)) => id as u32,
+            _ => return Err(format!("Server refused to create a stream - {}", describe_status(reply.get(3)))),
+        };
+        self.stream_id = Some(stream_id);
+
+        self.send_command(CSID_STREAM, stream_id, vec![
+            Amf0Value::string("publish"),
+            Amf0Value::Number(0.0),
+            Amf0Value::Null,
+            Amf0Value::string(stream_key),
+            Amf0Value::string("live"),
+        ])?;
+
+        let status = self
+            .wait_for(|values| {
+                values.first().and_then(Amf0Value::as_str) == Some("onStatus")
+                    && values
+                        .get(3)
+                        .and_then(|info| info.get("code"))
+                        .and_then(Amf0Value::as_str)
+                        .is_some_and(|code| code.starts_with("NetStream.Publish."))
+            })
+            .map_err(describe_key_error)?;
+        let info = status.get(3);
+        match info.and_then(|info| info.get("code")).and_then(Amf0Value::as_str) {
+            Some("NetStream.Publish.Start") => Ok(((), "Stream key accepted - no media was sent".to_string())),
+            Some("NetStream.Publish.BadName") => Err(format!("Stream key rejected - {}", describe_status(info))),
+            _ => Err(format!("Publish refused - {}", describe_status(info))),
+        }
+    }
+
+    /// Undo the key announcement and publish before disconnecting (best effort)
+    fn close(&mut self, stream_key: &str) {
+        if self.announced {
+            let _ = self.send_command(CSID_COMMAND, 0, vec![
+                Amf0Value::string("FCUnpublish"),
+                Amf0Value::Number(0.0),
+                Amf0Value::Null,
+                Amf0Value::string(stream_key),
+            ]);
+        }
+        let Some(stream_id) = self.stream_id else {
+            return;
+        };
+        let _ = self.send_command(CSID_COMMAND, 0, vec![
+            Amf0Value::string("deleteStream"),
+            Amf0Value::Number(0.0),
+            Amf0Value::Null,
+            Amf0Value::Number(f64::from(stream_id)),
+        ]);
+    }
+
+    /// Read messages until a command matches, handling the control messages on the way
+    fn wait_for(&mut self, matches: impl Fn(&[Amf0Value]) -> bool) -> Result<Vec<Amf0Value>, String> {
+        let deadline = Instant::now() + STAGE_TIMEOUT;
+        while Instant::now() < deadline {
+            let message = self.reader.read_message(&mut self.io).map_err(|e| describe_io(&e))?;
+            match message.type_id {
+                MSG_SET_CHUNK_SIZE => {
+                    let size = read_u32(&message.payload) & 0x7fff_ffff;
+                    self.reader.set_chunk_size(size as usize);
+                }
+                MSG_ABORT => self.reader.abort(read_u32(&message.payload)),
+                MSG_COMMAND_AMF0 => {
+                    let values = amf::decode_all(&message.payload)?;
+                    if matches(&values) {
+                        return Ok(values);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(no_answer())
+    }
+
+    fn send_command(&mut self, csid: u32, stream_id: u32, values: Vec<Amf0Value>) -> Result<(), String> {
+        let message = RtmpMessage::new(MSG_COMMAND_AMF0, stream_id, 0, amf::encode_all(&values));
+        self.writer
+            .write_message(&mut self.io, csid, &message)
+            .and_then(|_| self.io.flush())
+            .map_err(|e| describe_io(&e))
+    }
+}
+
+/// `_result` or `_error` for one of our transactions
+fn is_reply(values: &[Amf0Value], transaction: f64) -> bool {
+    matches!(values.first().and_then(Amf0Value::as_str), Some("_result" | "_error"))
+        && values.get(1).and_then(Amf0Value::as_number) == Some(transaction)
+}
+
+/// "code: description" from a status object
+fn describe_status(info: Option<&Amf0Value>) -> String {
+    let field = |name| info.and_then(|info| info.get(name)).and_then(Amf0Value::as_str);
+    match (field("code"), field("description")) {
+        (Some(code), Some(description)) if !description.is_empty() => format!("{code}: {description}"),
+        (Some(code), _) => code.to_string(),
+        (None, Some(description)) => description.to_string(),
+        (None, None) => "no reason given".to_string(),
+    }
+}
+
+fn no_answer() -> String {
+    format!("No answer within {}s", STAGE_TIMEOUT.as_secs())
+}
+
+/// Several platforms drop the connection instead of answering a bad key
+fn describe_key_error(err: String) -> String {
+    match err.as_str() {
+        "Server closed the connection" => "Server closed the connection - stream key likely rejected".to_string(),
+        _ => err,
+    }
+}
+
+fn describe_io(err: &io::Error) -> String {
+    match err.kind() {
+        ErrorKind::TimedOut | ErrorKind::WouldBlock => no_answer(),
+        ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => {
+            "Server closed the connection".to_string()
+        }
+        ErrorKind::ConnectionRefused => "Connection refused - server not accepting connections".to_string(),
+        _ => err.to_string(),
+    }
+}
+
+fn read_u32(payload: &[u8]) -> u32 {
+    match payload {
+        [a, b, c, d, ..] => u32::from_be_bytes([*a, *b, *c, *d]),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::services::rtmp::{PublishHandler, PublishRequest, RtmpServer};
+    use crate::services::FlvTag;
+
+    /// Accepts the key "good" and counts publishes
+    #[derive(Default)]
+    struct KeyCheck {
+        publishes: AtomicUsize,
+    }
+
+    impl PublishHandler for KeyCheck {
+        fn on_publish(&self, request: &PublishRequest) -> Result<(), String> {
+            self.publishes.fetch_add(1, Ordering::SeqCst);
+            if request.stream_key == "good" {
+                Ok(())
+            } else {
+                Err("Invalid stream key".to_string())
+            }
+        }
+
+        fn on_tag(&self, _tag: FlvTag) {
+            panic!("the probe must not send media");
+        }
+
+        fn on_unpublish(&self, _request: &PublishRequest) {}
+    }
+
+    fn stages(results: &[RtmpStageResult]) -> Vec<(RtmpProbeStage, bool)> {
+        results.iter().map(|result| (result.stage, result.success)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_probe_stops_before_publishing() {
+        let handler = Arc::new(KeyCheck::default());
+        let server = RtmpServer::bind("127.0.0.1:0", "live", handler.clone()).unwrap();
+        let url = format!("rtmp://{}/live", server.local_addr());
+
+        let checked = probe_rtmp_publish(&url, "good", false).await.unwrap();
+        assert_eq!(stages(&checked), vec![
+            (RtmpProbeStage::Dns, true),
+            (RtmpProbeStage::Tcp, true),
+            (RtmpProbeStage::Handshake, true),
+            (RtmpProbeStage::Connect, true),
+            (RtmpProbeStage::Key, true),
+        ]);
+        assert_eq!(handler.publishes.load(Ordering::SeqCst), 0);
+
+        let wrong_app = probe_rtmp_publish(&format!("rtmp://{}/other", server.local_addr()), "good", false)
+            .await
+            .unwrap();
+        assert_eq!(wrong_app.last().map(|r| (r.stage, r.success)), Some((RtmpProbeStage::Connect, false)));
+    }
+
+    #[tokio::test]
+    async fn test_opt_in_publish_reports_rejected_key() {
+        let server = RtmpServer::bind("127.0.0.1:0", "live", Arc::new(KeyCheck::default())).unwrap();
+        let url = format!("rtmp://{}/live", server.local_addr());
+
+        let accepted = probe_rtmp_publish(&url, "good", true).await.unwrap();
+        assert_eq!(accepted.last().map(|r| (r.stage, r.success)), Some((RtmpProbeStage::Publish, true)));
+
+        let rejected = probe_rtmp_publish(&url, "bad", true).await.unwrap();
+        let publish = rejected.last().unwrap();
+        assert_eq!((publish.stage, publish.success), (RtmpProbeStage::Publish, false));
+        assert!(publish.message.contains("Stream key rejected"), "{}", publish.message);
+    }
+
+    #[test]
+    fn test_parses_targets() {
+        let target = ProbeTarget::parse("rtmps://live-api-s.facebook.com:443/rtmp/").unwrap();
+        assert_eq!((target.host.as_str(), target.port, target.tls), ("live-api-s.facebook.com", 443, true));
+        assert_eq!(target.app, "rtmp");
+        assert_eq!(target.tc_url, "rtmps://live-api-s.facebook.com:443/rtmp");
+
+        let target = ProbeTarget::parse("rtmp://live.twitch.tv/app?bandwidthtest=true").unwrap();
+        assert_eq!((target.port, target.app.as_str()), (1935, "app?bandwidthtest=true"));
+
+        assert!(ProbeTarget::parse("srt://example.com:9000").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+// RTMP Handshake
+// Exchanges the C0/C1/C2 and S0/S1/S2 packets that open an RTMP connection
+
+use std::io::{self, Read, Write};
+use rand::RngCore;
+
+/// Size of the C1/S1/C2/S2 packets
+pub const HANDSHAKE_SIZE: usize = 1536;
+
+/// Plain RTMP (encrypted RTMPE variants are not supported)
+pub const RTMP_VERSION: u8 = 3;
+
+/// Answer a client's handshake
+///
+/// This is the simple handshake: S1 carries zeros where the Flash digest
+/// scheme would put its version, which tells publishers (OBS, FFmpeg, vMix)
+/// not to look for a digest.
+pub fn server_handshake<S: Read + Write>(stream: &mut S) -> io::Result<()> {
+    let mut c0 = [0u8; 1];
+    stream.read_exact(&mut c0)?;
+    if c0[0] != RTMP_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported RTMP version {}", c0[0]),
+        ));
+    }
+    let mut c1 = vec![0u8; HANDSHAKE_SIZE];
+    stream.read_exact(&mut c1)?;
+
+    let mut reply = Vec::with_capacity(1 + HANDSHAKE_SIZE * 2);
+    reply.push(RTMP_VERSION);
+    reply.extend_from_slice(&handshake_packet());
+    // S2 echoes C1
+    reply.extend_from_slice(&c1);
+    stream.write_all(&reply)?;
+    stream.flush()?;
+
+    let mut c2 = vec![0u8; HANDSHAKE_SIZE];
+    stream.read_exact(&mut c2)
+}
+
+/// Perform a client's side of the simple handshake
+pub fn client_handshake<S: Read + Write>(stream: &mut S) -> io::Result<()> {
+    stream.write_all(&client_hello())?;
+    stream.flush()?;
+
+    let mut reply = vec![0u8; 1 + HANDSHAKE_SIZE * 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != RTMP_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported RTMP version {}", reply[0]),
+        ));
+    }
+    // C2 echoes S1
+    stream.write_all(&reply[1..1 + HANDSHAKE_SIZE])?;
+    stream.flush()
+}
+
+/// C0 and C1 a client sends to open a handshake
+pub fn client_hello() -> Vec<u8> {
+    let mut hello = Vec::with_capacity(1 + HANDSHAKE_SIZE);
+    hello.push(RTMP_VERSION);
+    hello.extend_from_slice(&handshake_packet());
+    hello
+}
+
+/// C1/S1: zero time, zero version, then random bytes
+fn handshake_packet() -> Vec<u8> {
+    let mut packet = vec![0u8; HANDSHAKE_SIZE];
+    rand::thread_rng().fill_bytes(&mut packet[8..]);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// In-memory stream: reads from `input`, records writes in `output`
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_server_echoes_c1() {
+        let c1: Vec<u8> = (0..HANDSHAKE_SIZE).map(|i| (i % 251) as u8).collect();
+        let mut input = vec![RTMP_VERSION];
+        input.extend_from_slice(&c1);
+        input.extend_from_slice(&vec![0u8; HANDSHAKE_SIZE]);
+        let mut stream = Duplex { input: Cursor::new(input), output: Vec::new() };
+
+        server_handshake(&mut stream).unwrap();
+        assert_eq!(stream.output.len(), 1 + HANDSHAKE_SIZE * 2);
+        assert_eq!(stream.output[0], RTMP_VERSION);
+        assert_eq!(&stream.output[1..9], &[0u8; 8]);
+        assert_eq!(&stream.output[1 + HANDSHAKE_SIZE..], c1.as_slice());
+    }
+
+    #[test]
+    fn test_rejects_encrypted_handshake() {
+        let mut stream = Duplex { input: Cursor::new(vec![6; 1 + HANDSHAKE_SIZE]), output: Vec::new() };
+        assert!(server_handshake(&mut stream).is_err());
+        assert!(stream.output.is_empty());
+    }
+}
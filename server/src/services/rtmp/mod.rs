@@ -0,0 +1,12 @@
+// RTMP Module
+// Native RTMP ingest: handshake, chunk stream, AMF0 commands, the publish server and a target probe
+
+pub mod amf;
+pub mod chunk;
+mod client;
+mod handshake;
+mod server;
+
+pub use client::{probe_rtmp_publish, RtmpProbeStage, RtmpStageResult};
+pub use handshake::{client_handshake, client_hello, server_handshake, HANDSHAKE_SIZE, RTMP_VERSION};
+pub use server::{PublishHandler, PublishRequest, RtmpServer};
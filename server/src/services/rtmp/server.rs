@@ -0,0 +1,575 @@
+// RTMP Server
+// Accepts publishers and hands their audio, video and metadata on as FLV tags
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use crate::services::{FlvTag, FlvTagKind};
+use super::amf::{self, Amf0Value};
+use super::chunk::*;
+use super::handshake::server_handshake;
+
+/// How often blocked reads and the accept loop check for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Connections dropped after this long without receiving anything
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connections served at once; more are refused
+const MAX_CONNECTIONS: usize = 16;
+
+/// Values announced to clients on connect
+const WINDOW_ACK_SIZE: u32 = 2_500_000;
+const OUT_CHUNK_SIZE: usize = 4096;
+
+/// Message stream handed out by createStream
+const PUBLISH_STREAM_ID: u32 = 1;
+
+/// AMF0 encoding of `@setDataFrame`, which publishers put before `onMetaData`
+const SET_DATA_FRAME: &[u8] = b"\x02\x00\x0d@setDataFrame";
+
+/// A publisher asking to go live
+#[derive(Debug, Clone)]
+pub struct PublishRequest {
+    pub peer: SocketAddr,
+    pub app: String,
+    /// Stream name as sent by the publisher (usually the stream key)
+    pub stream_key: String,
+}
+
+/// Receives what publishers send
+pub trait PublishHandler: Send + Sync {
+    /// Accept or refuse a new connection before the handshake
+    fn on_connect(&self, _peer: SocketAddr) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Accept a publisher, or refuse it with a reason sent back in `onStatus`
+    fn on_publish(&self, request: &PublishRequest) -> Result<(), String>;
+
+    /// Audio, video or script tag from the accepted publisher
+    fn on_tag(&self, tag: FlvTag);
+
+    /// The accepted publisher stopped or disconnected
+    fn on_unpublish(&self, request: &PublishRequest);
+}
+
+/// In-process RTMP ingest listening on one address and application
+///
+/// Each connection is served on its own thread; the handler decides which
+/// publisher may go live. Dropping the server stops it.
+pub struct RtmpServer {
+    local_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RtmpServer {
+    /// Listen on `address` (`host:port`) for publishers of `app`
+    pub fn bind(address: &str, app: &str, handler: Arc<dyn PublishHandler>) -> Result<Self, String> {
+        let addr = address
+            .to_socket_addrs()
+            .map_err(|e| format!("Invalid RTMP listen address {address}: {e}"))?
+            .next()
+            .ok_or_else(|| format!("Invalid RTMP listen address {address}"))?;
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| format!("Failed to listen for RTMP on {addr}: {e}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure RTMP listener: {e}"))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read RTMP listen address: {e}"))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = normalize_app(app);
+        let accept_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || accept_loop(listener, app, handler, accept_stop));
+
+        log::info!("RTMP ingest listening on {local_addr}");
+        Ok(Self {
+            local_addr,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop listening and disconnect every client
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            log::info!("RTMP ingest on {} stopped", self.local_addr);
+        }
+    }
+}
+
+impl Drop for RtmpServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn accept_loop(listener: TcpListener, app: String, handler: Arc<dyn PublishHandler>, stop: Arc<AtomicBool>) {
+    let connections = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::SeqCst) {
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(err) => {
+                log::warn!("[RTMP] Accept failed: {err}");
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        if connections.load(Ordering::SeqCst) >= MAX_CONNECTIONS {
+            log::warn!("[RTMP] Refusing {peer}: too many connections");
+            continue;
+        }
+        if let Err(reason) = handler.on_connect(peer) {
+            log::warn!("[RTMP] Refusing {peer}: {reason}");
+            continue;
+        }
+        connections.fetch_add(1, Ordering::SeqCst);
+
+        let app = app.clone();
+        let handler = Arc::clone(&handler);
+        let stop = Arc::clone(&stop);
+        let connections = Arc::clone(&connections);
+        thread::spawn(move || {
+            match Session::open(stream, peer, app, handler, stop) {
+                Ok(mut session) => {
+                    if let Err(err) = session.run() {
+                        log::info!("[RTMP] {peer} disconnected: {err}");
+                    }
+                    session.end_publish();
+                }
+                Err(err) => log::warn!("[RTMP] {peer} failed to connect: {err}"),
+            }
+            connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+/// Socket reader that waits out read timeouts until the server stops or the peer goes idle
+struct SessionReader {
+    stream: TcpStream,
+    stop: Arc<AtomicBool>,
+    last_data: Instant,
+}
+
+impl Read for SessionReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.stream.read(buf) {
+                Ok(len) => {
+                    self.last_data = Instant::now();
+                    return Ok(len);
+                }
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if self.stop.load(Ordering::SeqCst) {
+                        return Err(io::Error::new(ErrorKind::ConnectionAborted, "server stopped"));
+                    }
+                    if self.last_data.elapsed() >= IDLE_TIMEOUT {
+                        return Err(io::Error::new(ErrorKind::TimedOut, "connection idle"));
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Write for SessionReader {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// One client connection
+struct Session {
+    io: SessionReader,
+    peer: SocketAddr,
+    expected_app: String,
+    handler: Arc<dyn PublishHandler>,
+    reader: ChunkReader,
+    writer: ChunkWriter,
+    app: Option<String>,
+    publishing: Option<PublishRequest>,
+    /// Acknowledgement window the peer asked for (0 = none)
+    ack_window: u64,
+    last_ack: u64,
+}
+
+impl Session {
+    fn open(
+        stream: TcpStream,
+        peer: SocketAddr,
+        expected_app: String,
+        handler: Arc<dyn PublishHandler>,
+        stop: Arc<AtomicBool>,
+    ) -> io::Result<Self> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        stream.set_nodelay(true)?;
+        let mut io = SessionReader {
+            stream,
+            stop,
+            last_data: Instant::now(),
+        };
+        server_handshake(&mut io)?;
+        log::debug!("[RTMP] Handshake with {peer} complete");
+
+        Ok(Self {
+            io,
+            peer,
+            expected_app,
+            handler,
+            reader: ChunkReader::new(),
+            writer: ChunkWriter::new(),
+            app: None,
+            publishing: None,
+            ack_window: 0,
+            last_ack: 0,
+        })
+    }
+
+    fn run(&mut self) -> io::Result<()> {
+        loop {
+            let message = self.reader.read_message(&mut self.io)?;
+            self.handle_message(message)?;
+
+            let read = self.reader.bytes_read();
+            if self.ack_window > 0 && read - self.last_ack >= self.ack_window {
+                self.last_ack = read;
+                self.send(CSID_CONTROL, RtmpMessage::control(MSG_ACKNOWLEDGEMENT, read as u32))?;
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: RtmpMessage) -> io::Result<()> {
+        match message.type_id {
+            MSG_SET_CHUNK_SIZE => {
+                let size = read_u32(&message.payload) & 0x7fff_ffff;
+                self.reader.set_chunk_size(size as usize);
+            }
+            MSG_ABORT => self.reader.abort(read_u32(&message.payload)),
+            MSG_WINDOW_ACK_SIZE => self.ack_window = u64::from(read_u32(&message.payload)),
+            MSG_AUDIO | MSG_VIDEO if self.publishing.is_some() && !message.payload.is_empty() => {
+                if let Some(kind) = FlvTagKind::from_type_id(message.type_id) {
+                    self.handler.on_tag(FlvTag::new(kind, message.timestamp, message.payload));
+                }
+            }
+            MSG_DATA_AMF0 if self.publishing.is_some() => {
+                let payload = match message.payload.strip_prefix(SET_DATA_FRAME) {
+                    Some(rest) => rest.to_vec(),
+                    None => message.payload,
+                };
+                self.handler.on_tag(FlvTag::new(FlvTagKind::Script, message.timestamp, payload));
+            }
+            MSG_COMMAND_AMF0 => {
+                let values = amf::decode_all(&message.payload)
+                    .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+                self.handle_command(message.stream_id, values)?;
+            }
+            // Acknowledgements, user control, bandwidth and AMF3 messages are not needed to ingest
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_command(&mut self, stream_id: u32, values: Vec<Amf0Value>) -> io::Result<()> {
+        let name = values.first().and_then(Amf0Value::as_str).unwrap_or_default();
+        let transaction = values.get(1).and_then(Amf0Value::as_number).unwrap_or(0.0);
+
+        match name {
+            "connect" => {
+                let app = values
+                    .get(2)
+                    .and_then(|object| object.get("app"))
+                    .and_then(Amf0Value::as_str)
+                    .map(normalize_app)
+                    .unwrap_or_default();
+                self.connect(transaction, app)
+            }
+            "releaseStream" | "FCPublish" | "getStreamLength" => {
+                self.reply(transaction, vec![Amf0Value::Null, Amf0Value::Undefined])
+            }
+            "createStream" => self.reply(
+                transaction,
+                vec![Amf0Value::Null, Amf0Value::Number(f64::from(PUBLISH_STREAM_ID))],
+            ),
+            "publish" => {
+                let stream_key = values.get(3).and_then(Amf0Value::as_str).unwrap_or_default();
+                self.publish(stream_id, stream_key.to_string())
+            }
+            "FCUnpublish" | "deleteStream" | "closeStream" => {
+                self.end_publish();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn connect(&mut self, transaction: f64, app: String) -> io::Result<()> {
+        if app != self.expected_app {
+            self.send_command(0, vec![
+                Amf0Value::string("_error"),
+                Amf0Value::Number(transaction),
+                Amf0Value::Null,
+                status("error", "NetConnection.Connect.Rejected", &format!("Unknown application '{app}'")),
+            ])?;
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("requested application '{app}', expected '{}'", self.expected_app),
+            ));
+        }
+
+        self.send(CSID_CONTROL, RtmpMessage::control(MSG_WINDOW_ACK_SIZE, WINDOW_ACK_SIZE))?;
+        let mut bandwidth = WINDOW_ACK_SIZE.to_be_bytes().to_vec();
+        // Dynamic limit
+        bandwidth.push(2);
+        self.send(CSID_CONTROL, RtmpMessage::new(MSG_SET_PEER_BANDWIDTH, 0, 0, bandwidth))?;
+        self.send(CSID_CONTROL, RtmpMessage::control(MSG_SET_CHUNK_SIZE, OUT_CHUNK_SIZE as u32))?;
+        self.writer.set_chunk_size(OUT_CHUNK_SIZE);
+
+        let mut info = status("status", "NetConnection.Connect.Success", "Connection succeeded.");
+        if let Amf0Value::Object(properties) = &mut info {
+            properties.push(("objectEncoding".to_string(), Amf0Value::Number(0.0)));
+        }
+        self.send_command(0, vec![
+            Amf0Value::string("_result"),
+            Amf0Value::Number(transaction),
+            Amf0Value::object([
+                ("fmsVer", Amf0Value::string("FMS/3,0,1,123")),
+                ("capabilities", Amf0Value::Number(31.0)),
+            ]),
+            info,
+        ])?;
+        self.app = Some(app);
+        Ok(())
+    }
+
+    fn publish(&mut self, stream_id: u32, stream_key: String) -> io::Result<()> {
+        let Some(app) = self.app.clone() else {
+            return Err(io::Error::new(ErrorKind::InvalidData, "publish before connect"));
+        };
+        if self.publishing.is_some() {
+            return Ok(());
+        }
+
+        let request = PublishRequest {
+            peer: self.peer,
+            app,
+            stream_key,
+        };
+        if let Err(reason) = self.handler.on_publish(&request) {
+            self.send_status(stream_id, "error", "NetStream.Publish.BadName", &reason)?;
+            return Err(io::Error::new(ErrorKind::PermissionDenied, reason));
+        }
+
+        log::info!("[RTMP] {} started publishing to '{}'", self.peer, request.app);
+        self.publishing = Some(request);
+        // User control: Stream Begin
+        let mut stream_begin = vec![0, 0];
+        stream_begin.extend_from_slice(&stream_id.to_be_bytes());
+        self.send(CSID_CONTROL, RtmpMessage::new(MSG_USER_CONTROL, 0, 0, stream_begin))?;
+        self.send_status(stream_id, "status", "NetStream.Publish.Start", "Publishing started.")
+    }
+
+    /// Tell the handler the publisher is gone (once)
+    fn end_publish(&mut self) {
+        if let Some(request) = self.publishing.take() {
+            log::info!("[RTMP] {} stopped publishing", self.peer);
+            self.handler.on_unpublish(&request);
+        }
+    }
+
+    fn reply(&mut self, transaction: f64, mut values: Vec<Amf0Value>) -> io::Result<()> {
+        // Transaction 0 means the client does not expect an answer
+        if transaction == 0.0 {
+            return Ok(());
+        }
+        values.insert(0, Amf0Value::Number(transaction));
+        values.insert(0, Amf0Value::string("_result"));
+        self.send_command(0, values)
+    }
+
+    fn send_status(&mut self, stream_id: u32, level: &str, code: &str, description: &str) -> io::Result<()> {
+        self.send_command(stream_id, vec![
+            Amf0Value::string("onStatus"),
+            Amf0Value::Number(0.0),
+            Amf0Value::Null,
+            status(level, code, description),
+        ])
+    }
+
+    fn send_command(&mut self, stream_id: u32, values: Vec<Amf0Value>) -> io::Result<()> {
+        let payload = amf::encode_all(&values);
+        self.send(CSID_COMMAND, RtmpMessage::new(MSG_COMMAND_AMF0, stream_id, 0, payload))
+    }
+
+    fn send(&mut self, csid: u32, message: RtmpMessage) -> io::Result<()> {
+        self.writer.write_message(&mut self.io, csid, &message)
+    }
+}
+
+/// `onStatus` / `_result` information object
+fn status(level: &str, code: &str, description: &str) -> Amf0Value {
+    Amf0Value::object([
+        ("level", Amf0Value::string(level)),
+        ("code", Amf0Value::string(code)),
+        ("description", Amf0Value::string(description)),
+    ])
+}
+
+/// Application name without slashes or a query string (`/live/?x` -> `live`)
+fn normalize_app(app: &str) -> String {
+    app.split('?').next().unwrap_or_default().trim_matches('/').to_string()
+}
+
+fn read_u32(payload: &[u8]) -> u32 {
+    match payload {
+        [a, b, c, d, ..] => u32::from_be_bytes([*a, *b, *c, *d]),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use super::super::handshake::{HANDSHAKE_SIZE, RTMP_VERSION};
+
+    #[derive(Default)]
+    struct Recorder {
+        tags: Mutex<Vec<FlvTag>>,
+        keys: Mutex<Vec<String>>,
+        unpublished: AtomicBool,
+    }
+
+    impl PublishHandler for Recorder {
+        fn on_publish(&self, request: &PublishRequest) -> Result<(), String> {
+            self.keys.lock().unwrap().push(request.stream_key.clone());
+            if request.stream_key == "wrong" {
+                return Err("Invalid stream key".to_string());
+            }
+            Ok(())
+        }
+
+        fn on_tag(&self, tag: FlvTag) {
+            self.tags.lock().unwrap().push(tag);
+        }
+
+        fn on_unpublish(&self, _request: &PublishRequest) {
+            self.unpublished.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Minimal publisher: handshake, connect, createStream, publish
+    fn publish(addr: SocketAddr, app: &str, key: &str) -> (TcpStream, ChunkReader) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut hello = vec![RTMP_VERSION];
+        hello.extend_from_slice(&[0u8; HANDSHAKE_SIZE]);
+        stream.write_all(&hello).unwrap();
+        let mut reply = vec![0u8; 1 + HANDSHAKE_SIZE * 2];
+        stream.read_exact(&mut reply).unwrap();
+        stream.write_all(&reply[1..1 + HANDSHAKE_SIZE]).unwrap();
+
+        let writer = ChunkWriter::new();
+        let command = |values: Vec<Amf0Value>, stream_id| {
+            RtmpMessage::new(MSG_COMMAND_AMF0, stream_id, 0, amf::encode_all(&values))
+        };
+        writer.write_message(&mut stream, 3, &command(vec![
+            Amf0Value::string("connect"),
+            Amf0Value::Number(1.0),
+            Amf0Value::object([("app", Amf0Value::string(app))]),
+        ], 0)).unwrap();
+        writer.write_message(&mut stream, 3, &command(vec![
+            Amf0Value::string("createStream"),
+            Amf0Value::Number(2.0),
+            Amf0Value::Null,
+        ], 0)).unwrap();
+        writer.write_message(&mut stream, 8, &command(vec![
+            Amf0Value::string("publish"),
+            Amf0Value::Number(0.0),
+            Amf0Value::Null,
+            Amf0Value::string(key),
+            Amf0Value::string("live"),
+        ], 1)).unwrap();
+        (stream, ChunkReader::new())
+    }
+
+    /// Read server messages until an `onStatus`, returning its code
+    fn status_code(stream: &mut TcpStream, reader: &mut ChunkReader) -> String {
+        loop {
+            let message = reader.read_message(stream).unwrap();
+            if message.type_id == MSG_SET_CHUNK_SIZE {
+                reader.set_chunk_size(read_u32(&message.payload) as usize);
+            }
+            if message.type_id != MSG_COMMAND_AMF0 {
+                continue;
+            }
+            let values = amf::decode_all(&message.payload).unwrap();
+            if values[0].as_str() == Some("onStatus") {
+                return values[3].get("code").and_then(Amf0Value::as_str).unwrap().to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn test_publisher_tags_reach_handler() {
+        let recorder = Arc::new(Recorder::default());
+        let mut server = RtmpServer::bind("127.0.0.1:0", "/live", recorder.clone()).unwrap();
+        let (mut stream, mut reader) = publish(server.local_addr(), "live", "key123");
+        assert_eq!(status_code(&mut stream, &mut reader), "NetStream.Publish.Start");
+
+        let writer = ChunkWriter::new();
+        let mut metadata = SET_DATA_FRAME.to_vec();
+        metadata.extend(amf::encode_all(&[
+            Amf0Value::string("onMetaData"),
+            Amf0Value::EcmaArray(vec![("width".to_string(), Amf0Value::Number(1280.0))]),
+        ]));
+        writer.write_message(&mut stream, 4, &RtmpMessage::new(MSG_DATA_AMF0, 1, 0, metadata)).unwrap();
+        writer.write_message(&mut stream, 6, &RtmpMessage::new(MSG_VIDEO, 1, 33, vec![0x17, 1, 0, 0, 0])).unwrap();
+        drop(stream);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !recorder.unpublished.load(Ordering::SeqCst) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(recorder.unpublished.load(Ordering::SeqCst));
+        let tags = recorder.tags.lock().unwrap();
+        assert_eq!(tags.len(), 2);
+        assert!(tags[0].is_metadata());
+        assert_eq!((tags[1].kind, tags[1].timestamp), (FlvTagKind::Video, 33));
+        assert_eq!(*recorder.keys.lock().unwrap(), vec!["key123".to_string()]);
+        server.stop();
+    }
+
+    #[test]
+    fn test_refused_publisher_gets_error_status() {
+        let recorder = Arc::new(Recorder::default());
+        let server = RtmpServer::bind("127.0.0.1:0", "live", recorder.clone()).unwrap();
+        let (mut stream, mut reader) = publish(server.local_addr(), "live", "wrong");
+        assert_eq!(status_code(&mut stream, &mut reader), "NetStream.Publish.BadName");
+        assert!(!recorder.unpublished.load(Ordering::SeqCst));
+    }
+}
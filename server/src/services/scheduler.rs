@@ -0,0 +1,459 @@
+// Scheduler Service
+// Starts and stops profiles' streams at scheduled times
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use tokio::sync::mpsc;
+use crate::models::{ScheduleEntry, ScheduleEvent};
+use crate::services::{emit_event, CronSchedule, EventSink};
+
+/// How often schedules are checked
+const SCHEDULER_TICK: Duration = Duration::from_secs(5);
+
+/// How long before a start the `schedule_upcoming` event is sent
+const UPCOMING_LEAD_MS: i64 = 5 * 60_000;
+
+/// How late an entry without a duration may still start (e.g. after a server restart)
+const LATE_START_GRACE_MS: i64 = 10 * 60_000;
+
+/// Occurrences walked per entry and tick when catching up after downtime
+const MAX_CATCH_UP: usize = 1000;
+
+/// Work the scheduler hands to the server, which owns the stream and chat services
+#[derive(Debug, Clone)]
+pub enum ScheduleAction {
+    /// Start the entry's groups for the occurrence beginning at `occurrence`
+    Start {
+        entry: ScheduleEntry,
+        occurrence: i64,
+        /// The occurrence was live before a server restart
+        resumed: bool,
+    },
+    /// Stop the groups the entry started (its duration has elapsed)
+    Stop { entry: ScheduleEntry },
+}
+
+/// Stores schedule entries in `schedules.json` and works out when they are due
+pub struct Scheduler {
+    path: PathBuf,
+    entries: Mutex<Vec<ScheduleEntry>>,
+    /// Occurrences already announced as upcoming (schedule id, occurrence)
+    announced: Mutex<HashSet<(String, i64)>>,
+    event_sink: Arc<dyn EventSink>,
+}
+
+impl Scheduler {
+    /// Create a scheduler, loading saved entries from the app data directory
+    pub fn new(app_data_dir: PathBuf, event_sink: Arc<dyn EventSink>) -> Self {
+        let path = app_data_dir.join("schedules.json");
+        let entries = match Self::read_entries(&path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("Failed to load schedules, starting with none: {e}");
+                Vec::new()
+            }
+        };
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            announced: Mutex::new(HashSet::new()),
+            event_sink,
+        }
+    }
+
+    fn read_entries(path: &PathBuf) -> Result<Vec<ScheduleEntry>, String> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read schedules: {e}"))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse schedules: {e}"))
+    }
+
+    fn write_entries(&self, entries: &[ScheduleEntry]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create schedules directory: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize schedules: {e}"))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write schedules: {e}"))
+    }
+
+    /// Persist after a run-state change; a failed write is logged, not fatal
+    fn persist(&self, entries: &[ScheduleEntry]) {
+        if let Err(e) = self.write_entries(entries) {
+            log::error!("{e}");
+        }
+    }
+
+    /// Schedule entries, optionally limited to one profile
+    pub fn list(&self, profile_name: Option<&str>) -> Vec<ScheduleEntry> {
+        self.lock()
+            .iter()
+            .filter(|entry| profile_name.map_or(true, |name| entry.profile_name == name))
+            .cloned()
+            .collect()
+    }
+
+    /// Create or update an entry
+    ///
+    /// Run state is kept unless the timing changed, in which case the entry
+    /// starts counting from now.
+    pub fn save(&self, mut entry: ScheduleEntry) -> Result<ScheduleEntry, String> {
+        entry.validate()?;
+        if let Some(cron) = &entry.cron {
+            CronSchedule::parse(cron)?;
+        }
+
+        let now = now_ms();
+        let mut entries = self.lock();
+        if entry.id.is_empty() {
+            entry.id = uuid::Uuid::new_v4().to_string();
+        }
+
+        match entries.iter_mut().find(|existing| existing.id == entry.id) {
+            Some(existing) => {
+                if existing.same_timing(&entry) {
+                    entry.created_at = existing.created_at;
+                    entry.last_occurrence = existing.last_occurrence;
+                } else {
+                    entry.created_at = now;
+                    entry.last_occurrence = None;
+                }
+                entry.running_since = existing.running_since;
+                entry.started_group_ids = existing.started_group_ids.clone();
+                *existing = entry.clone();
+            }
+            None => {
+                entry.created_at = now;
+                entry.last_occurrence = None;
+                entry.running_since = None;
+                entry.started_group_ids.clear();
+                entries.push(entry.clone());
+            }
+        }
+
+        self.write_entries(&entries)?;
+        log::info!("Saved schedule {} for profile '{}'", entry.id, entry.profile_name);
+        Ok(entry)
+    }
+
+    /// Delete an entry (a live occurrence keeps streaming)
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|entry| entry.id != id);
+        if entries.len() == before {
+            return Err(format!("Schedule '{id}' not found"));
+        }
+        self.write_entries(&entries)
+    }
+
+    /// Forget live occurrences after the user stopped every stream by hand,
+    /// so they are not resumed after a restart
+    pub fn clear_running(&self) {
+        let mut entries = self.lock();
+        let mut changed = false;
+        for entry in entries.iter_mut().filter(|entry| entry.running_since.is_some()) {
+            entry.running_since = None;
+            entry.started_group_ids.clear();
+            changed = true;
+        }
+        if changed {
+            self.persist(&entries);
+        }
+    }
+
+    /// Record a successful start of `group_ids` and emit `schedule_started`
+    pub fn report_started(&self, id: &str, occurrence: i64, group_ids: Vec<String>) {
+        let event = {
+            let mut entries = self.lock();
+            let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) else {
+                return;
+            };
+            entry.running_since = Some(occurrence);
+            entry.started_group_ids = group_ids;
+            let event = schedule_event(entry, occurrence, None);
+            self.persist(&entries);
+            event
+        };
+        log::info!("Scheduled stream started for profile '{}'", event.profile_name);
+        emit_event(self.event_sink.as_ref(), "schedule_started", &event);
+    }
+
+    /// Record a failed start and emit `schedule_missed`
+    pub fn report_failed(&self, id: &str, occurrence: i64, error: String) {
+        let event = {
+            let mut entries = self.lock();
+            let Some(index) = entries.iter().position(|entry| entry.id == id) else {
+                return;
+            };
+            if entries[index].running_since == Some(occurrence) {
+                entries[index].running_since = None;
+                entries[index].started_group_ids.clear();
+                self.persist(&entries);
+            }
+            schedule_event(&entries[index], occurrence, Some(error))
+        };
+        log::warn!(
+            "Scheduled stream for profile '{}' did not start: {}",
+            event.profile_name,
+            event.reason.as_deref().unwrap_or_default()
+        );
+        emit_event(self.event_sink.as_ref(), "schedule_missed", &event);
+    }
+
+    /// Work out what is due at `now`, emitting upcoming and missed events
+    ///
+    /// On `startup`, occurrences that were live when the server stopped are
+    /// started again if their window is still open.
+    pub fn due_actions(&self, now: i64, startup: bool) -> Vec<ScheduleAction> {
+        let mut actions = Vec::new();
+        let mut upcoming = Vec::new();
+        let mut missed = Vec::new();
+        let mut changed = false;
+
+        let mut entries = self.lock();
+        for entry in entries.iter_mut() {
+            // Stops apply even if the entry was disabled while live
+            if let Some(since) = entry.running_since {
+                match entry.stop_time(since) {
+                    Some(stop) if now >= stop => {
+                        entry.running_since = None;
+                        changed = true;
+                        actions.push(ScheduleAction::Stop { entry: entry.clone() });
+                        entry.started_group_ids.clear();
+                    }
+                    _ if startup => actions.push(ScheduleAction::Start {
+                        entry: entry.clone(),
+                        occurrence: since,
+                        resumed: true,
+                    }),
+                    _ => {}
+                }
+            }
+
+            if !entry.enabled {
+                continue;
+            }
+
+            let mut last_missed = None;
+            for _ in 0..MAX_CATCH_UP {
+                let occurrence = match next_occurrence(entry) {
+                    Ok(Some(occurrence)) => occurrence,
+                    Ok(None) => break,
+                    Err(e) => {
+                        log::warn!("Schedule {} is invalid: {e}", entry.id);
+                        break;
+                    }
+                };
+
+                if occurrence > now {
+                    if occurrence - now <= UPCOMING_LEAD_MS && self.announce(&entry.id, occurrence) {
+                        upcoming.push(schedule_event(entry, occurrence, None));
+                    }
+                    break;
+                }
+
+                entry.last_occurrence = Some(occurrence);
+                changed = true;
+                let window_end = entry
+                    .stop_time(occurrence)
+                    .unwrap_or(occurrence + LATE_START_GRACE_MS);
+                if now < window_end {
+                    actions.push(ScheduleAction::Start {
+                        entry: entry.clone(),
+                        occurrence,
+                        resumed: false,
+                    });
+                } else {
+                    last_missed = Some(occurrence);
+                }
+            }
+
+            if let Some(occurrence) = last_missed {
+                missed.push(schedule_event(
+                    entry,
+                    occurrence,
+                    Some("The server was not running at the scheduled time".to_string()),
+                ));
+            }
+        }
+        if changed {
+            self.persist(&entries);
+        }
+        drop(entries);
+
+        for event in &upcoming {
+            emit_event(self.event_sink.as_ref(), "schedule_upcoming", event);
+        }
+        for event in &missed {
+            log::warn!("Missed scheduled stream for profile '{}'", event.profile_name);
+            emit_event(self.event_sink.as_ref(), "schedule_missed", event);
+        }
+        actions
+    }
+
+    /// Remember an upcoming announcement; false if it was already sent
+    fn announce(&self, id: &str, occurrence: i64) -> bool {
+        self.announced
+            .lock()
+            .map(|mut announced| announced.insert((id.to_string(), occurrence)))
+            .unwrap_or(false)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ScheduleEntry>> {
+        self.entries.lock().unwrap_or_else(|e| {
+            log::warn!("Schedules mutex poisoned, recovering: {e}");
+            e.into_inner()
+        })
+    }
+}
+
+/// Check schedules periodically and send due actions until the receiver is dropped
+pub async fn run_scheduler(scheduler: Arc<Scheduler>, actions: mpsc::UnboundedSender<ScheduleAction>) {
+    let mut interval = tokio::time::interval(SCHEDULER_TICK);
+    let mut startup = true;
+    loop {
+        interval.tick().await;
+        for action in scheduler.due_actions(now_ms(), startup) {
+            if actions.send(action).is_err() {
+                return;
+            }
+        }
+        startup = false;
+    }
+}
+
+/// Next occurrence of an entry that has not been started or missed yet (Unix ms)
+fn next_occurrence(entry: &ScheduleEntry) -> Result<Option<i64>, String> {
+    if let Some(start_at) = entry.start_at {
+        return Ok((entry.last_occurrence != Some(start_at)).then_some(start_at));
+    }
+    let Some(cron) = &entry.cron else {
+        return Ok(None);
+    };
+
+    let cron = CronSchedule::parse(cron)?;
+    // Count from the creation time so saving an entry never fires past occurrences
+    let after = entry.last_occurrence.unwrap_or(entry.created_at);
+    let mut cursor = to_local(after);
+    // A local time skipped by a DST change has no timestamp; try the next one
+    for _ in 0..4 {
+        let Some(next) = cron.next_after(cursor) else {
+            return Ok(None);
+        };
+        if let Some(ms) = from_local(next) {
+            return Ok(Some(ms));
+        }
+        cursor = next;
+    }
+    Ok(None)
+}
+
+fn schedule_event(entry: &ScheduleEntry, occurrence: i64, reason: Option<String>) -> ScheduleEvent {
+    ScheduleEvent {
+        schedule_id: entry.id.clone(),
+        profile_name: entry.profile_name.clone(),
+        occurrence,
+        stop_at: entry.stop_time(occurrence),
+        group_ids: entry.group_ids.clone(),
+        reason,
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn to_local(ms: i64) -> NaiveDateTime {
+    Local
+        .timestamp_millis_opt(ms)
+        .earliest()
+        .map(|time| time.naive_local())
+        .unwrap_or_default()
+}
+
+fn from_local(time: NaiveDateTime) -> Option<i64> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::NoopEventSink;
+
+    fn scheduler(dir: &tempfile::TempDir) -> Scheduler {
+        Scheduler::new(dir.path().to_path_buf(), Arc::new(NoopEventSink))
+    }
+
+    fn one_off(start_at: i64, duration_minutes: Option<u32>) -> ScheduleEntry {
+        ScheduleEntry {
+            id: String::new(),
+            profile_name: "Main".to_string(),
+            enabled: true,
+            start_at: Some(start_at),
+            cron: None,
+            duration_minutes,
+            group_ids: Vec::new(),
+            created_at: 0,
+            last_occurrence: None,
+            running_since: None,
+            started_group_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_one_off_starts_once_and_stops_after_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let scheduler = scheduler(&dir);
+        let start = now_ms() + 60_000;
+        let entry = scheduler.save(one_off(start, Some(30))).unwrap();
+
+        assert!(scheduler.due_actions(start - 1, false).is_empty());
+        let actions = scheduler.due_actions(start + 1_000, false);
+        assert!(matches!(actions.as_slice(), [ScheduleAction::Start { occurrence, .. }] if *occurrence == start));
+        scheduler.report_started(&entry.id, start, vec!["main".to_string()]);
+        assert!(scheduler.due_actions(start + 2_000, false).is_empty());
+
+        // Only the groups the occurrence started are stopped
+        let actions = scheduler.due_actions(start + 30 * 60_000, false);
+        assert!(matches!(actions.as_slice(), [ScheduleAction::Stop { entry }] if entry.started_group_ids == ["main"]));
+        assert!(scheduler.list(None)[0].started_group_ids.is_empty());
+        assert!(scheduler.due_actions(start + 31 * 60_000, false).is_empty());
+    }
+
+    #[test]
+    fn test_restart_resumes_open_window_and_skips_closed_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = now_ms() + 60_000;
+        let (live, late) = {
+            let scheduler = scheduler(&dir);
+            let live = scheduler.save(one_off(start, Some(60))).unwrap();
+            let late = scheduler.save(one_off(start, Some(5))).unwrap();
+            scheduler.due_actions(start, false);
+            scheduler.report_started(&live.id, start, Vec::new());
+            (live, late)
+        };
+
+        // Reloaded from disk 10 minutes in: the 60 minute entry is resumed, the
+        // 5 minute one was live too but its window has closed
+        let scheduler = scheduler(&dir);
+        scheduler.report_started(&late.id, start, Vec::new());
+        let actions = scheduler.due_actions(start + 10 * 60_000, true);
+        assert_eq!(actions.len(), 2);
+        assert!(actions.iter().any(|action| matches!(action,
+            ScheduleAction::Start { entry, resumed: true, .. } if entry.id == live.id)));
+        assert!(actions.iter().any(|action| matches!(action,
+            ScheduleAction::Stop { entry } if entry.id == late.id)));
+    }
+}
@@ -0,0 +1,443 @@
+// Session History Service
+// Records each streaming run with downsampled metrics and keeps it in the app data directory
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde_json::Value;
+use crate::models::{
+    OutputGroup, SessionEvent, SessionEventKind, SessionGroup, SessionSample, SessionTarget,
+    StreamSession, StreamSessionSummary, StreamStats,
+};
+
+/// Directory under the app data directory holding one JSON file per session
+const SESSIONS_DIR: &str = "sessions";
+
+/// Stats are averaged over this long before a sample is kept
+const SAMPLE_INTERVAL_MS: i64 = 5_000;
+
+/// How often a live session is written out, so a crash loses at most this much
+const SAVE_INTERVAL_MS: i64 = 60_000;
+
+/// Stats averaged so far for one group's current sample interval
+struct SampleBucket {
+    start: i64,
+    count: u32,
+    fps: f64,
+    bitrate: f64,
+    speed: f64,
+    dropped_frames: u64,
+}
+
+impl SampleBucket {
+    fn new(start: i64) -> Self {
+        Self { start, count: 0, fps: 0.0, bitrate: 0.0, speed: 0.0, dropped_frames: 0 }
+    }
+
+    fn add(&mut self, stats: &StreamStats) {
+        self.count += 1;
+        self.fps += stats.fps;
+        self.bitrate += stats.bitrate;
+        self.speed += stats.speed;
+        self.dropped_frames = stats.dropped_frames;
+    }
+
+    fn sample(&self, group_id: &str) -> SessionSample {
+        let count = f64::from(self.count.max(1));
+        SessionSample {
+            at: self.start,
+            group_id: group_id.to_string(),
+            fps: self.fps / count,
+            bitrate: self.bitrate / count,
+            speed: self.speed / count,
+            dropped_frames: self.dropped_frames,
+        }
+    }
+}
+
+struct ActiveSession {
+    session: StreamSession,
+    buckets: HashMap<String, SampleBucket>,
+    last_saved: i64,
+}
+
+impl ActiveSession {
+    /// Move finished buckets (or all of them) into the session's samples
+    fn flush_buckets(&mut self, now: i64, all: bool) {
+        let done: Vec<String> = self
+            .buckets
+            .iter()
+            .filter(|(_, bucket)| all || now - bucket.start >= SAMPLE_INTERVAL_MS)
+            .map(|(group_id, _)| group_id.clone())
+            .collect();
+        for group_id in done {
+            if let Some(bucket) = self.buckets.remove(&group_id) {
+                if bucket.count > 0 {
+                    self.session.samples.push(bucket.sample(&group_id));
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the history of streaming sessions in `sessions/<id>.json`
+///
+/// A session begins when the first output group starts and ends when the last
+/// one stops. Stats and stream events reach it through [`SessionHistory::observe`].
+pub struct SessionHistory {
+    dir: PathBuf,
+    active: Mutex<Option<ActiveSession>>,
+}
+
+impl SessionHistory {
+    /// Open the history, closing sessions a crash or shutdown left open
+    pub fn new(app_data_dir: &Path) -> Self {
+        let history = Self {
+            dir: app_data_dir.join(SESSIONS_DIR),
+            active: Mutex::new(None),
+        };
+        for mut session in history.read_all() {
+            if session.ended_at.is_none() {
+                session.ended_at = Some(session.last_activity());
+                if let Err(e) = history.write(&session) {
+                    log::warn!("Failed to close interrupted session {}: {e}", session.id);
+                }
+            }
+        }
+        history
+    }
+
+    /// Begin a session for `groups`, or add them to the one already running
+    pub fn record_start(&self, profile_name: &str, groups: &[OutputGroup]) {
+        let now = chrono::Local::now().timestamp_millis();
+        let Ok(mut active) = self.active.lock() else {
+            return;
+        };
+        // Another profile going live is a new broadcast
+        if active.as_ref().is_some_and(|running| running.session.profile_name != profile_name) {
+            if let Some(previous) = active.take() {
+                self.finish(previous, now);
+            }
+        }
+        let active = active.get_or_insert_with(|| {
+            log::info!("Recording stream session for profile '{profile_name}'");
+            ActiveSession {
+                session: StreamSession {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    profile_name: profile_name.to_string(),
+                    started_at: now,
+                    ended_at: None,
+                    groups: Vec::new(),
+                    events: Vec::new(),
+                    samples: Vec::new(),
+                },
+                buckets: HashMap::new(),
+                last_saved: 0,
+            }
+        });
+
+        for group in groups {
+            if active.session.groups.iter().any(|known| known.id == group.id) {
+                continue;
+            }
+            active.session.groups.push(SessionGroup {
+                id: group.id.clone(),
+                name: group.name.clone(),
+                targets: group
+                    .stream_targets
+                    .iter()
+                    .map(|target| SessionTarget {
+                        id: target.id.clone(),
+                        name: target.name.clone(),
+                        service: target.service.clone(),
+                        protocol: target.protocol,
+                    })
+                    .collect(),
+            });
+        }
+        self.save(active, now);
+    }
+
+    /// End the running session and write it out
+    pub fn record_stop(&self) {
+        let now = chrono::Local::now().timestamp_millis();
+        let finished = self.active.lock().ok().and_then(|mut active| active.take());
+        if let Some(active) = finished {
+            self.finish(active, now);
+        }
+    }
+
+    /// End the running session when `event` means a group stopped by itself and `groups_left` says none is left
+    ///
+    /// A group stops by itself when its input ends or it fails without reconnecting
+    /// automatically; stop commands end the session with [`SessionHistory::record_stop`].
+    pub fn record_group_end(&self, event: &str, payload: &Value, groups_left: impl FnOnce() -> bool) {
+        let ended = match event {
+            "stream_ended" | "stream_gave_up" => true,
+            "stream_error" => payload["autoReconnect"] == Value::Bool(false),
+            _ => false,
+        };
+        if ended && !groups_left() {
+            self.record_stop();
+        }
+    }
+
+    fn finish(&self, mut active: ActiveSession, now: i64) {
+        active.flush_buckets(now, true);
+        active.session.ended_at = Some(now);
+        if let Err(e) = self.write(&active.session) {
+            log::error!("Failed to save stream session: {e}");
+        }
+    }
+
+    /// Feed an emitted event into the running session
+    pub fn observe(&self, event: &str, payload: &Value) {
+        self.observe_at(event, payload, chrono::Local::now().timestamp_millis());
+    }
+
+    fn observe_at(&self, event: &str, payload: &Value, now: i64) {
+        let Ok(mut guard) = self.active.lock() else {
+            return;
+        };
+        let Some(active) = guard.as_mut() else {
+            return;
+        };
+
+        let group_id = payload["groupId"].as_str().or_else(|| payload.as_str()).unwrap_or_default();
+        let field = |name: &str| payload[name].as_u64().unwrap_or(0);
+        let (kind, message) = match event {
+            "stream_stats" => {
+                let Ok(stats) = serde_json::from_value::<StreamStats>(payload.clone()) else {
+                    return;
+                };
+                if !active.session.groups.iter().any(|group| group.id == stats.group_id) {
+                    return;
+                }
+                active.flush_buckets(now, false);
+                // A lower drop count means FFmpeg restarted; keep the old run's count in its own sample
+                if let Some(bucket) = active.buckets.get(&stats.group_id) {
+                    if stats.dropped_frames < bucket.dropped_frames {
+                        active.session.samples.push(bucket.sample(&stats.group_id));
+                        active.buckets.remove(&stats.group_id);
+                    }
+                }
+                active
+                    .buckets
+                    .entry(stats.group_id.clone())
+                    .or_insert_with(|| SampleBucket::new(now))
+                    .add(&stats);
+                if now - active.last_saved >= SAVE_INTERVAL_MS {
+                    self.save(active, now);
+                }
+                return;
+            }
+            "stream_error" => (SessionEventKind::Error, payload["error"].as_str().unwrap_or_default().to_string()),
+            "stream_reconnecting" => (
+                SessionEventKind::Reconnecting,
+                format!("Attempt {} of {} in {}s", field("attempt"), field("maxAttempts"), field("delaySecs")),
+            ),
+            "stream_reconnected" => (SessionEventKind::Reconnected, format!("Reconnected on attempt {}", field("attempt"))),
+            "stream_gave_up" => (
+                SessionEventKind::GaveUp,
+                format!("Gave up after {} attempts: {}", field("attempts"), payload["error"].as_str().unwrap_or_default()),
+            ),
+            "stream_ended" => (SessionEventKind::Ended, "Stream ended".to_string()),
+            _ => return,
+        };
+        if group_id.is_empty() || !active.session.groups.iter().any(|group| group.id == group_id) {
+            return;
+        }
+        active.session.events.push(SessionEvent {
+            at: now,
+            group_id: group_id.to_string(),
+            kind,
+            message,
+        });
+        self.save(active, now);
+    }
+
+    /// All sessions, newest first (the running one included)
+    pub fn list(&self) -> Vec<StreamSessionSummary> {
+        let live = self.live_session();
+        let mut summaries: Vec<StreamSessionSummary> = self
+            .read_all()
+            .iter()
+            .filter(|session| live.as_ref().map_or(true, |live| live.id != session.id))
+            .chain(live.as_ref())
+            .map(StreamSession::summary)
+            .collect();
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.started_at));
+        summaries
+    }
+
+    /// Full session with its samples and events
+    pub fn get(&self, id: &str) -> Result<StreamSession, String> {
+        if let Some(live) = self.live_session().filter(|live| live.id == id) {
+            return Ok(live);
+        }
+        let path = self.session_path(id)?;
+        let content = std::fs::read_to_string(&path).map_err(|_| format!("Stream session not found: {id}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse stream session: {e}"))
+    }
+
+    /// Write a session to `path` as JSON, or as CSV samples when the path ends in `.csv`
+    pub fn export(&self, id: &str, path: &Path) -> Result<(), String> {
+        let session = self.get(id)?;
+        let is_csv = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let content = if is_csv {
+            session.samples_csv()
+        } else {
+            serde_json::to_string_pretty(&session).map_err(|e| format!("Failed to serialize stream session: {e}"))?
+        };
+        std::fs::write(path, content).map_err(|e| format!("Failed to write export file: {e}"))
+    }
+
+    /// Snapshot of the running session, including the samples still being averaged
+    fn live_session(&self) -> Option<StreamSession> {
+        let guard = self.active.lock().ok()?;
+        let active = guard.as_ref()?;
+        let mut session = active.session.clone();
+        session
+            .samples
+            .extend(active.buckets.iter().filter(|(_, b)| b.count > 0).map(|(id, b)| b.sample(id)));
+        Some(session)
+    }
+
+    fn save(&self, active: &mut ActiveSession, now: i64) {
+        active.last_saved = now;
+        if let Err(e) = self.write(&active.session) {
+            log::warn!("Failed to save stream session: {e}");
+        }
+    }
+
+    fn write(&self, session: &StreamSession) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create sessions directory: {e}"))?;
+        let content = serde_json::to_string(session).map_err(|e| format!("Failed to serialize stream session: {e}"))?;
+        std::fs::write(self.session_path(&session.id)?, content)
+            .map_err(|e| format!("Failed to write stream session: {e}"))
+    }
+
+    fn read_all(&self) -> Vec<StreamSession> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&content)
+                    .map_err(|e| log::warn!("Skipping unreadable session {}: {e}", path.display()))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Session file for an ID, refusing anything that could leave the sessions directory
+    fn session_path(&self, id: &str) -> Result<PathBuf, String> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid stream session ID: {id}"));
+        }
+        Ok(self.dir.join(format!("{id}.json")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn group(id: &str) -> OutputGroup {
+        let mut group = OutputGroup::new();
+        group.id = id.to_string();
+        group.name = format!("Group {id}");
+        group
+    }
+
+    fn stats(group_id: &str, bitrate: f64, dropped_frames: u64) -> Value {
+        json!({
+            "groupId": group_id, "frame": 0, "fps": 30.0, "bitrate": bitrate, "speed": 1.0,
+            "size": 0, "time": 0.0, "droppedFrames": dropped_frames, "dupFrames": 0
+        })
+    }
+
+    #[test]
+    fn test_records_downsampled_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = SessionHistory::new(dir.path());
+        history.record_start("Main", &[group("a")]);
+        let start = history.live_session().unwrap().started_at;
+
+        // One-second stats; a reconnect restarts FFmpeg's drop counter partway through
+        for second in 0..10i64 {
+            let dropped = if second < 6 { second } else { second - 6 };
+            history.observe_at("stream_stats", &stats("a", 6000.0 + second as f64, dropped as u64), start + second * 1000);
+        }
+        history.observe_at("stream_error", &json!({ "groupId": "a", "error": "Broken pipe" }), start + 5500);
+        history.observe_at("stream_reconnected", &json!({ "groupId": "a", "pid": 1, "attempt": 1 }), start + 5800);
+        history.observe_at("stream_stats", &stats("unknown", 1.0, 0), start + 9000);
+        history.record_stop();
+
+        let summaries = history.list();
+        assert_eq!(summaries.len(), 1);
+        let session = history.get(&summaries[0].id).unwrap();
+        assert!(session.ended_at.is_some());
+        assert_eq!(session.samples.len(), 3);
+        assert_eq!(session.samples[0].bitrate, 6002.0);
+        assert_eq!(summaries[0].errors, 1);
+        assert_eq!(summaries[0].reconnects, 1);
+        assert_eq!(summaries[0].dropped_frames, 5 + 3);
+
+        let csv_path = dir.path().join("session.csv");
+        history.export(&session.id, &csv_path).unwrap();
+        let csv = std::fs::read_to_string(csv_path).unwrap();
+        assert!(csv.starts_with("time,elapsed_secs,group_id"));
+        assert!(csv.contains(",0.0,a,Group a,30.00,6002.0,"));
+
+        assert!(history.get("../settings").is_err());
+    }
+
+    #[test]
+    fn test_session_ends_when_last_group_ends_by_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = SessionHistory::new(dir.path());
+        history.record_start("Main", &[group("a"), group("b")]);
+        let first = history.live_session().unwrap().id;
+
+        // Reconnecting, or another group still live, keeps the session open
+        let reconnecting = json!({ "groupId": "a", "error": "Broken pipe", "autoReconnect": true });
+        history.record_group_end("stream_error", &reconnecting, || false);
+        history.record_group_end("stream_ended", &json!("a"), || true);
+        assert_eq!(history.live_session().unwrap().id, first);
+
+        let rejected = json!({ "groupId": "b", "error": "Invalid stream key", "autoReconnect": false });
+        history.observe("stream_error", &rejected);
+        history.record_group_end("stream_error", &rejected, || false);
+        assert!(history.live_session().is_none());
+        assert!(history.get(&first).unwrap().ended_at.is_some());
+
+        // The next broadcast is a session of its own, as is another profile's
+        history.record_start("Main", &[group("a")]);
+        let second = history.live_session().unwrap().id;
+        assert_ne!(second, first);
+        history.record_start("Other", &[group("c")]);
+        assert_eq!(history.live_session().unwrap().profile_name, "Other");
+        assert!(history.get(&second).unwrap().ended_at.is_some());
+    }
+
+    #[test]
+    fn test_closes_interrupted_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = SessionHistory::new(dir.path());
+        history.record_start("Main", &[group("a")]);
+        let id = history.live_session().unwrap().id;
+        drop(history);
+
+        let reopened = SessionHistory::new(dir.path());
+        assert!(reopened.get(&id).unwrap().ended_at.is_some());
+    }
+}
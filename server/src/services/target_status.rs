@@ -0,0 +1,276 @@
+// Target Status Service
+// Tracks per-target connection state from each target's FFmpeg output
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use crate::models::{StreamTarget, TargetProtocol, TargetState, TargetStatus};
+use crate::services::{emit_event, EventSink};
+
+/// Number of stderr lines searched for protocol-level failure details
+const DETAIL_LOOKBACK_LINES: usize = 8;
+
+/// Status entry tagged with the FFmpeg process it belongs to
+struct TrackedTarget {
+    pid: u32,
+    status: TargetStatus,
+}
+
+/// Shared per-target status table, updated by the stats reader threads
+#[derive(Clone, Default)]
+pub struct TargetStatusTracker {
+    targets: Arc<Mutex<HashMap<String, TrackedTarget>>>,
+}
+
+impl TargetStatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the targets of a freshly spawned group process as connecting
+    ///
+    /// Replaces every entry of the group, so targets disabled since the last
+    /// start drop out of the table.
+    pub fn begin_process(
+        &self,
+        group_id: &str,
+        pid: u32,
+        targets: &[&StreamTarget],
+        event_sink: &dyn EventSink,
+    ) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut emitted = Vec::with_capacity(targets.len());
+        {
+            let mut table = self.lock();
+            table.retain(|_, tracked| tracked.status.group_id != group_id);
+            for target in targets {
+                let status = TargetStatus {
+                    group_id: group_id.to_string(),
+                    target_id: target.id.clone(),
+                    name: target.name.clone(),
+                    service: target.service.clone(),
+                    protocol: target.protocol,
+                    state: TargetState::Connecting,
+                    error: None,
+                    updated_at: now,
+                };
+                emitted.push(status.clone());
+                table.insert(target.id.clone(), TrackedTarget { pid, status });
+            }
+        }
+        Self::emit_all(event_sink, &emitted);
+    }
+
+    /// Register a target added to a running group process as connecting
+    pub fn add_target(&self, group_id: &str, pid: u32, target: &StreamTarget, event_sink: &dyn EventSink) {
+        let status = TargetStatus {
+            group_id: group_id.to_string(),
+            target_id: target.id.clone(),
+            name: target.name.clone(),
+            service: target.service.clone(),
+            protocol: target.protocol,
+            state: TargetState::Connecting,
+            error: None,
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        };
+        self.lock().insert(target.id.clone(), TrackedTarget { pid, status: status.clone() });
+        Self::emit_all(event_sink, &[status]);
+    }
+
+    /// Mark a connecting target live once its output is flowing
+    pub fn mark_live(&self, target_id: &str, pid: u32, event_sink: &dyn EventSink) {
+        self.transition(
+            |tracked| {
+                tracked.pid == pid
+                    && tracked.status.target_id == target_id
+                    && tracked.status.state == TargetState::Connecting
+            },
+            TargetState::Live,
+            None,
+            event_sink,
+        );
+    }
+
+    /// Mark a target whose output process failed
+    pub fn mark_failed(&self, target_id: &str, pid: u32, error: String, event_sink: &dyn EventSink) {
+        self.transition(
+            |tracked| tracked.pid == pid && tracked.status.target_id == target_id,
+            TargetState::Failed,
+            Some(error),
+            event_sink,
+        );
+    }
+
+    /// Mark every target of an exited process that had not already failed
+    pub fn fail_process(&self, pid: u32, error: &str, event_sink: &dyn EventSink) {
+        self.transition(
+            |tracked| tracked.pid == pid && tracked.status.state != TargetState::Failed,
+            TargetState::Failed,
+            Some(error.to_string()),
+            event_sink,
+        );
+    }
+
+    /// Mark a group's targets as waiting to reconnect, keeping their last error
+    pub fn mark_group_retrying(&self, group_id: &str, event_sink: &dyn EventSink) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut emitted = Vec::new();
+        for tracked in self.lock().values_mut() {
+            if tracked.status.group_id == group_id && tracked.status.state != TargetState::Retrying {
+                tracked.status.state = TargetState::Retrying;
+                tracked.status.updated_at = now;
+                emitted.push(tracked.status.clone());
+            }
+        }
+        Self::emit_all(event_sink, &emitted);
+    }
+
+    /// Mark one target as waiting to reconnect, keeping its last error
+    pub fn mark_target_retrying(&self, target_id: &str, event_sink: &dyn EventSink) {
+        self.transition(|tracked| tracked.status.target_id == target_id, TargetState::Retrying, None, event_sink);
+    }
+
+    /// Mark a target that will not be reconnected again
+    pub fn mark_target_gave_up(&self, target_id: &str, error: String, event_sink: &dyn EventSink) {
+        self.transition(|tracked| tracked.status.target_id == target_id, TargetState::Failed, Some(error), event_sink);
+    }
+
+    /// Drop the entry of a target removed from a running group
+    pub fn remove_target(&self, target_id: &str) {
+        self.lock().remove(target_id);
+    }
+
+    /// Drop the entries of a process that ended without error
+    pub fn clear_process(&self, pid: u32) {
+        self.lock().retain(|_, tracked| tracked.pid != pid);
+    }
+
+    /// Drop the entries of a stopped group
+    pub fn clear_group(&self, group_id: &str) {
+        self.lock().retain(|_, tracked| tracked.status.group_id != group_id);
+    }
+
+    /// Drop every entry (all groups stopped)
+    pub fn clear_all(&self) {
+        self.lock().clear();
+    }
+
+    /// Current statuses, optionally limited to one group, sorted by group and name
+    pub fn statuses(&self, group_id: Option<&str>) -> Vec<TargetStatus> {
+        let mut statuses: Vec<TargetStatus> = self
+            .lock()
+            .values()
+            .filter(|tracked| group_id.map_or(true, |id| tracked.status.group_id == id))
+            .map(|tracked| tracked.status.clone())
+            .collect();
+        statuses.sort_by(|a, b| a.group_id.cmp(&b.group_id).then_with(|| a.name.cmp(&b.name)));
+        statuses
+    }
+
+    fn transition<F>(&self, matches: F, state: TargetState, error: Option<String>, event_sink: &dyn EventSink)
+    where
+        F: Fn(&TrackedTarget) -> bool,
+    {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut emitted = Vec::new();
+        for tracked in self.lock().values_mut() {
+            if !matches(tracked) {
+                continue;
+            }
+            if tracked.status.state == state && (error.is_none() || tracked.status.error == error) {
+                continue;
+            }
+            tracked.status.state = state;
+            if error.is_some() || state == TargetState::Live {
+                tracked.status.error = error.clone();
+            }
+            tracked.status.updated_at = now;
+            emitted.push(tracked.status.clone());
+        }
+        Self::emit_all(event_sink, &emitted);
+    }
+
+    fn emit_all(event_sink: &dyn EventSink, statuses: &[TargetStatus]) {
+        for status in statuses {
+            log::info!(
+                "[FFmpeg:{}] Target '{}' is {:?}{}",
+                status.group_id,
+                status.name,
+                status.state,
+                status.error.as_deref().map(|e| format!(": {e}")).unwrap_or_default()
+            );
+            emit_event(event_sink, "target_status", status);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, TrackedTarget>> {
+        self.targets.lock().unwrap_or_else(|e| {
+            log::warn!("Target status mutex poisoned, recovering: {e}");
+            e.into_inner()
+        })
+    }
+}
+
+/// Build a failure reason, adding the protocol's own error line when one preceded it
+///
+/// FFmpeg's exit summary only carries a generic error string ("Input/output error");
+/// the protocol logs the useful part (e.g. `[rtmp @ 0x..] Server error: ...`) before it.
+pub fn describe_target_failure(
+    reason: &str,
+    protocol: TargetProtocol,
+    recent_lines: &VecDeque<String>,
+) -> String {
+    let contexts: &[&str] = match protocol {
+        TargetProtocol::Rtmp => &["rtmp", "tls", "tcp"],
+        TargetProtocol::Srt => &["srt"],
+        TargetProtocol::Rist => &["rist"],
+        TargetProtocol::Whip => &["whip", "http", "tls", "tcp"],
+        TargetProtocol::Record => &["segment", "file"],
+        TargetProtocol::Hls => &["hls", "dash", "file"],
+    };
+
+    let detail = recent_lines
+        .iter()
+        .rev()
+        .take(DETAIL_LOOKBACK_LINES)
+        .find_map(|line| {
+            let start = line.find('[')?;
+            let (context, message) = line[start + 1..].split_once(']')?;
+            let name = context.split(" @").next()?.trim();
+            contexts
+                .iter()
+                .any(|c| name.starts_with(c))
+                .then(|| message.trim().to_string())
+        })
+        .filter(|message| !message.is_empty());
+
+    match detail {
+        Some(detail) => format!("{detail} ({reason})"),
+        None => reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_target_failure_uses_protocol_line() {
+        let lines: VecDeque<String> = [
+            "[srt @ 0x1] Connection to peer timed out",
+            "[rtmp @ 0x2] Server error: Invalid stream key",
+            "[out#0/flv @ 0x3] Error muxing a packet",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        assert_eq!(
+            describe_target_failure("Input/output error", TargetProtocol::Rtmp, &lines),
+            "Server error: Invalid stream key (Input/output error)"
+        );
+        assert_eq!(
+            describe_target_failure("Input/output error", TargetProtocol::Rist, &lines),
+            "Input/output error"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// WHIP Stand-in Endpoint
+// Minimal local WHIP server for testing WHIP targets without a platform account
+
+use axum::{
+    extract::{OriginalUri, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, post},
+    Router,
+};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Shared state for the stand-in endpoint
+#[derive(Clone)]
+struct WhipStandinState {
+    /// Expected bearer token (None = accept unauthenticated offers)
+    token: Option<String>,
+    /// Active session resource IDs
+    sessions: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Build a router implementing the WHIP signaling flow (RFC 9725)
+///
+/// `POST /` accepts an SDP offer and answers with `201 Created`, an SDP answer
+/// and a `Location` header; `DELETE /resource/:id` ends the session. The answer
+/// advertises no usable ICE candidates, so FFmpeg can negotiate but will not
+/// establish media - use it for `test_whip_target` and argument checks.
+pub fn whip_standin_router(token: Option<String>) -> Router {
+    let state = WhipStandinState {
+        token: token.filter(|t| !t.is_empty()),
+        sessions: Arc::new(Mutex::new(HashSet::new())),
+    };
+
+    Router::new()
+        .route("/", post(create_session))
+        .route("/resource/:id", delete(delete_session))
+        .with_state(state)
+}
+
+fn is_authorized(state: &WhipStandinState, headers: &HeaderMap) -> bool {
+    let Some(expected) = &state.token else {
+        return true;
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) => {
+            use subtle::ConstantTimeEq;
+            provided.as_bytes().ct_eq(expected.as_bytes()).into()
+        }
+        None => false,
+    }
+}
+
+async fn create_session(
+    State(state): State<WhipStandinState>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let is_sdp = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/sdp"));
+    if !is_sdp {
+        return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+    }
+
+    let Some(answer) = build_answer(&body) else {
+        return (StatusCode::BAD_REQUEST, "Invalid SDP offer").into_response();
+    };
+
+    let id = uuid::Uuid::new_v4().to_string();
+    if let Ok(mut sessions) = state.sessions.lock() {
+        sessions.insert(id.clone());
+    }
+    log::debug!("WHIP stand-in created session {id}");
+
+    let location = format!("{}/resource/{id}", uri.path().trim_end_matches('/'));
+    (
+        StatusCode::CREATED,
+        [
+            (header::CONTENT_TYPE, "application/sdp".to_string()),
+            (header::LOCATION, location),
+        ],
+        answer,
+    )
+        .into_response()
+}
+
+async fn delete_session(
+    State(state): State<WhipStandinState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> StatusCode {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let removed = state
+        .sessions
+        .lock()
+        .map(|mut sessions| sessions.remove(&id))
+        .unwrap_or(false);
+
+    if removed {
+        log::debug!("WHIP stand-in deleted session {id}");
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// Answer every media section of the offer as recvonly, keeping its first payload type
+fn build_answer(offer: &str) -> Option<String> {
+    if !offer.trim_start().starts_with("v=0") {
+        return None;
+    }
+
+    let mut answer = String::from(
+        "v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\na=ice-lite\r\n",
+    );
+    let mut mids = Vec::new();
+    let mut sections = offer.split("\nm=").skip(1).peekable();
+    sections.peek()?;
+
+    for section in sections {
+        let lines: Vec<&str> = section.lines().map(str::trim_end).collect();
+        let mut media_line = lines.first()?.split_whitespace();
+        let kind = media_line.next()?;
+        let _port = media_line.next()?;
+        let proto = media_line.next()?;
+        let payload = media_line.next()?;
+
+        let mid = lines
+            .iter()
+            .find_map(|line| line.strip_prefix("a=mid:"))
+            .unwrap_or(kind);
+        mids.push(mid.to_string());
+
+        answer.push_str(&format!("m={kind} 9 {proto} {payload}\r\n"));
+        answer.push_str("c=IN IP4 0.0.0.0\r\n");
+        answer.push_str("a=ice-ufrag:standin\r\na=ice-pwd:standinstandinstandin00\r\n");
+        answer.push_str(&format!("a=fingerprint:sha-256 {}\r\n", ["00"; 32].join(":")));
+        answer.push_str(&format!("a=setup:passive\r\na=mid:{mid}\r\na=recvonly\r\na=rtcp-mux\r\n"));
+        for line in &lines {
+            let is_payload_attr = line
+                .strip_prefix("a=rtpmap:")
+                .or_else(|| line.strip_prefix("a=fmtp:"))
+                .is_some_and(|rest| rest.split_whitespace().next() == Some(payload));
+            if is_payload_attr {
+                answer.push_str(line);
+                answer.push_str("\r\n");
+            }
+        }
+    }
+
+    answer.insert_str(
+        answer.find("a=ice-lite")?,
+        &format!("a=group:BUNDLE {}\r\n", mids.join(" ")),
+    );
+    Some(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_whip_target;
+
+    async fn serve(token: Option<&str>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().nest("/whip", whip_standin_router(token.map(str::to_string)));
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}/whip")
+    }
+
+    #[tokio::test]
+    async fn test_whip_target_against_standin() {
+        let url = serve(Some("secret-token")).await;
+
+        let result = test_whip_target(url.clone(), "secret-token".to_string()).await.unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let result = test_whip_target(url, "wrong-token".to_string()).await.unwrap();
+        assert!(!result.success);
+        assert!(result.message.contains("bearer token"));
+    }
+
+    #[test]
+    fn test_answer_mirrors_offer_sections() {
+        let offer = "v=0\r\ns=-\r\nm=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=mid:0\r\na=rtpmap:111 opus/48000/2\r\n\
+                     m=video 9 UDP/TLS/RTP/SAVPF 96\r\na=mid:1\r\na=rtpmap:96 H264/90000\r\n";
+        let answer = build_answer(offer).unwrap();
+        assert!(answer.contains("a=group:BUNDLE 0 1"));
+        assert!(answer.contains("m=audio 9 UDP/TLS/RTP/SAVPF 111"));
+        assert!(answer.contains("a=rtpmap:96 H264/90000"));
+        assert!(build_answer("not sdp").is_none());
+    }
+}